@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use domain::api_token::{ApiToken, TokenScope};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NewApiTokenDto {
+    pub name: String,
+    pub scope: TokenScope,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A new API token together with the token itself, which cannot be looked up later.
+#[derive(Debug, Clone)]
+pub struct CreatedApiTokenDto {
+    pub api_token: ApiToken,
+    pub token: String,
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+
+/// API tokens that are rejected before they are created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiTokenError {
+    BlankName,
+    NameTooLong { max: usize },
+    ExpiryInPast,
+}
+
+impl ApiTokenError {
+    /// Stable kebab-case identifier of the variant for clients that branch on the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiTokenError::BlankName => "blank-api-token-name",
+            ApiTokenError::NameTooLong { .. } => "api-token-name-too-long",
+            ApiTokenError::ExpiryInPast => "api-token-expiry-in-past",
+        }
+    }
+}
+
+impl fmt::Display for ApiTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiTokenError::BlankName => write!(f, "the token name must not be blank"),
+            ApiTokenError::NameTooLong { max } => write!(f, "the token name must not be longer than {max} characters"),
+            ApiTokenError::ExpiryInPast => write!(f, "the token must expire in the future"),
+        }
+    }
+}
+
+impl std::error::Error for ApiTokenError {}
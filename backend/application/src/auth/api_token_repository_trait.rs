@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::api_token::ApiToken;
+
+#[async_trait]
+pub trait ApiTokenRepositoryTrait: Send + Sync {
+    async fn save(&self, api_token: ApiToken) -> anyhow::Result<ApiToken>;
+    /// The user's tokens, newest first.
+    async fn find_by_user(&self, user_id: i64) -> anyhow::Result<Vec<ApiToken>>;
+    async fn find_by_hash(&self, token_hash: &str) -> anyhow::Result<Option<ApiToken>>;
+    /// Deletes the token if it belongs to the user, and tells whether it did.
+    async fn delete(&self, user_id: i64, id: i64) -> anyhow::Result<bool>;
+    /// Sets `last_used_at`, unless it was set less than a minute ago, so that busy tokens do not
+    /// cost a write per request.
+    async fn record_use(&self, id: i64, now: DateTime<Utc>) -> anyhow::Result<()>;
+}
@@ -0,0 +1,92 @@
+use crate::auth::api_token_dto::{CreatedApiTokenDto, NewApiTokenDto};
+use crate::auth::api_token_error::ApiTokenError;
+use crate::auth::api_token_repository_trait::ApiTokenRepositoryTrait;
+use crate::auth::api_token_service_trait::ApiTokenServiceTrait;
+use crate::auth::principal::Principal;
+use crate::auth::token_issuer_trait::TokenIssuerTrait;
+use crate::auth::user_repository_trait::UserRepositoryTrait;
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::api_token::{API_TOKEN_PREFIX, ApiToken};
+use std::sync::Arc;
+
+/// Upper bound for token names, counted in characters.
+const MAX_NAME_LENGTH: usize = 100;
+
+#[derive(Clone)]
+pub struct ApiTokenService {
+    api_token_repository: Arc<dyn ApiTokenRepositoryTrait>,
+    user_repository: Arc<dyn UserRepositoryTrait>,
+    token_issuer: Arc<dyn TokenIssuerTrait>,
+}
+
+impl ApiTokenService {
+    pub fn new(
+        api_token_repository: Arc<dyn ApiTokenRepositoryTrait>,
+        user_repository: Arc<dyn UserRepositoryTrait>,
+        token_issuer: Arc<dyn TokenIssuerTrait>,
+    ) -> Self {
+        Self { api_token_repository, user_repository, token_issuer }
+    }
+}
+
+#[async_trait]
+impl ApiTokenServiceTrait for ApiTokenService {
+    async fn create_token(&self, user_id: i64, api_token: NewApiTokenDto) -> anyhow::Result<CreatedApiTokenDto> {
+        let name = api_token.name.trim();
+        if name.is_empty() {
+            return Err(ApiTokenError::BlankName.into());
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(ApiTokenError::NameTooLong { max: MAX_NAME_LENGTH }.into());
+        }
+        if api_token.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(ApiTokenError::ExpiryInPast.into());
+        }
+        let token = format!("{API_TOKEN_PREFIX}{}", self.token_issuer.new_opaque_token());
+        let api_token = self
+            .api_token_repository
+            .save(ApiToken {
+                user_id,
+                name: name.to_string(),
+                token_hash: self.token_issuer.hash_opaque_token(&token),
+                scope: api_token.scope,
+                expires_at: api_token.expires_at,
+                ..Default::default()
+            })
+            .await?;
+        Ok(CreatedApiTokenDto { api_token, token })
+    }
+
+    async fn find_tokens(&self, user_id: i64) -> anyhow::Result<Vec<ApiToken>> {
+        self.api_token_repository.find_by_user(user_id).await
+    }
+
+    async fn revoke_token(&self, user_id: i64, id: i64) -> anyhow::Result<bool> {
+        self.api_token_repository.delete(user_id, id).await
+    }
+
+    async fn authenticate(&self, token: &str) -> anyhow::Result<Option<Principal>> {
+        if !token.starts_with(API_TOKEN_PREFIX) {
+            return Ok(None);
+        }
+        let now = Utc::now();
+        let token_hash = self.token_issuer.hash_opaque_token(token);
+        let Some(api_token) = self.api_token_repository.find_by_hash(&token_hash).await? else {
+            return Ok(None);
+        };
+        if !api_token.is_active(now) {
+            return Ok(None);
+        }
+        let Some(user) = self.user_repository.find_by_id(api_token.user_id).await? else {
+            return Ok(None);
+        };
+        self.api_token_repository.record_use(api_token.id, now).await?;
+        Ok(Some(Principal {
+            user_id: user.id,
+            email: user.email,
+            display_name: user.display_name,
+            scope: api_token.scope,
+        }))
+    }
+}
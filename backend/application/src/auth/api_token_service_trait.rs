@@ -0,0 +1,17 @@
+use crate::auth::api_token_dto::{CreatedApiTokenDto, NewApiTokenDto};
+use crate::auth::principal::Principal;
+use anyhow::Result;
+use async_trait::async_trait;
+use domain::api_token::ApiToken;
+
+/// Personal API tokens of the signed-in user.
+#[async_trait]
+pub trait ApiTokenServiceTrait: Send + Sync {
+    /// Fails with an `ApiTokenError` for invalid names and expiries.
+    async fn create_token(&self, user_id: i64, api_token: NewApiTokenDto) -> Result<CreatedApiTokenDto>;
+    async fn find_tokens(&self, user_id: i64) -> Result<Vec<ApiToken>>;
+    /// Revokes one of the user's tokens, and tells whether there was such a token.
+    async fn revoke_token(&self, user_id: i64, id: i64) -> Result<bool>;
+    /// The principal of a valid API token, limited to the token's scope.
+    async fn authenticate(&self, token: &str) -> Result<Option<Principal>>;
+}
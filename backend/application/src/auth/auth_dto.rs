@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use domain::user::User;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RegisterDto {
+    pub email: String,
+    pub password: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LoginDto {
+    pub email: String,
+    pub password: String,
+}
+
+/// An account at an OpenID Connect provider, as vouched for by a validated ID token.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExternalIdentityDto {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    /// Whether the provider checked that the email address belongs to the account.
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+/// Credentials handed out at sign-in and on every refresh.
+#[derive(Debug, Clone)]
+pub struct AuthSessionDto {
+    pub user: User,
+    pub access_token: String,
+    pub access_token_expires_at: DateTime<Utc>,
+    /// Can be exchanged once for new credentials, until it expires.
+    pub refresh_token: String,
+    pub refresh_token_expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    InvalidEmail(String),
+    BlankDisplayName,
+    DisplayNameTooLong { max: usize },
+    WeakPassword { min: usize },
+    EmailTaken,
+    /// Unknown email or wrong password, deliberately not telling which.
+    InvalidCredentials,
+    /// Unknown, expired, already used or revoked.
+    InvalidRefreshToken,
+    /// The identity provider shared no usable email address for a new user.
+    MissingEmail,
+    /// An account with the email address exists, but the identity provider has not verified it.
+    UnverifiedEmail,
+}
+
+impl AuthError {
+    /// Stable kebab-case identifier of the variant for clients that branch on the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AuthError::InvalidEmail(_) => "invalid-email",
+            AuthError::BlankDisplayName => "blank-display-name",
+            AuthError::DisplayNameTooLong { .. } => "display-name-too-long",
+            AuthError::WeakPassword { .. } => "weak-password",
+            AuthError::EmailTaken => "email-taken",
+            AuthError::InvalidCredentials => "invalid-credentials",
+            AuthError::InvalidRefreshToken => "invalid-refresh-token",
+            AuthError::MissingEmail => "missing-email",
+            AuthError::UnverifiedEmail => "unverified-email",
+        }
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::InvalidEmail(email) => write!(f, "{email:?} is not an email address"),
+            AuthError::BlankDisplayName => write!(f, "the display name must not be blank"),
+            AuthError::DisplayNameTooLong { max } => {
+                write!(f, "the display name must not be longer than {max} characters")
+            }
+            AuthError::WeakPassword { min } => write!(f, "the password must be at least {min} characters long"),
+            AuthError::EmailTaken => write!(f, "a user with this email address already exists"),
+            AuthError::InvalidCredentials => write!(f, "the email address or password is wrong"),
+            AuthError::InvalidRefreshToken => write!(f, "the refresh token is invalid or has expired"),
+            AuthError::MissingEmail => write!(f, "the identity provider did not share an email address"),
+            AuthError::UnverifiedEmail => {
+                write!(f, "the identity provider has not verified the email address of an existing account")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
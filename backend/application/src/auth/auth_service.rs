@@ -0,0 +1,181 @@
+use crate::auth::auth_dto::{AuthSessionDto, ExternalIdentityDto, LoginDto, RegisterDto};
+use crate::auth::auth_error::AuthError;
+use crate::auth::auth_service_trait::AuthServiceTrait;
+use crate::auth::password_hasher_trait::PasswordHasherTrait;
+use crate::auth::principal::Principal;
+use crate::auth::token_issuer_trait::TokenIssuerTrait;
+use crate::auth::user_repository_trait::UserRepositoryTrait;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use domain::user::{MIN_PASSWORD_LENGTH, RefreshToken, User, UserIdentity, normalize_email};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Upper bound for display names, counted in characters.
+const MAX_DISPLAY_NAME_LENGTH: usize = 100;
+
+/// How long credentials stay valid. Access tokens cannot be revoked, so they are kept short.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionPolicy {
+    pub access_token_lifetime: Duration,
+    /// Counted from the last refresh, so that sessions in use do not run out.
+    pub refresh_token_lifetime: Duration,
+}
+
+impl Default for SessionPolicy {
+    fn default() -> Self {
+        SessionPolicy {
+            access_token_lifetime: Duration::from_secs(15 * 60),
+            refresh_token_lifetime: Duration::from_secs(30 * 24 * 60 * 60),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthService {
+    user_repository: Arc<dyn UserRepositoryTrait>,
+    password_hasher: Arc<dyn PasswordHasherTrait>,
+    token_issuer: Arc<dyn TokenIssuerTrait>,
+    session_policy: SessionPolicy,
+}
+
+impl AuthService {
+    pub fn new(
+        user_repository: Arc<dyn UserRepositoryTrait>,
+        password_hasher: Arc<dyn PasswordHasherTrait>,
+        token_issuer: Arc<dyn TokenIssuerTrait>,
+        session_policy: SessionPolicy,
+    ) -> Self {
+        Self { user_repository, password_hasher, token_issuer, session_policy }
+    }
+
+    /// Issues credentials for the user, continuing the given refresh token family or starting a new one.
+    async fn start_session(&self, user: User, family: Option<String>, now: DateTime<Utc>) -> anyhow::Result<AuthSessionDto> {
+        let access_token_expires_at = now + TimeDelta::from_std(self.session_policy.access_token_lifetime)?;
+        let refresh_token_expires_at = now + TimeDelta::from_std(self.session_policy.refresh_token_lifetime)?;
+        let access_token = self.token_issuer.issue_access_token(&user, access_token_expires_at)?;
+        let refresh_token = self.token_issuer.new_opaque_token();
+        let token_hash = self.token_issuer.hash_opaque_token(&refresh_token);
+        self.user_repository
+            .save_refresh_token(RefreshToken {
+                user_id: user.id,
+                family: family.unwrap_or_else(|| token_hash.clone()),
+                token_hash,
+                expires_at: refresh_token_expires_at,
+                ..Default::default()
+            })
+            .await?;
+        Ok(AuthSessionDto { user, access_token, access_token_expires_at, refresh_token, refresh_token_expires_at })
+    }
+}
+
+fn validate_display_name(display_name: &str) -> Result<String, AuthError> {
+    let display_name = display_name.trim();
+    if display_name.is_empty() {
+        return Err(AuthError::BlankDisplayName);
+    }
+    if display_name.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+        return Err(AuthError::DisplayNameTooLong { max: MAX_DISPLAY_NAME_LENGTH });
+    }
+    Ok(display_name.to_string())
+}
+
+#[async_trait]
+impl AuthServiceTrait for AuthService {
+    async fn register(&self, registration: RegisterDto) -> anyhow::Result<AuthSessionDto> {
+        let email = normalize_email(&registration.email).ok_or(AuthError::InvalidEmail(registration.email))?;
+        let display_name = validate_display_name(&registration.display_name)?;
+        if registration.password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(AuthError::WeakPassword { min: MIN_PASSWORD_LENGTH }.into());
+        }
+        if self.user_repository.find_by_email(&email).await?.is_some() {
+            return Err(AuthError::EmailTaken.into());
+        }
+        let password_hash = self.password_hasher.hash_password(&registration.password).await?;
+        let user = self
+            .user_repository
+            .save(User { email, display_name, password_hash: Some(password_hash), ..Default::default() })
+            .await?;
+        self.start_session(user, None, Utc::now()).await
+    }
+
+    async fn login(&self, login: LoginDto) -> anyhow::Result<AuthSessionDto> {
+        let user = match normalize_email(&login.email) {
+            Some(email) => self.user_repository.find_by_email(&email).await?,
+            None => None,
+        };
+        let account = user.and_then(|user| Some((user.password_hash.clone()?, user)));
+        let Some((password_hash, user)) = account else {
+            // hashing anyway takes as long as a wrong password, so timing does not reveal who has an account
+            self.password_hasher.hash_password(&login.password).await?;
+            return Err(AuthError::InvalidCredentials.into());
+        };
+        if !self.password_hasher.verify_password(&login.password, &password_hash).await {
+            return Err(AuthError::InvalidCredentials.into());
+        }
+        self.start_session(user, None, Utc::now()).await
+    }
+
+    async fn sign_in_with_identity(&self, identity: ExternalIdentityDto) -> anyhow::Result<AuthSessionDto> {
+        if let Some(user) = self.user_repository.find_by_identity(&identity.issuer, &identity.subject).await? {
+            return self.start_session(user, None, Utc::now()).await;
+        }
+        let email = identity.email.as_deref().and_then(normalize_email).ok_or(AuthError::MissingEmail)?;
+        let user = match self.user_repository.find_by_email(&email).await? {
+            // linking by an address nobody checked would hand the account to whoever claims it
+            Some(_) if !identity.email_verified => return Err(AuthError::UnverifiedEmail.into()),
+            Some(user) => user,
+            None => {
+                let display_name = identity
+                    .name
+                    .as_deref()
+                    .and_then(|name| validate_display_name(name).ok())
+                    .unwrap_or_else(|| email.split('@').next().unwrap_or_default().to_string());
+                self.user_repository
+                    .save(User { email, display_name, password_hash: None, ..Default::default() })
+                    .await?
+            }
+        };
+        self.user_repository
+            .save_identity(UserIdentity {
+                user_id: user.id,
+                issuer: identity.issuer,
+                subject: identity.subject,
+                ..Default::default()
+            })
+            .await?;
+        self.start_session(user, None, Utc::now()).await
+    }
+
+    async fn refresh(&self, refresh_token: &str) -> anyhow::Result<AuthSessionDto> {
+        let now = Utc::now();
+        let token_hash = self.token_issuer.hash_opaque_token(refresh_token);
+        let Some(token) = self.user_repository.find_refresh_token(&token_hash).await? else {
+            return Err(AuthError::InvalidRefreshToken.into());
+        };
+        // losing the race against a concurrent refresh counts as reuse too
+        if token.revoked_at.is_some() || !self.user_repository.revoke_refresh_token(token.id, now).await? {
+            self.user_repository.revoke_refresh_token_family(&token.family, now).await?;
+            return Err(AuthError::InvalidRefreshToken.into());
+        }
+        if !token.is_active(now) {
+            return Err(AuthError::InvalidRefreshToken.into());
+        }
+        let Some(user) = self.user_repository.find_by_id(token.user_id).await? else {
+            return Err(AuthError::InvalidRefreshToken.into());
+        };
+        self.start_session(user, Some(token.family), now).await
+    }
+
+    async fn logout(&self, refresh_token: &str) -> anyhow::Result<()> {
+        let token_hash = self.token_issuer.hash_opaque_token(refresh_token);
+        if let Some(token) = self.user_repository.find_refresh_token(&token_hash).await? {
+            self.user_repository.revoke_refresh_token_family(&token.family, Utc::now()).await?;
+        }
+        Ok(())
+    }
+
+    fn authenticate(&self, access_token: &str) -> Option<Principal> {
+        self.token_issuer.verify_access_token(access_token)
+    }
+}
@@ -0,0 +1,22 @@
+use crate::auth::auth_dto::{AuthSessionDto, ExternalIdentityDto, LoginDto, RegisterDto};
+use crate::auth::principal::Principal;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Registration, sign-in and the sessions that follow. Rejections fail with an `AuthError`.
+#[async_trait]
+pub trait AuthServiceTrait: Send + Sync {
+    /// Creates the user and signs them in.
+    async fn register(&self, registration: RegisterDto) -> Result<AuthSessionDto>;
+    async fn login(&self, login: LoginDto) -> Result<AuthSessionDto>;
+    /// Signs in the user linked to the provider account. A first sign-in links the account to the
+    /// user with its verified email address, or provisions a new user.
+    async fn sign_in_with_identity(&self, identity: ExternalIdentityDto) -> Result<AuthSessionDto>;
+    /// Exchanges a refresh token for new credentials. A token that was already exchanged signs out
+    /// its whole family, as it must have been stolen.
+    async fn refresh(&self, refresh_token: &str) -> Result<AuthSessionDto>;
+    /// Revokes the refresh token's family; access tokens stay valid until they expire.
+    async fn logout(&self, refresh_token: &str) -> Result<()>;
+    /// The principal of a valid access token.
+    fn authenticate(&self, access_token: &str) -> Option<Principal>;
+}
@@ -0,0 +1,13 @@
+pub mod auth_service;
+pub mod auth_service_trait;
+pub mod user_repository_trait;
+pub mod password_hasher_trait;
+pub mod token_issuer_trait;
+pub mod principal;
+pub mod auth_dto;
+pub mod auth_error;
+pub mod api_token_service;
+pub mod api_token_service_trait;
+pub mod api_token_repository_trait;
+pub mod api_token_dto;
+pub mod api_token_error;
\ No newline at end of file
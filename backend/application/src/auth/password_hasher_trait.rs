@@ -0,0 +1,9 @@
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait PasswordHasherTrait: Send + Sync {
+    /// Hashes the password with a fresh salt.
+    async fn hash_password(&self, password: &str) -> anyhow::Result<String>;
+    /// Whether the password matches the hash; malformed hashes match nothing.
+    async fn verify_password(&self, password: &str, password_hash: &str) -> bool;
+}
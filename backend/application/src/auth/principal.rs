@@ -0,0 +1,11 @@
+use domain::api_token::TokenScope;
+
+/// The signed-in user a request is made on behalf of, as vouched for by its access or API token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub user_id: i64,
+    pub email: String,
+    pub display_name: String,
+    /// Sessions may do anything, API tokens what they were created for.
+    pub scope: TokenScope,
+}
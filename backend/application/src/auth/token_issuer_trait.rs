@@ -0,0 +1,14 @@
+use crate::auth::principal::Principal;
+use chrono::{DateTime, Utc};
+use domain::user::User;
+
+pub trait TokenIssuerTrait: Send + Sync {
+    /// Signs an access token for the user that is valid until `expires_at`.
+    fn issue_access_token(&self, user: &User, expires_at: DateTime<Utc>) -> anyhow::Result<String>;
+    /// The principal of a correctly signed access token that has not expired yet.
+    fn verify_access_token(&self, access_token: &str) -> Option<Principal>;
+    /// A new unguessable token, as used for refresh and API tokens.
+    fn new_opaque_token(&self) -> String;
+    /// The hash opaque tokens are stored and looked up by.
+    fn hash_opaque_token(&self, token: &str) -> String;
+}
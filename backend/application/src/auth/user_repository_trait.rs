@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::user::{RefreshToken, User, UserIdentity};
+
+#[async_trait]
+pub trait UserRepositoryTrait: Send + Sync {
+    async fn save(&self, user: User) -> anyhow::Result<User>;
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<User>>;
+    /// Looks up a normalized email address.
+    async fn find_by_email(&self, email: &str) -> anyhow::Result<Option<User>>;
+    /// The user linked to the provider account.
+    async fn find_by_identity(&self, issuer: &str, subject: &str) -> anyhow::Result<Option<User>>;
+    async fn save_identity(&self, identity: UserIdentity) -> anyhow::Result<UserIdentity>;
+    async fn save_refresh_token(&self, refresh_token: RefreshToken) -> anyhow::Result<RefreshToken>;
+    async fn find_refresh_token(&self, token_hash: &str) -> anyhow::Result<Option<RefreshToken>>;
+    /// Revokes the token unless it already is, and tells whether it was this call that revoked it.
+    async fn revoke_refresh_token(&self, id: i64, now: DateTime<Utc>) -> anyhow::Result<bool>;
+    /// Revokes every token of the family that is not revoked yet.
+    async fn revoke_refresh_token_family(&self, family: &str, now: DateTime<Utc>) -> anyhow::Result<()>;
+}
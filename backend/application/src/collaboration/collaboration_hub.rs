@@ -0,0 +1,442 @@
+use crate::collaboration::collaboration_message_dto::{
+    ClientId, ClientMessageDto, LockDto, PresenceDto, ServerMessageDto, SubscriptionFilterDto,
+};
+use crate::task::task_event_dto::TaskEventDto;
+use domain::task::Task;
+use std::collections::{BTreeMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Messages to deliver, in order, each addressed to one client.
+pub type Outbox = Vec<(ClientId, ServerMessageDto)>;
+
+struct Client {
+    user: String,
+    /// Workspaces the user was a member of when connecting, whose task events the client receives.
+    workspace_ids: HashSet<i64>,
+    task_ids: HashSet<i64>,
+    filter: Option<SubscriptionFilterDto>,
+    focus: Option<i64>,
+}
+
+struct TaskLock {
+    client_id: ClientId,
+    expires_at: Instant,
+}
+
+/// Presence and soft locks of the connected clients. It does no I/O and reads no clock:
+/// every call is given the current time and returns the messages the transport has to send.
+pub struct CollaborationHub {
+    lock_timeout: Duration,
+    next_client_id: ClientId,
+    clients: BTreeMap<ClientId, Client>,
+    locks: BTreeMap<i64, TaskLock>,
+}
+
+impl CollaborationHub {
+    pub fn new(lock_timeout: Duration) -> Self {
+        Self { lock_timeout, next_client_id: 1, clients: BTreeMap::new(), locks: BTreeMap::new() }
+    }
+
+    pub fn connect(&mut self, user: String, workspace_ids: HashSet<i64>) -> (ClientId, Outbox) {
+        let client_id = self.next_client_id;
+        self.next_client_id += 1;
+        let client = Client { user, workspace_ids, task_ids: HashSet::new(), filter: None, focus: None };
+        self.clients.insert(client_id, client);
+        let welcome = ServerMessageDto::Welcome { client_id, lock_timeout: self.lock_timeout };
+        (client_id, vec![(client_id, welcome)])
+    }
+
+    /// Releases the client's locks and clears its presence.
+    pub fn disconnect(&mut self, client_id: ClientId) -> Outbox {
+        let mut outbox = Outbox::new();
+        let held: Vec<i64> = self
+            .locks
+            .iter()
+            .filter(|(_, lock)| lock.client_id == client_id)
+            .map(|(task_id, _)| *task_id)
+            .collect();
+        for task_id in held {
+            self.locks.remove(&task_id);
+            self.notify_subscribers(&mut outbox, task_id, client_id, ServerMessageDto::Unlocked { task_id });
+        }
+        if let Some(client) = self.clients.remove(&client_id)
+            && let Some(task_id) = client.focus
+        {
+            let presence = PresenceDto { client_id, user: client.user, task_id: None };
+            self.notify_subscribers(&mut outbox, task_id, client_id, ServerMessageDto::Presence(presence));
+        }
+        outbox
+    }
+
+    pub fn handle(&mut self, client_id: ClientId, message: ClientMessageDto, now: Instant) -> Outbox {
+        if !self.clients.contains_key(&client_id) {
+            return Outbox::new();
+        }
+        match message {
+            ClientMessageDto::Subscribe { task_ids, filter } => self.subscribe(client_id, task_ids, filter, now),
+            ClientMessageDto::Focus(task_id) => self.focus(client_id, task_id),
+            ClientMessageDto::Lock(task_id) => self.lock(client_id, task_id, now),
+            ClientMessageDto::Unlock(task_id) => self.unlock(client_id, task_id),
+        }
+    }
+
+    /// Releases locks that were not renewed in time, telling their holders as well.
+    pub fn expire_locks(&mut self, now: Instant) -> Outbox {
+        let mut outbox = Outbox::new();
+        let expired: Vec<(i64, ClientId)> = self
+            .locks
+            .iter()
+            .filter(|(_, lock)| lock.expires_at <= now)
+            .map(|(task_id, lock)| (*task_id, lock.client_id))
+            .collect();
+        for (task_id, holder_id) in expired {
+            self.locks.remove(&task_id);
+            outbox.push((holder_id, ServerMessageDto::Unlocked { task_id }));
+            self.notify_subscribers(&mut outbox, task_id, holder_id, ServerMessageDto::Unlocked { task_id });
+        }
+        outbox
+    }
+
+    /// Forwards a committed change to the clients subscribed to the task or matching it, as long as
+    /// they are members of its workspace.
+    pub fn task_event(&self, event: &TaskEventDto) -> Outbox {
+        match event {
+            TaskEventDto::Created(task) | TaskEventDto::Updated(task) => self
+                .members_of(task.workspace_id)
+                .filter(|(_, client)| {
+                    client.task_ids.contains(&task.id)
+                        || client.filter.as_ref().is_some_and(|filter| matches_filter(task, filter))
+                })
+                .map(|(client_id, _)| (*client_id, ServerMessageDto::TaskChanged(Box::new(task.clone()))))
+                .collect(),
+            // a deleted task can no longer be matched, so every filter hears about it
+            TaskEventDto::Deleted { id, workspace_id } => self
+                .members_of(*workspace_id)
+                .filter(|(_, client)| client.task_ids.contains(id) || client.filter.is_some())
+                .map(|(client_id, _)| {
+                    (*client_id, ServerMessageDto::TaskDeleted { id: *id, workspace_id: *workspace_id })
+                })
+                .collect(),
+        }
+    }
+
+    fn members_of(&self, workspace_id: i64) -> impl Iterator<Item = (&ClientId, &Client)> {
+        self.clients.iter().filter(move |(_, client)| client.workspace_ids.contains(&workspace_id))
+    }
+
+    fn subscribe(
+        &mut self,
+        client_id: ClientId,
+        task_ids: Vec<i64>,
+        filter: Option<SubscriptionFilterDto>,
+        now: Instant,
+    ) -> Outbox {
+        let task_ids: HashSet<i64> = task_ids.into_iter().collect();
+        let presence = self
+            .clients
+            .iter()
+            .filter(|(other_id, other)| {
+                **other_id != client_id && other.focus.is_some_and(|task_id| task_ids.contains(&task_id))
+            })
+            .map(|(other_id, other)| PresenceDto { client_id: *other_id, user: other.user.clone(), task_id: other.focus })
+            .collect();
+        let locks = self
+            .locks
+            .iter()
+            .filter(|(task_id, lock)| task_ids.contains(task_id) && lock.expires_at > now)
+            .map(|(task_id, lock)| self.lock_dto(*task_id, lock.client_id))
+            .collect();
+        if let Some(client) = self.clients.get_mut(&client_id) {
+            client.task_ids = task_ids;
+            client.filter = filter;
+        }
+        vec![(client_id, ServerMessageDto::Snapshot { presence, locks })]
+    }
+
+    fn focus(&mut self, client_id: ClientId, task_id: Option<i64>) -> Outbox {
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return Outbox::new();
+        };
+        let previous = std::mem::replace(&mut client.focus, task_id);
+        if previous == task_id {
+            return Outbox::new();
+        }
+        let presence = ServerMessageDto::Presence(PresenceDto { client_id, user: client.user.clone(), task_id });
+        // subscribers of the previous task learn that the user moved on
+        let recipients: Vec<ClientId> = self
+            .clients
+            .iter()
+            .filter(|(other_id, other)| {
+                **other_id != client_id
+                    && [previous, task_id].iter().flatten().any(|task_id| other.task_ids.contains(task_id))
+            })
+            .map(|(other_id, _)| *other_id)
+            .collect();
+        recipients.into_iter().map(|other_id| (other_id, presence.clone())).collect()
+    }
+
+    fn lock(&mut self, client_id: ClientId, task_id: i64, now: Instant) -> Outbox {
+        let expires_at = now + self.lock_timeout;
+        match self.locks.get_mut(&task_id) {
+            Some(lock) if lock.client_id == client_id => {
+                // renewing is silent
+                lock.expires_at = expires_at;
+                Outbox::new()
+            }
+            Some(lock) if lock.expires_at > now => {
+                let holder_id = lock.client_id;
+                vec![(client_id, ServerMessageDto::LockDenied(self.lock_dto(task_id, holder_id)))]
+            }
+            _ => {
+                let mut outbox = Outbox::new();
+                if let Some(expired) = self.locks.insert(task_id, TaskLock { client_id, expires_at }) {
+                    outbox.push((expired.client_id, ServerMessageDto::Unlocked { task_id }));
+                }
+                let locked = ServerMessageDto::Locked(self.lock_dto(task_id, client_id));
+                outbox.push((client_id, locked.clone()));
+                self.notify_subscribers(&mut outbox, task_id, client_id, locked);
+                outbox
+            }
+        }
+    }
+
+    fn unlock(&mut self, client_id: ClientId, task_id: i64) -> Outbox {
+        if self.locks.get(&task_id).is_none_or(|lock| lock.client_id != client_id) {
+            return Outbox::new();
+        }
+        self.locks.remove(&task_id);
+        let mut outbox = vec![(client_id, ServerMessageDto::Unlocked { task_id })];
+        self.notify_subscribers(&mut outbox, task_id, client_id, ServerMessageDto::Unlocked { task_id });
+        outbox
+    }
+
+    fn lock_dto(&self, task_id: i64, client_id: ClientId) -> LockDto {
+        let user = self.clients.get(&client_id).map(|client| client.user.clone()).unwrap_or_default();
+        LockDto { task_id, client_id, user }
+    }
+
+    /// Queues the message for every client subscribed to the task, except `sender_id`.
+    fn notify_subscribers(&self, outbox: &mut Outbox, task_id: i64, sender_id: ClientId, message: ServerMessageDto) {
+        for (client_id, client) in &self.clients {
+            if *client_id != sender_id && client.task_ids.contains(&task_id) {
+                outbox.push((*client_id, message.clone()));
+            }
+        }
+    }
+}
+
+fn matches_filter(task: &Task, filter: &SubscriptionFilterDto) -> bool {
+    filter.project_id.is_none_or(|project_id| task.project_id == Some(project_id))
+        && filter.tag_id.is_none_or(|tag_id| task.tags.iter().any(|tag| tag.id == tag_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::tag::Tag;
+
+    const TIMEOUT: Duration = Duration::from_secs(30);
+    const WORKSPACE_ID: i64 = 1;
+
+    fn hub_with(users: &[&str]) -> (CollaborationHub, Vec<ClientId>) {
+        let mut hub = CollaborationHub::new(TIMEOUT);
+        let client_ids = users
+            .iter()
+            .map(|user| hub.connect(user.to_string(), HashSet::from([WORKSPACE_ID])).0)
+            .collect();
+        (hub, client_ids)
+    }
+
+    fn subscribe(hub: &mut CollaborationHub, client_id: ClientId, task_ids: &[i64], now: Instant) -> Outbox {
+        let message = ClientMessageDto::Subscribe { task_ids: task_ids.to_vec(), filter: None };
+        hub.handle(client_id, message, now)
+    }
+
+    fn recipients(outbox: &Outbox) -> Vec<ClientId> {
+        outbox.iter().map(|(client_id, _)| *client_id).collect()
+    }
+
+    #[test]
+    fn connecting_welcomes_the_client() {
+        let mut hub = CollaborationHub::new(TIMEOUT);
+        let (first, _) = hub.connect("Alice".into(), HashSet::new());
+        let (second, outbox) = hub.connect("Bob".into(), HashSet::new());
+        assert_ne!(first, second);
+        assert!(matches!(
+            outbox.as_slice(),
+            [(client_id, ServerMessageDto::Welcome { lock_timeout: TIMEOUT, .. })] if *client_id == second
+        ));
+    }
+
+    #[test]
+    fn focus_is_reported_to_subscribers_of_the_task_only() {
+        let now = Instant::now();
+        let (mut hub, clients) = hub_with(&["Alice", "Bob", "Carol"]);
+        let [alice, bob, carol] = clients[..] else { unreachable!() };
+        subscribe(&mut hub, bob, &[1], now);
+        subscribe(&mut hub, carol, &[2], now);
+
+        let outbox = hub.handle(alice, ClientMessageDto::Focus(Some(1)), now);
+        assert_eq!(recipients(&outbox), vec![bob]);
+        assert!(matches!(
+            &outbox[0].1,
+            ServerMessageDto::Presence(PresenceDto { user, task_id: Some(1), .. }) if user == "Alice"
+        ));
+
+        // moving on is reported to the subscribers of both tasks
+        let outbox = hub.handle(alice, ClientMessageDto::Focus(Some(2)), now);
+        assert_eq!(recipients(&outbox), vec![bob, carol]);
+        assert!(hub.handle(alice, ClientMessageDto::Focus(Some(2)), now).is_empty());
+    }
+
+    #[test]
+    fn subscribing_returns_current_presence_and_locks() {
+        let now = Instant::now();
+        let (mut hub, clients) = hub_with(&["Alice", "Bob"]);
+        let [alice, bob] = clients[..] else { unreachable!() };
+        hub.handle(alice, ClientMessageDto::Focus(Some(1)), now);
+        hub.handle(alice, ClientMessageDto::Lock(1), now);
+        hub.handle(alice, ClientMessageDto::Lock(3), now);
+
+        let outbox = subscribe(&mut hub, bob, &[1, 2], now);
+        let [(client_id, ServerMessageDto::Snapshot { presence, locks })] = outbox.as_slice() else {
+            panic!("expected a snapshot");
+        };
+        assert_eq!(*client_id, bob);
+        assert_eq!(presence, &vec![PresenceDto { client_id: alice, user: "Alice".into(), task_id: Some(1) }]);
+        assert_eq!(locks, &vec![LockDto { task_id: 1, client_id: alice, user: "Alice".into() }]);
+    }
+
+    #[test]
+    fn a_lock_is_exclusive_until_released() {
+        let now = Instant::now();
+        let (mut hub, clients) = hub_with(&["Alice", "Bob"]);
+        let [alice, bob] = clients[..] else { unreachable!() };
+        subscribe(&mut hub, bob, &[1], now);
+
+        let outbox = hub.handle(alice, ClientMessageDto::Lock(1), now);
+        assert_eq!(recipients(&outbox), vec![alice, bob]);
+        assert!(outbox.iter().all(|(_, message)| matches!(message, ServerMessageDto::Locked(lock) if lock.client_id == alice)));
+
+        let outbox = hub.handle(bob, ClientMessageDto::Lock(1), now);
+        assert!(matches!(
+            outbox.as_slice(),
+            [(client_id, ServerMessageDto::LockDenied(LockDto { user, .. }))] if *client_id == bob && user == "Alice"
+        ));
+
+        // only the holder can release it
+        assert!(hub.handle(bob, ClientMessageDto::Unlock(1), now).is_empty());
+        let outbox = hub.handle(alice, ClientMessageDto::Unlock(1), now);
+        assert_eq!(recipients(&outbox), vec![alice, bob]);
+        assert!(matches!(outbox[0].1, ServerMessageDto::Unlocked { task_id: 1 }));
+
+        let outbox = hub.handle(bob, ClientMessageDto::Lock(1), now);
+        assert!(matches!(&outbox[0].1, ServerMessageDto::Locked(lock) if lock.client_id == bob));
+    }
+
+    #[test]
+    fn renewed_locks_outlive_the_timeout() {
+        let start = Instant::now();
+        let (mut hub, clients) = hub_with(&["Alice", "Bob"]);
+        let [alice, bob] = clients[..] else { unreachable!() };
+        hub.handle(alice, ClientMessageDto::Lock(1), start);
+
+        let renewal = start + TIMEOUT / 2;
+        assert!(hub.handle(alice, ClientMessageDto::Lock(1), renewal).is_empty());
+        assert!(hub.expire_locks(start + TIMEOUT).is_empty());
+        let outbox = hub.handle(bob, ClientMessageDto::Lock(1), start + TIMEOUT);
+        assert!(matches!(outbox[0].1, ServerMessageDto::LockDenied(_)));
+    }
+
+    #[test]
+    fn expired_locks_are_released() {
+        let start = Instant::now();
+        let (mut hub, clients) = hub_with(&["Alice", "Bob"]);
+        let [alice, bob] = clients[..] else { unreachable!() };
+        subscribe(&mut hub, bob, &[1], start);
+        hub.handle(alice, ClientMessageDto::Lock(1), start);
+
+        assert!(hub.expire_locks(start + TIMEOUT / 2).is_empty());
+        let outbox = hub.expire_locks(start + TIMEOUT);
+        assert_eq!(recipients(&outbox), vec![alice, bob]);
+        assert!(outbox.iter().all(|(_, message)| matches!(message, ServerMessageDto::Unlocked { task_id: 1 })));
+        assert!(hub.expire_locks(start + TIMEOUT * 2).is_empty());
+    }
+
+    #[test]
+    fn an_expired_lock_can_be_taken_before_it_is_swept() {
+        let start = Instant::now();
+        let (mut hub, clients) = hub_with(&["Alice", "Bob"]);
+        let [alice, bob] = clients[..] else { unreachable!() };
+        hub.handle(alice, ClientMessageDto::Lock(1), start);
+
+        let outbox = hub.handle(bob, ClientMessageDto::Lock(1), start + TIMEOUT);
+        assert!(matches!(outbox[0], (client_id, ServerMessageDto::Unlocked { task_id: 1 }) if client_id == alice));
+        assert!(matches!(&outbox[1], (client_id, ServerMessageDto::Locked(lock)) if *client_id == bob && lock.client_id == bob));
+    }
+
+    #[test]
+    fn disconnecting_releases_locks_and_presence() {
+        let now = Instant::now();
+        let (mut hub, clients) = hub_with(&["Alice", "Bob"]);
+        let [alice, bob] = clients[..] else { unreachable!() };
+        subscribe(&mut hub, bob, &[1, 2], now);
+        hub.handle(alice, ClientMessageDto::Focus(Some(1)), now);
+        hub.handle(alice, ClientMessageDto::Lock(2), now);
+
+        let outbox = hub.disconnect(alice);
+        assert_eq!(recipients(&outbox), vec![bob, bob]);
+        assert!(matches!(outbox[0].1, ServerMessageDto::Unlocked { task_id: 2 }));
+        assert!(matches!(&outbox[1].1, ServerMessageDto::Presence(PresenceDto { task_id: None, .. })));
+
+        // gone clients are ignored
+        assert!(hub.handle(alice, ClientMessageDto::Lock(3), now).is_empty());
+        let outbox = hub.handle(bob, ClientMessageDto::Lock(2), now);
+        assert!(matches!(&outbox[0].1, ServerMessageDto::Locked(lock) if lock.client_id == bob));
+    }
+
+    #[test]
+    fn task_changes_reach_subscribers_and_matching_filters() {
+        let now = Instant::now();
+        let (mut hub, clients) = hub_with(&["Alice", "Bob", "Carol", "Dave"]);
+        let [alice, bob, carol, dave] = clients[..] else { unreachable!() };
+        subscribe(&mut hub, alice, &[1], now);
+        let project_filter = SubscriptionFilterDto { project_id: Some(7), tag_id: None };
+        hub.handle(bob, ClientMessageDto::Subscribe { task_ids: vec![], filter: Some(project_filter) }, now);
+        let tag_filter = SubscriptionFilterDto { project_id: None, tag_id: Some(3) };
+        hub.handle(carol, ClientMessageDto::Subscribe { task_ids: vec![], filter: Some(tag_filter) }, now);
+        subscribe(&mut hub, dave, &[2], now);
+
+        let task = Task {
+            id: 1,
+            workspace_id: WORKSPACE_ID,
+            project_id: Some(7),
+            tags: vec![Tag { id: 4, ..Default::default() }],
+            ..Default::default()
+        };
+        let outbox = hub.task_event(&TaskEventDto::Updated(task));
+        assert_eq!(recipients(&outbox), vec![alice, bob]);
+        assert!(matches!(&outbox[0].1, ServerMessageDto::TaskChanged(task) if task.id == 1));
+
+        let outbox = hub.task_event(&TaskEventDto::Deleted { id: 1, workspace_id: WORKSPACE_ID });
+        assert_eq!(recipients(&outbox), vec![alice, bob, carol]);
+        assert!(outbox.iter().all(|(_, message)| matches!(message, ServerMessageDto::TaskDeleted { id: 1, .. })));
+    }
+
+    #[test]
+    fn task_events_only_reach_members_of_the_workspace() {
+        let now = Instant::now();
+        let mut hub = CollaborationHub::new(TIMEOUT);
+        let (member, _) = hub.connect("Alice".into(), HashSet::from([WORKSPACE_ID]));
+        let (outsider, _) = hub.connect("Mallory".into(), HashSet::from([WORKSPACE_ID + 1]));
+        let everything = SubscriptionFilterDto { project_id: None, tag_id: None };
+        for client_id in [member, outsider] {
+            hub.handle(client_id, ClientMessageDto::Subscribe { task_ids: vec![1], filter: Some(everything.clone()) }, now);
+        }
+
+        let task = Task { id: 1, workspace_id: WORKSPACE_ID, ..Default::default() };
+        assert_eq!(recipients(&hub.task_event(&TaskEventDto::Created(task))), vec![member]);
+        let deleted = TaskEventDto::Deleted { id: 1, workspace_id: WORKSPACE_ID };
+        assert_eq!(recipients(&hub.task_event(&deleted)), vec![member]);
+    }
+}
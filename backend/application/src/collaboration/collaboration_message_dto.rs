@@ -0,0 +1,69 @@
+use domain::task::Task;
+use std::time::Duration;
+
+/// Identifies one connection; a user with two tabs open is two clients.
+pub type ClientId = u64;
+
+/// Tasks a client wants to hear about, besides the ones it subscribed to by id.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SubscriptionFilterDto {
+    pub project_id: Option<i64>,
+    pub tag_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientMessageDto {
+    /// Replaces the client's subscription. Presence and locks are only reported for `task_ids`,
+    /// changes also for tasks matching `filter`.
+    Subscribe {
+        task_ids: Vec<i64>,
+        filter: Option<SubscriptionFilterDto>,
+    },
+    /// The task the user is looking at, if any.
+    Focus(Option<i64>),
+    /// Takes or renews the soft lock on a task.
+    Lock(i64),
+    Unlock(i64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresenceDto {
+    pub client_id: ClientId,
+    pub user: String,
+    pub task_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockDto {
+    pub task_id: i64,
+    pub client_id: ClientId,
+    pub user: String,
+}
+
+#[derive(Clone)]
+pub enum ServerMessageDto {
+    /// First message on every connection. Locks have to be renewed within `lock_timeout`.
+    Welcome { client_id: ClientId, lock_timeout: Duration },
+    /// Presence and locks on the subscribed tasks, sent in reply to a subscription.
+    Snapshot { presence: Vec<PresenceDto>, locks: Vec<LockDto> },
+    Presence(PresenceDto),
+    Locked(LockDto),
+    /// The lock was refused because someone else holds it.
+    LockDenied(LockDto),
+    Unlocked { task_id: i64 },
+    TaskChanged(Box<Task>),
+    TaskDeleted { id: i64, workspace_id: i64 },
+    /// A message could not be understood.
+    Error(String),
+}
+
+impl ServerMessageDto {
+    /// The workspace of the task a change or deletion is about.
+    pub fn task_workspace_id(&self) -> Option<i64> {
+        match self {
+            ServerMessageDto::TaskChanged(task) => Some(task.workspace_id),
+            ServerMessageDto::TaskDeleted { workspace_id, .. } => Some(*workspace_id),
+            _ => None,
+        }
+    }
+}
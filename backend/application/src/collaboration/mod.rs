@@ -0,0 +1,2 @@
+pub mod collaboration_hub;
+pub mod collaboration_message_dto;
\ No newline at end of file
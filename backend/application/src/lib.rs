@@ -1 +1,7 @@
-pub mod task;
\ No newline at end of file
+pub mod task;
+pub mod tag;
+pub mod project;
+pub mod collaboration;
+pub mod webhook;
+pub mod auth;
+pub mod workspace;
\ No newline at end of file
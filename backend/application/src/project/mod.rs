@@ -0,0 +1,3 @@
+pub mod project_service;
+pub mod project_repository_trait;
+pub mod project_service_trait;
\ No newline at end of file
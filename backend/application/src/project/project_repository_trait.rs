@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+use domain::project::Project;
+
+#[async_trait]
+pub trait ProjectRepositoryTrait: Send + Sync {
+    async fn save(&self, project: Project) -> anyhow::Result<Project>;
+    /// Ordered by name.
+    async fn find_by_workspace(&self, workspace_id: i64, include_archived: bool) -> anyhow::Result<Vec<Project>>;
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Project>>;
+    async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool>;
+}
@@ -0,0 +1,111 @@
+use crate::project::project_repository_trait::ProjectRepositoryTrait;
+use crate::project::project_service_trait::ProjectServiceTrait;
+use crate::workspace::workspace_error::WorkspaceError;
+use crate::workspace::workspace_policy::WorkspaceAction;
+use crate::workspace::workspace_repository_trait::WorkspaceRepositoryTrait;
+use crate::workspace::workspace_service::{authorize_member, default_workspace};
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::project::Project;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct ProjectService {
+    project_repository: Arc<dyn ProjectRepositoryTrait>,
+    workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+}
+
+impl ProjectService {
+    pub fn new(
+        project_repository: Arc<dyn ProjectRepositoryTrait>,
+        workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+    ) -> Self {
+        Self { project_repository, workspace_repository }
+    }
+
+    /// Loads the project if the user's role in its workspace allows the action.
+    async fn authorize(&self, user_id: i64, id: i64, action: WorkspaceAction) -> anyhow::Result<Option<Project>> {
+        match self.project_repository.find_by_id(id).await? {
+            Some(project)
+                if authorize_member(&*self.workspace_repository, user_id, project.workspace_id, action).await? =>
+            {
+                Ok(Some(project))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn set_archived(&self, user_id: i64, id: i64, archived: bool) -> anyhow::Result<Option<Project>> {
+        if let Some(mut project) = self.authorize(user_id, id, WorkspaceAction::EditTasks).await? {
+            if project.archived_at.is_some() != archived {
+                project.archived_at = archived.then(Utc::now);
+                project = self.project_repository.save(project).await?;
+            }
+            Ok(Some(project))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[async_trait]
+impl ProjectServiceTrait for ProjectService {
+    async fn create_project(&self, user_id: i64, workspace_id: Option<i64>, name: String) -> anyhow::Result<Project> {
+        let workspace_id = match workspace_id {
+            Some(workspace_id) => workspace_id,
+            None => default_workspace(&*self.workspace_repository, user_id).await?,
+        };
+        if !authorize_member(&*self.workspace_repository, user_id, workspace_id, WorkspaceAction::EditTasks).await? {
+            return Err(WorkspaceError::NotFound(workspace_id).into());
+        }
+        let new_project = Project {
+            workspace_id,
+            name,
+            ..Default::default()
+        };
+        self.project_repository.save(new_project).await
+    }
+
+    async fn rename_project(&self, user_id: i64, id: i64, name: String) -> anyhow::Result<Option<Project>> {
+        if let Some(mut project) = self.authorize(user_id, id, WorkspaceAction::EditTasks).await? {
+            project.name = name;
+            let updated = self.project_repository.save(project).await?;
+            Ok(Some(updated))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn archive_project(&self, user_id: i64, id: i64) -> anyhow::Result<Option<Project>> {
+        self.set_archived(user_id, id, true).await
+    }
+
+    async fn unarchive_project(&self, user_id: i64, id: i64) -> anyhow::Result<Option<Project>> {
+        self.set_archived(user_id, id, false).await
+    }
+
+    async fn delete_project(&self, user_id: i64, id: i64) -> anyhow::Result<bool> {
+        if self.authorize(user_id, id, WorkspaceAction::EditTasks).await?.is_none() {
+            return Ok(false);
+        }
+        self.project_repository.delete_by_id(id).await
+    }
+
+    async fn get_project(&self, user_id: i64, id: i64) -> anyhow::Result<Option<Project>> {
+        self.authorize(user_id, id, WorkspaceAction::ViewTasks).await
+    }
+
+    async fn find_projects(&self, user_id: i64, include_archived: bool) -> anyhow::Result<Vec<Project>> {
+        let mut projects = Vec::new();
+        // every member may view the projects of their workspace
+        for membership in self.workspace_repository.find_memberships(user_id).await? {
+            projects.extend(
+                self.project_repository
+                    .find_by_workspace(membership.workspace.id, include_archived)
+                    .await?,
+            );
+        }
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(projects)
+    }
+}
@@ -0,0 +1,18 @@
+use domain::project::Project;
+use async_trait::async_trait;
+use anyhow::Result;
+
+/// Projects belong to a workspace: its members see them, members who may edit tasks manage them.
+#[async_trait]
+pub trait ProjectServiceTrait: Send + Sync {
+    /// Creates the project in the workspace, by default the user's oldest one they may edit in.
+    async fn create_project(&self, user_id: i64, workspace_id: Option<i64>, name: String) -> Result<Project>;
+    async fn rename_project(&self, user_id: i64, id: i64, name: String) -> Result<Option<Project>>;
+    async fn archive_project(&self, user_id: i64, id: i64) -> Result<Option<Project>>;
+    async fn unarchive_project(&self, user_id: i64, id: i64) -> Result<Option<Project>>;
+    /// Deletes the project; its tasks are kept without a project.
+    async fn delete_project(&self, user_id: i64, id: i64) -> Result<bool>;
+    async fn get_project(&self, user_id: i64, id: i64) -> Result<Option<Project>>;
+    /// The projects of every workspace the user belongs to.
+    async fn find_projects(&self, user_id: i64, include_archived: bool) -> Result<Vec<Project>>;
+}
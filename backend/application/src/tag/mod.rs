@@ -0,0 +1,3 @@
+pub mod tag_service;
+pub mod tag_repository_trait;
+pub mod tag_service_trait;
\ No newline at end of file
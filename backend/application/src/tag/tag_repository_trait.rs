@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+use domain::tag::Tag;
+
+#[async_trait]
+pub trait TagRepositoryTrait: Send + Sync {
+    async fn save(&self, tag: Tag) -> anyhow::Result<Tag>;
+    /// Ordered by name.
+    async fn find_by_workspace(&self, workspace_id: i64) -> anyhow::Result<Vec<Tag>>;
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Tag>>;
+    async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool>;
+}
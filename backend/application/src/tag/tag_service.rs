@@ -0,0 +1,92 @@
+use crate::tag::tag_repository_trait::TagRepositoryTrait;
+use crate::tag::tag_service_trait::TagServiceTrait;
+use crate::workspace::workspace_error::WorkspaceError;
+use crate::workspace::workspace_policy::WorkspaceAction;
+use crate::workspace::workspace_repository_trait::WorkspaceRepositoryTrait;
+use crate::workspace::workspace_service::{authorize_member, default_workspace};
+use async_trait::async_trait;
+use domain::tag::Tag;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct TagService {
+    tag_repository: Arc<dyn TagRepositoryTrait>,
+    workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+}
+
+impl TagService {
+    pub fn new(
+        tag_repository: Arc<dyn TagRepositoryTrait>,
+        workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+    ) -> Self {
+        Self { tag_repository, workspace_repository }
+    }
+
+    /// Loads the tag if the user's role in its workspace allows the action.
+    async fn authorize(&self, user_id: i64, id: i64, action: WorkspaceAction) -> anyhow::Result<Option<Tag>> {
+        match self.tag_repository.find_by_id(id).await? {
+            Some(tag) if authorize_member(&*self.workspace_repository, user_id, tag.workspace_id, action).await? => {
+                Ok(Some(tag))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl TagServiceTrait for TagService {
+    async fn create_tag(
+        &self,
+        user_id: i64,
+        workspace_id: Option<i64>,
+        name: String,
+        color: String,
+    ) -> anyhow::Result<Tag> {
+        let workspace_id = match workspace_id {
+            Some(workspace_id) => workspace_id,
+            None => default_workspace(&*self.workspace_repository, user_id).await?,
+        };
+        if !authorize_member(&*self.workspace_repository, user_id, workspace_id, WorkspaceAction::EditTasks).await? {
+            return Err(WorkspaceError::NotFound(workspace_id).into());
+        }
+        let new_tag = Tag {
+            workspace_id,
+            name,
+            color,
+            ..Default::default()
+        };
+        self.tag_repository.save(new_tag).await
+    }
+
+    async fn update_tag(&self, user_id: i64, id: i64, name: String, color: String) -> anyhow::Result<Option<Tag>> {
+        if let Some(mut tag) = self.authorize(user_id, id, WorkspaceAction::EditTasks).await? {
+            tag.name = name;
+            tag.color = color;
+            let updated = self.tag_repository.save(tag).await?;
+            Ok(Some(updated))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn delete_tag(&self, user_id: i64, id: i64) -> anyhow::Result<bool> {
+        if self.authorize(user_id, id, WorkspaceAction::EditTasks).await?.is_none() {
+            return Ok(false);
+        }
+        self.tag_repository.delete_by_id(id).await
+    }
+
+    async fn get_tag(&self, user_id: i64, id: i64) -> anyhow::Result<Option<Tag>> {
+        self.authorize(user_id, id, WorkspaceAction::ViewTasks).await
+    }
+
+    async fn find_tags(&self, user_id: i64) -> anyhow::Result<Vec<Tag>> {
+        let mut tags = Vec::new();
+        // every member may view the tags of their workspace
+        for membership in self.workspace_repository.find_memberships(user_id).await? {
+            tags.extend(self.tag_repository.find_by_workspace(membership.workspace.id).await?);
+        }
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tags)
+    }
+}
@@ -0,0 +1,15 @@
+use domain::tag::Tag;
+use async_trait::async_trait;
+use anyhow::Result;
+
+/// Tags belong to a workspace: its members see them, members who may edit tasks manage them.
+#[async_trait]
+pub trait TagServiceTrait: Send + Sync {
+    /// Creates the tag in the workspace, by default the user's oldest one they may edit in.
+    async fn create_tag(&self, user_id: i64, workspace_id: Option<i64>, name: String, color: String) -> Result<Tag>;
+    async fn update_tag(&self, user_id: i64, id: i64, name: String, color: String) -> Result<Option<Tag>>;
+    async fn delete_tag(&self, user_id: i64, id: i64) -> Result<bool>;
+    async fn get_tag(&self, user_id: i64, id: i64) -> Result<Option<Tag>>;
+    /// The tags of every workspace the user belongs to.
+    async fn find_tags(&self, user_id: i64) -> Result<Vec<Tag>>;
+}
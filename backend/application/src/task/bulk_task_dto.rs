@@ -0,0 +1,45 @@
+use crate::task::task_error::TaskError;
+use crate::task::task_filter_and_sort_dto::TaskFilterAndSortDto;
+use crate::task::task_patch_dto::TaskPatchDto;
+use domain::task::Task;
+
+/// Upper bound for the number of tasks a single bulk request may change.
+pub const MAX_BULK_ITEMS: usize = 500;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkOperationDto {
+    MarkDone { force: bool },
+    Reopen,
+    Delete,
+    Retitle(String),
+    Set(TaskPatchDto),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkItemDto {
+    pub id: i64,
+    pub operation: BulkOperationDto,
+    pub expected_version: Option<i64>,
+}
+
+/// Either a list of operations, or one operation applied to every task matching a selector.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkRequestDto {
+    Items(Vec<BulkItemDto>),
+    Selector {
+        filter_and_sort: Box<TaskFilterAndSortDto>,
+        operation: BulkOperationDto,
+    },
+}
+
+pub enum BulkOutcomeDto {
+    Applied(Box<Task>),
+    Failed(TaskError),
+    /// The operation succeeded but was undone because another one failed.
+    RolledBack,
+}
+
+pub struct BulkItemResultDto {
+    pub id: i64,
+    pub outcome: BulkOutcomeDto,
+}
@@ -0,0 +1,62 @@
+use std::collections::{HashMap, HashSet};
+
+/// "Blocked by" relations between tasks, with edges pointing from a task to its blockers.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    blockers: HashMap<i64, Vec<i64>>,
+}
+
+impl DependencyGraph {
+    /// Builds the graph from `(task_id, blocker_id)` pairs.
+    pub fn new(dependencies: impl IntoIterator<Item = (i64, i64)>) -> Self {
+        let mut blockers: HashMap<i64, Vec<i64>> = HashMap::new();
+        for (task_id, blocker_id) in dependencies {
+            blockers.entry(task_id).or_default().push(blocker_id);
+        }
+        Self { blockers }
+    }
+
+    /// Whether letting `blocker_id` block `task_id` would close a cycle, which is the case when
+    /// the blocker is the task itself or already waits on it, directly or transitively.
+    pub fn would_create_cycle(&self, task_id: i64, blocker_id: i64) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending = vec![blocker_id];
+        while let Some(current_id) = pending.pop() {
+            if current_id == task_id {
+                return true;
+            }
+            if visited.insert(current_id)
+                && let Some(blocker_ids) = self.blockers.get(&current_id)
+            {
+                pending.extend(blocker_ids);
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_are_detected() {
+        let table = [
+            // existing (task, blocker) edges, the new edge, whether it closes a cycle
+            (vec![], (1, 1), true),
+            (vec![(2, 1)], (1, 2), true),
+            (vec![(2, 3), (3, 1)], (1, 2), true),
+            (vec![(1, 2), (2, 3)], (3, 4), false),
+            (vec![(1, 2), (2, 3)], (1, 3), false),
+            (vec![(2, 3), (3, 4)], (1, 2), false),
+        ];
+        for (dependencies, (task_id, blocker_id), expected) in table {
+            let graph = DependencyGraph::new(dependencies.clone());
+            assert_eq!(
+                graph.would_create_cycle(task_id, blocker_id),
+                expected,
+                "{blocker_id} blocking {task_id} with {dependencies:?}"
+            );
+        }
+    }
+}
@@ -1,4 +1,12 @@
 pub mod task_service;
 pub mod task_repository_trait;
 pub mod task_service_trait;
-pub mod task_filter_and_sort_dto;
\ No newline at end of file
+pub mod task_filter_and_sort_dto;
+pub mod task_page_dto;
+pub mod task_patch_dto;
+pub mod new_task_dto;
+pub mod task_error;
+pub mod dependency_graph;
+pub mod bulk_task_dto;
+pub mod task_event_dto;
+pub mod task_event_publisher_trait;
\ No newline at end of file
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use domain::task::Priority;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NewTaskDto {
+    pub title: String,
+    pub description: Option<String>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub priority: Priority,
+    pub parent_id: Option<i64>,
+    pub recurrence_rule: Option<String>,
+    pub recurrence_time_zone: Option<String>,
+    pub project_id: Option<i64>,
+    /// The parent's workspace, or the user's oldest workspace, when absent.
+    pub workspace_id: Option<i64>,
+    /// The signed-in user, filled in by the caller rather than taken from the request.
+    pub created_by: Option<i64>,
+}
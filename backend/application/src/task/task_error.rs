@@ -0,0 +1,135 @@
+use crate::workspace::workspace_policy::WorkspaceAction;
+use domain::validation::FieldError;
+use std::fmt;
+
+/// Failures of task operations. All but `Storage` are caused by the request and can be acted upon.
+#[derive(Debug)]
+pub enum TaskError {
+    NotFound(i64),
+    TagNotFound(i64),
+    /// The user to assign is no member of the task's workspace.
+    NotAMember(i64),
+    InvalidField(FieldError),
+    ParentNotFound(i64),
+    ParentCycle { id: i64, parent_id: i64 },
+    OpenSubtasks(i64),
+    DependencyCycle { id: i64, blocker_id: i64 },
+    OpenBlockers(i64),
+    InvalidRecurrence(String),
+    ProjectNotFound(i64),
+    WorkspaceNotFound(i64),
+    /// The user's role in the task's workspace does not allow the action.
+    Forbidden(WorkspaceAction),
+    InvalidCursor,
+    VersionMismatch(i64),
+    TooManyBulkItems { max: usize },
+    Storage(anyhow::Error),
+}
+
+/// Coarse classification of a `TaskError`, telling callers how to react.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskErrorKind {
+    NotFound,
+    Validation,
+    Conflict,
+    Forbidden,
+    PreconditionFailed,
+    Storage,
+}
+
+impl TaskError {
+    pub fn kind(&self) -> TaskErrorKind {
+        match self {
+            TaskError::NotFound(_) | TaskError::TagNotFound(_) => TaskErrorKind::NotFound,
+            TaskError::InvalidField(_)
+            | TaskError::ParentNotFound(_)
+            | TaskError::InvalidRecurrence(_)
+            | TaskError::ProjectNotFound(_)
+            | TaskError::WorkspaceNotFound(_)
+            | TaskError::NotAMember(_)
+            | TaskError::InvalidCursor
+            | TaskError::TooManyBulkItems { .. } => TaskErrorKind::Validation,
+            TaskError::ParentCycle { .. }
+            | TaskError::OpenSubtasks(_)
+            | TaskError::DependencyCycle { .. }
+            | TaskError::OpenBlockers(_) => TaskErrorKind::Conflict,
+            TaskError::Forbidden(_) => TaskErrorKind::Forbidden,
+            TaskError::VersionMismatch(_) => TaskErrorKind::PreconditionFailed,
+            TaskError::Storage(_) => TaskErrorKind::Storage,
+        }
+    }
+
+    /// Stable kebab-case identifier of the variant for clients that branch on the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TaskError::NotFound(_) => "task-not-found",
+            TaskError::TagNotFound(_) => "tag-not-found",
+            TaskError::NotAMember(_) => "not-a-member",
+            TaskError::InvalidField(_) => "invalid-field",
+            TaskError::ParentNotFound(_) => "parent-not-found",
+            TaskError::ParentCycle { .. } => "parent-cycle",
+            TaskError::OpenSubtasks(_) => "open-subtasks",
+            TaskError::DependencyCycle { .. } => "dependency-cycle",
+            TaskError::OpenBlockers(_) => "open-blockers",
+            TaskError::InvalidRecurrence(_) => "invalid-recurrence",
+            TaskError::ProjectNotFound(_) => "project-not-found",
+            TaskError::WorkspaceNotFound(_) => "workspace-not-found",
+            TaskError::Forbidden(_) => "forbidden",
+            TaskError::InvalidCursor => "invalid-cursor",
+            TaskError::VersionMismatch(_) => "version-mismatch",
+            TaskError::TooManyBulkItems { .. } => "too-many-bulk-items",
+            TaskError::Storage(_) => "storage",
+        }
+    }
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::NotFound(id) => write!(f, "task {id} does not exist"),
+            TaskError::TagNotFound(tag_id) => write!(f, "tag {tag_id} does not exist"),
+            TaskError::NotAMember(user_id) => write!(f, "user {user_id} is no member of the task's workspace"),
+            TaskError::InvalidField(error) => error.fmt(f),
+            TaskError::ParentNotFound(parent_id) => write!(f, "parent task {parent_id} does not exist"),
+            TaskError::ParentCycle { id, parent_id } => {
+                write!(f, "task {parent_id} is task {id} itself or one of its subtasks")
+            }
+            TaskError::OpenSubtasks(id) => write!(f, "task {id} still has open subtasks"),
+            TaskError::DependencyCycle { id, blocker_id } => {
+                write!(f, "task {blocker_id} is task {id} itself or already waits on it")
+            }
+            TaskError::OpenBlockers(id) => write!(f, "task {id} is blocked by open tasks"),
+            TaskError::InvalidRecurrence(reason) => write!(f, "invalid recurrence: {reason}"),
+            TaskError::ProjectNotFound(project_id) => write!(f, "project {project_id} does not exist"),
+            TaskError::WorkspaceNotFound(workspace_id) => write!(f, "workspace {workspace_id} does not exist"),
+            TaskError::Forbidden(action) => write!(f, "your role in the workspace does not allow you to {action}"),
+            TaskError::InvalidCursor => write!(f, "cursor is malformed or belongs to another sort order"),
+            TaskError::VersionMismatch(id) => write!(f, "task {id} has been changed in the meantime"),
+            TaskError::TooManyBulkItems { max } => write!(f, "a bulk request may change at most {max} tasks"),
+            TaskError::Storage(error) => write!(f, "storage failure: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for TaskError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TaskError::Storage(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<FieldError> for TaskError {
+    fn from(error: FieldError) -> Self {
+        TaskError::InvalidField(error)
+    }
+}
+
+/// Repositories report failures as `anyhow::Error`; task errors raised there are recovered,
+/// everything else is a storage failure.
+impl From<anyhow::Error> for TaskError {
+    fn from(error: anyhow::Error) -> Self {
+        error.downcast().unwrap_or_else(TaskError::Storage)
+    }
+}
@@ -0,0 +1,10 @@
+use domain::task::Task;
+
+/// A committed change, published so that other clients can follow along.
+#[derive(Clone)]
+pub enum TaskEventDto {
+    Created(Task),
+    Updated(Task),
+    /// The task was moved to the trash or purged.
+    Deleted { id: i64, workspace_id: i64 },
+}
@@ -0,0 +1,6 @@
+use crate::task::task_event_dto::TaskEventDto;
+
+pub trait TaskEventPublisherTrait: Send + Sync {
+    /// Must not block. Subscribers that cannot keep up may miss events.
+    fn publish(&self, event: TaskEventDto);
+}
@@ -1,19 +1,61 @@
 use chrono::{DateTime, Utc};
+use domain::task::Priority;
 
-#[derive(Debug, Clone, PartialEq)]
+pub const DEFAULT_PAGE_SIZE: u64 = 50;
+pub const MAX_PAGE_SIZE: u64 = 200;
+
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct TaskFilterAndSortDto {
     pub filters: Option<TaskFilterAndSortFiltersDto>,
     pub sort: Option<TaskFilterAndSortSortDto>,
+    pub limit: Option<u64>,
+    /// Continues after the page that returned this cursor; only valid with the same sort.
+    pub cursor: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl TaskFilterAndSortDto {
+    pub fn page_size(&self) -> u64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct TaskFilterAndSortFiltersDto {
     pub done: Option<bool>,
+    /// Case-insensitive substring of the title.
     pub title: Option<String>,
+    /// Full-text search over title and description in web search syntax.
+    pub query: Option<String>,
     pub created_after: Option<DateTime<Utc>>,
     pub created_before: Option<DateTime<Utc>>,
     pub updated_after: Option<DateTime<Utc>>,
     pub updated_before: Option<DateTime<Utc>>,
+    pub deleted: Option<bool>,
+    /// Trashed tasks along with the others when true, overriding `deleted`; set by the service,
+    /// not by clients.
+    pub include_deleted: Option<bool>,
+    pub due_after: Option<DateTime<Utc>>,
+    pub due_before: Option<DateTime<Utc>>,
+    pub overdue: Option<bool>,
+    pub priorities: Option<Vec<Priority>>,
+    pub tags_any: Option<Vec<i64>>,
+    pub tags_all: Option<Vec<i64>>,
+    pub tags_none: Option<Vec<i64>>,
+    pub blocked: Option<bool>,
+    pub series_id: Option<i64>,
+    /// Tasks of archived projects are only returned when their project is asked for explicitly.
+    pub project_id: Option<i64>,
+    /// Only tasks without a parent, or whose parent is in the trash, when true.
+    pub top_level: Option<bool>,
+    /// Only tasks this user is assigned to.
+    pub assignee: Option<i64>,
+    /// Only tasks without assignees when true, only assigned tasks when false.
+    pub unassigned: Option<bool>,
+    pub created_by: Option<i64>,
+    pub workspace_id: Option<i64>,
+    /// Only tasks of these workspaces, those whose tasks the policy lets the user view; set by the
+    /// service, not by clients.
+    pub viewable_workspace_ids: Option<Vec<i64>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -28,6 +70,10 @@ pub enum TaskFilterAndSortSortFieldDto {
     UpdatedAt,
     Title,
     Done,
+    DueAt,
+    Priority,
+    /// How well a task matches the `query` filter; ordered by id without one.
+    Relevance,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -35,3 +81,10 @@ pub enum TaskFilterAndSortSortDirectionDto {
     Asc,
     Desc,
 }
+
+impl TaskFilterAndSortFiltersDto {
+    /// The full-text search, unless it is blank.
+    pub fn search_query(&self) -> Option<&str> {
+        self.query.as_deref().filter(|query| !query.trim().is_empty())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,22 @@
+use domain::task::Task;
+
+/// One page of a filtered task listing.
+#[derive(Default)]
+pub struct TaskPageDto {
+    pub items: Vec<Task>,
+    /// Opaque position to continue after, absent on the last page.
+    pub next_cursor: Option<String>,
+    /// Number of tasks matching the filters, across all pages.
+    pub total_estimate: u64,
+    /// Matched words of the listed tasks, only filled in for a full-text search.
+    pub highlights: Vec<TaskHighlightDto>,
+}
+
+/// The title and an excerpt of the description of a task found by a full-text search,
+/// with the matched words wrapped in `<mark>` and `</mark>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskHighlightDto {
+    pub task_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+}
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use domain::task::Priority;
+
+/// Changes to a task following JSON Merge Patch: `None` leaves a field untouched,
+/// `Some(None)` clears it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TaskPatchDto {
+    pub title: Option<String>,
+    pub description: Option<Option<String>>,
+    pub done: Option<bool>,
+    pub priority: Option<Priority>,
+    pub due_at: Option<Option<DateTime<Utc>>>,
+    pub parent_id: Option<Option<i64>>,
+    pub project_id: Option<Option<i64>>,
+    /// Clearing the rule also clears its time zone.
+    pub recurrence_rule: Option<Option<String>>,
+    pub recurrence_time_zone: Option<Option<String>>,
+}
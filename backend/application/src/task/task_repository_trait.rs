@@ -1,10 +1,40 @@
 use async_trait::async_trait;
 use domain::task::Task;
 use crate::task::task_filter_and_sort_dto::TaskFilterAndSortDto;
+use crate::task::task_page_dto::TaskPageDto;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Work passed to `TaskRepositoryTrait::transaction`, given a repository bound to the transaction.
+pub type TransactionWork = Box<
+    dyn FnOnce(Arc<dyn TaskRepositoryTrait>) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>> + Send,
+>;
 
 #[async_trait]
 pub trait TaskRepositoryTrait: Send + Sync {
+    /// Inserts new tasks. Updates only succeed while the stored version still equals
+    /// `task.version` and fail with `TaskError::VersionMismatch` otherwise.
     async fn save(&self, task: Task) -> anyhow::Result<Task>;
+    /// All matching tasks; `limit` and `cursor` are ignored.
     async fn find_tasks(&self, filter_and_sort: TaskFilterAndSortDto) -> anyhow::Result<Vec<Task>>;
+    /// One page of matching tasks, ordered by the sort field and then by id.
+    async fn find_task_page(&self, filter_and_sort: TaskFilterAndSortDto) -> anyhow::Result<TaskPageDto>;
     async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Task>>;
+    async fn find_children(&self, parent_id: i64) -> anyhow::Result<Vec<Task>>;
+    async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool>;
+    async fn add_tag(&self, task_id: i64, tag_id: i64) -> anyhow::Result<()>;
+    async fn remove_tag(&self, task_id: i64, tag_id: i64) -> anyhow::Result<()>;
+    async fn add_assignee(&self, task_id: i64, user_id: i64) -> anyhow::Result<()>;
+    async fn remove_assignee(&self, task_id: i64, user_id: i64) -> anyhow::Result<()>;
+    /// All `(task_id, blocker_id)` pairs.
+    async fn find_dependencies(&self) -> anyhow::Result<Vec<(i64, i64)>>;
+    /// Keeps other transactions from changing dependencies until the current one ends. Only
+    /// valid inside `transaction`.
+    async fn lock_dependencies(&self) -> anyhow::Result<()>;
+    async fn add_blocker(&self, task_id: i64, blocker_id: i64) -> anyhow::Result<()>;
+    async fn remove_blocker(&self, task_id: i64, blocker_id: i64) -> anyhow::Result<()>;
+    /// Runs `work` in one database transaction, committed when it succeeds and rolled back
+    /// when it fails.
+    async fn transaction(&self, work: TransactionWork) -> anyhow::Result<()>;
 }
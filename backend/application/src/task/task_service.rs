@@ -1,56 +1,1274 @@
+use crate::project::project_repository_trait::ProjectRepositoryTrait;
+use crate::tag::tag_repository_trait::TagRepositoryTrait;
 use crate::task::task_repository_trait::TaskRepositoryTrait;
 use crate::task::task_service_trait::TaskServiceTrait;
 use async_trait::async_trait;
-use domain::task::Task;
-use std::sync::Arc;
-use crate::task::task_filter_and_sort_dto::TaskFilterAndSortDto;
+use domain::task::{Priority, Task};
+use std::sync::{Arc, Mutex, PoisonError};
+use crate::task::bulk_task_dto::{BulkItemDto, BulkItemResultDto, BulkOperationDto, BulkOutcomeDto, BulkRequestDto, MAX_BULK_ITEMS};
+use crate::task::dependency_graph::DependencyGraph;
+use crate::task::new_task_dto::NewTaskDto;
+use crate::task::task_error::TaskError;
+use crate::task::task_event_dto::TaskEventDto;
+use crate::task::task_event_publisher_trait::TaskEventPublisherTrait;
+use crate::task::task_filter_and_sort_dto::{MAX_PAGE_SIZE, TaskFilterAndSortDto, TaskFilterAndSortFiltersDto};
+use crate::task::task_page_dto::TaskPageDto;
+use crate::task::task_patch_dto::TaskPatchDto;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use domain::recurrence::RecurrenceRule;
+use domain::validation::{validate_description, validate_title};
+use std::collections::{HashMap, HashSet};
+use crate::workspace::workspace_policy::{Decision, WorkspaceAction, decide};
+use crate::workspace::workspace_repository_trait::WorkspaceRepositoryTrait;
+use crate::workspace::workspace_service::{allowed_workspace_ids, default_workspace};
+
+/// What completing a task does to its open subtasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParentCompletion {
+    /// Refuse to complete the task while any subtask is open.
+    #[default]
+    RequireSubtasksDone,
+    /// Complete all open subtasks along with the task.
+    Cascade,
+}
 
 #[derive(Clone)]
 pub struct TaskService {
     task_repository: Arc<dyn TaskRepositoryTrait>,
+    tag_repository: Arc<dyn TagRepositoryTrait>,
+    project_repository: Arc<dyn ProjectRepositoryTrait>,
+    workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+    task_events: Arc<dyn TaskEventPublisherTrait>,
+    parent_completion: ParentCompletion,
 }
 
 impl TaskService {
-    pub fn new(task_repository: Arc<dyn TaskRepositoryTrait>) -> Self {
-        Self { task_repository }
+    pub fn new(
+        task_repository: Arc<dyn TaskRepositoryTrait>,
+        tag_repository: Arc<dyn TagRepositoryTrait>,
+        project_repository: Arc<dyn ProjectRepositoryTrait>,
+        workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+        task_events: Arc<dyn TaskEventPublisherTrait>,
+        parent_completion: ParentCompletion,
+    ) -> Self {
+        Self {
+            task_repository,
+            tag_repository,
+            project_repository,
+            workspace_repository,
+            task_events,
+            parent_completion,
+        }
+    }
+
+    /// Refuses projects that do not exist or belong to another workspace than the task.
+    async fn ensure_project_exists(&self, project_id: Option<i64>, workspace_id: i64) -> Result<(), TaskError> {
+        if let Some(project_id) = project_id
+            && self
+                .project_repository
+                .find_by_id(project_id)
+                .await?
+                .is_none_or(|project| project.workspace_id != workspace_id)
+        {
+            return Err(TaskError::ProjectNotFound(project_id));
+        }
+        Ok(())
+    }
+
+    async fn find_task(&self, id: i64) -> Result<Task, TaskError> {
+        self.task_repository.find_by_id(id).await?.ok_or(TaskError::NotFound(id))
+    }
+
+    /// Consults the workspace policy. Outsiders get `concealed`, so that they learn nothing about
+    /// the workspace.
+    async fn authorize_in(
+        &self,
+        user_id: i64,
+        workspace_id: i64,
+        action: WorkspaceAction,
+        concealed: TaskError,
+    ) -> Result<(), TaskError> {
+        let role = self.workspace_repository.find_role(workspace_id, user_id).await?;
+        match decide(role, action) {
+            Decision::Allow => Ok(()),
+            Decision::Deny => Err(TaskError::Forbidden(action)),
+            Decision::Conceal => Err(concealed),
+        }
+    }
+
+    /// The workspaces whose tasks the policy lets the user view, for restricting task queries.
+    async fn viewable_workspace_ids(&self, user_id: i64) -> Result<Vec<i64>, TaskError> {
+        Ok(allowed_workspace_ids(&*self.workspace_repository, user_id, WorkspaceAction::ViewTasks).await?)
+    }
+
+    /// Loads the task if the user's role in its workspace allows the action.
+    async fn authorize(&self, user_id: i64, id: i64, action: WorkspaceAction) -> Result<Task, TaskError> {
+        let task = self.find_task(id).await?;
+        self.authorize_in(user_id, task.workspace_id, action, TaskError::NotFound(id)).await?;
+        Ok(task)
+    }
+
+    /// Reloads a task whose tags, assignees or blockers have changed and publishes it.
+    async fn find_changed_task(&self, id: i64) -> Result<Task, TaskError> {
+        let task = self.find_task(id).await?;
+        self.task_events.publish(TaskEventDto::Updated(task.clone()));
+        Ok(task)
+    }
+
+    /// Bumps the version of a task whose tags, assignees or blockers are about to change.
+    async fn touch(&self, task: Task, expected_version: Option<i64>) -> Result<Task, TaskError> {
+        check_version(&task, expected_version)?;
+        Ok(self.task_repository.save(task).await?)
+    }
+
+    /// Completes the task and schedules the next occurrence if it repeats.
+    async fn complete(&self, mut task: Task) -> Result<Task, TaskError> {
+        if task.done {
+            return Ok(task);
+        }
+        task.done = true;
+        task.completed_at = Some(Utc::now());
+        let task = self.task_repository.save(task).await?;
+        self.task_events.publish(TaskEventDto::Updated(task.clone()));
+        self.schedule_next_occurrence(&task).await?;
+        Ok(task)
+    }
+
+    /// Checks that the task may be completed and, in cascade mode, completes its open subtasks.
+    async fn prepare_completion(&self, task: &Task, force: bool) -> Result<(), TaskError> {
+        if task.blocked && !force {
+            return Err(TaskError::OpenBlockers(task.id));
+        }
+        let open_subtasks: Vec<Task> = self
+            .find_descendants(task.id)
+            .await?
+            .into_iter()
+            .filter(|subtask| !subtask.done)
+            .collect();
+        if open_subtasks.is_empty() {
+            return Ok(());
+        }
+        match self.parent_completion {
+            ParentCompletion::RequireSubtasksDone => Err(TaskError::OpenSubtasks(task.id)),
+            ParentCompletion::Cascade => {
+                if let Some(blocked_subtask) = open_subtasks.iter().find(|subtask| subtask.blocked && !force) {
+                    return Err(TaskError::OpenBlockers(blocked_subtask.id));
+                }
+                for subtask in open_subtasks {
+                    self.complete(subtask).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn schedule_next_occurrence(&self, task: &Task) -> Result<(), TaskError> {
+        let Some(rule) = task.recurrence_rule.as_deref() else {
+            return Ok(());
+        };
+        let (rule, time_zone) = parse_recurrence(rule, task.recurrence_time_zone.as_deref())?;
+        let series_id = task.series_id.unwrap_or(task.id);
+
+        // the series starts with its first task, which keeps COUNT and UNTIL meaningful; every
+        // occurrence carries that start, so purging the first task does not restart the series
+        let start = task.series_start.unwrap_or_else(|| task.due_at.unwrap_or(task.created_at));
+        let Some(next_due_at) = rule.next_after_completion(start.with_timezone(&time_zone), task.due_at, Utc::now()) else {
+            return Ok(());
+        };
+        let next_due_at = next_due_at.with_timezone(&Utc);
+
+        // reopening and completing again must not schedule the same occurrence twice, not even
+        // when that occurrence has been moved to the trash
+        let series = self
+            .task_repository
+            .find_tasks(TaskFilterAndSortDto {
+                filters: Some(TaskFilterAndSortFiltersDto {
+                    series_id: Some(series_id),
+                    include_deleted: Some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .await?;
+        if series.iter().any(|occurrence| occurrence.due_at == Some(next_due_at)) {
+            return Ok(());
+        }
+
+        let mut next_task = self
+            .task_repository
+            .save(Task {
+                title: task.title.clone(),
+                description: task.description.clone(),
+                priority: task.priority,
+                due_at: Some(next_due_at),
+                parent_id: task.parent_id,
+                recurrence_rule: task.recurrence_rule.clone(),
+                recurrence_time_zone: task.recurrence_time_zone.clone(),
+                series_id: Some(series_id),
+                series_start: Some(start),
+                project_id: task.project_id,
+                workspace_id: task.workspace_id,
+                created_by: task.created_by,
+                ..Default::default()
+            })
+            .await?;
+        for tag in &task.tags {
+            self.task_repository.add_tag(next_task.id, tag.id).await?;
+        }
+        for assignee in &task.assignees {
+            self.task_repository.add_assignee(next_task.id, assignee.id).await?;
+        }
+        next_task.tags = task.tags.clone();
+        next_task.assignees = task.assignees.clone();
+        self.task_events.publish(TaskEventDto::Created(next_task));
+        Ok(())
+    }
+
+    async fn find_descendants(&self, id: i64) -> Result<Vec<Task>, TaskError> {
+        let mut descendants = Vec::new();
+        let mut visited = HashSet::from([id]);
+        let mut pending = vec![id];
+        while let Some(parent_id) = pending.pop() {
+            for child in self.task_repository.find_children(parent_id).await? {
+                if visited.insert(child.id) {
+                    pending.push(child.id);
+                    descendants.push(child);
+                }
+            }
+        }
+        Ok(descendants)
+    }
+
+    async fn apply_bulk_operation(&self, user_id: i64, item: BulkItemDto) -> Result<Task, TaskError> {
+        let BulkItemDto { id, operation, expected_version } = item;
+        match operation {
+            BulkOperationDto::MarkDone { force } => self.mark_done(user_id, id, force, expected_version).await,
+            BulkOperationDto::Reopen => self.mark_undone(user_id, id, expected_version).await,
+            BulkOperationDto::Delete => self.delete_task(user_id, id, expected_version).await,
+            BulkOperationDto::Retitle(title) => self.rename_task(user_id, id, title, expected_version).await,
+            BulkOperationDto::Set(patch) => self.update_task(user_id, id, patch, false, expected_version).await,
+        }
+    }
+
+    /// The tasks a bulk selector matches, read page by page so that a broad filter is refused
+    /// once it matches more than `MAX_BULK_ITEMS` rather than after loading every match.
+    async fn find_selected_tasks(
+        &self,
+        user_id: i64,
+        mut filter_and_sort: TaskFilterAndSortDto,
+    ) -> Result<Vec<Task>, TaskError> {
+        let workspace_ids = self.viewable_workspace_ids(user_id).await?;
+        filter_and_sort.filters.get_or_insert_default().viewable_workspace_ids = Some(workspace_ids);
+        filter_and_sort.limit = Some((MAX_BULK_ITEMS + 1).min(MAX_PAGE_SIZE as usize) as u64);
+        let mut tasks = Vec::new();
+        loop {
+            let page = self.task_repository.find_task_page(filter_and_sort.clone()).await?;
+            tasks.extend(page.items);
+            if tasks.len() > MAX_BULK_ITEMS {
+                return Err(TaskError::TooManyBulkItems { max: MAX_BULK_ITEMS });
+            }
+            match page.next_cursor {
+                Some(cursor) => filter_and_sort.cursor = Some(cursor),
+                None => return Ok(tasks),
+            }
+        }
+    }
+
+    /// The parent has to belong to the same workspace as the task.
+    async fn ensure_valid_parent(&self, id: i64, parent_id: i64, workspace_id: i64) -> Result<(), TaskError> {
+        // walk up from the new parent; reaching the task itself would close a cycle
+        let mut visited = HashSet::new();
+        let mut ancestor_id = Some(parent_id);
+        while let Some(current_id) = ancestor_id {
+            if current_id == id || !visited.insert(current_id) {
+                return Err(TaskError::ParentCycle { id, parent_id });
+            }
+            match self.task_repository.find_by_id(current_id).await? {
+                Some(parent) if current_id == parent_id && parent.workspace_id != workspace_id => {
+                    return Err(TaskError::ParentNotFound(parent_id));
+                }
+                Some(ancestor) => ancestor_id = ancestor.parent_id,
+                None if current_id == parent_id => return Err(TaskError::ParentNotFound(parent_id)),
+                None => break,
+            }
+        }
+        Ok(())
     }
 }
 
 #[async_trait]
 impl TaskServiceTrait for TaskService {
-    async fn create_task(&self, title: &str) -> anyhow::Result<Task> {
+    async fn create_task(&self, user_id: i64, new_task: NewTaskDto) -> Result<Task, TaskError> {
+        let parent = match new_task.parent_id {
+            Some(parent_id) => {
+                let parent = self
+                    .task_repository
+                    .find_by_id(parent_id)
+                    .await?
+                    .ok_or(TaskError::ParentNotFound(parent_id))?;
+                let concealed = TaskError::ParentNotFound(parent_id);
+                self.authorize_in(user_id, parent.workspace_id, WorkspaceAction::ViewTasks, concealed)
+                    .await?;
+                Some(parent)
+            }
+            None => None,
+        };
+        // subtasks go where their parent is, other tasks to the user's first workspace
+        let workspace_id = match new_task.workspace_id.or(parent.as_ref().map(|parent| parent.workspace_id)) {
+            Some(workspace_id) => workspace_id,
+            None => default_workspace(&*self.workspace_repository, user_id).await?,
+        };
+        let concealed = TaskError::WorkspaceNotFound(workspace_id);
+        self.authorize_in(user_id, workspace_id, WorkspaceAction::EditTasks, concealed)
+            .await?;
+        if let Some(parent) = parent
+            && parent.workspace_id != workspace_id
+        {
+            return Err(TaskError::ParentNotFound(parent.id));
+        }
+        self.ensure_project_exists(new_task.project_id, workspace_id).await?;
+        let (recurrence_rule, recurrence_time_zone) =
+            normalize_recurrence(new_task.recurrence_rule, new_task.recurrence_time_zone)?;
         let new_task = Task {
-            title: title.to_string(),
+            title: validate_title(&new_task.title)?,
+            description: normalize_description(new_task.description)?,
+            due_at: new_task.due_at,
+            priority: new_task.priority,
+            parent_id: new_task.parent_id,
+            recurrence_rule,
+            recurrence_time_zone,
+            project_id: new_task.project_id,
+            workspace_id,
+            created_by: new_task.created_by,
             ..Default::default()
         };
-        self.task_repository.save(new_task).await
+        let task = self.task_repository.save(new_task).await?;
+        self.task_events.publish(TaskEventDto::Created(task.clone()));
+        Ok(task)
     }
 
-    async fn mark_done(&self, id: i64) -> anyhow::Result<Option<Task>> {
-        if let Some(mut task) = self.task_repository.find_by_id(id).await? {
-            task.done = true;
-            let updated = self.task_repository.save(task).await?;
-            Ok(Some(updated))
+    async fn update_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        patch: TaskPatchDto,
+        force: bool,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let mut task = self.authorize(user_id, id, WorkspaceAction::EditTasks).await?;
+        check_version(&task, expected_version)?;
+
+        // validate everything before anything is written
+        if let Some(Some(parent_id)) = patch.parent_id
+            && task.parent_id != Some(parent_id)
+        {
+            self.ensure_valid_parent(id, parent_id, task.workspace_id).await?;
+        }
+        if let Some(project_id) = patch.project_id {
+            self.ensure_project_exists(project_id, task.workspace_id).await?;
+        }
+        let recurrence = if patch.recurrence_rule.is_some() || patch.recurrence_time_zone.is_some() {
+            Some(normalize_recurrence(
+                patch.recurrence_rule.unwrap_or_else(|| task.recurrence_rule.clone()),
+                patch.recurrence_time_zone.unwrap_or_else(|| task.recurrence_time_zone.clone()),
+            )?)
         } else {
-            Ok(None)
+            None
+        };
+        let title = patch.title.as_deref().map(validate_title).transpose()?;
+        let description = patch.description.map(normalize_description).transpose()?;
+        let completing = patch.done == Some(true) && !task.done;
+        if completing {
+            self.prepare_completion(&task, force).await?;
+        }
+
+        if let Some(title) = title {
+            task.title = title;
+        }
+        if let Some(description) = description {
+            task.description = description;
+        }
+        if let Some(done) = patch.done
+            && done != task.done
+        {
+            task.done = done;
+            task.completed_at = done.then(Utc::now);
+        }
+        if let Some(priority) = patch.priority {
+            task.priority = priority;
+        }
+        if let Some(due_at) = patch.due_at {
+            task.due_at = due_at;
+        }
+        if let Some(parent_id) = patch.parent_id {
+            task.parent_id = parent_id;
+        }
+        if let Some(project_id) = patch.project_id {
+            task.project_id = project_id;
         }
+        if let Some((recurrence_rule, recurrence_time_zone)) = recurrence {
+            task.recurrence_rule = recurrence_rule;
+            task.recurrence_time_zone = recurrence_time_zone;
+        }
+
+        let task = self.task_repository.save(task).await?;
+        self.task_events.publish(TaskEventDto::Updated(task.clone()));
+        if completing {
+            self.schedule_next_occurrence(&task).await?;
+        }
+        Ok(task)
     }
 
-    async fn rename_task(&self, id: i64, new_title: String) -> anyhow::Result<Option<Task>> {
-        if let Some(mut task) = self.task_repository.find_by_id(id).await? {
-            task.title = new_title;
-            let updated = self.task_repository.save(task).await?;
-            Ok(Some(updated))
-        } else {
-            Ok(None)
+    async fn mark_done(
+        &self,
+        user_id: i64,
+        id: i64,
+        force: bool,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let patch = TaskPatchDto {
+            done: Some(true),
+            ..Default::default()
+        };
+        self.update_task(user_id, id, patch, force, expected_version).await
+    }
+
+    async fn mark_undone(&self, user_id: i64, id: i64, expected_version: Option<i64>) -> Result<Task, TaskError> {
+        let patch = TaskPatchDto {
+            done: Some(false),
+            ..Default::default()
+        };
+        self.update_task(user_id, id, patch, false, expected_version).await
+    }
+
+    async fn rename_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        new_title: String,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let patch = TaskPatchDto {
+            title: Some(new_title),
+            ..Default::default()
+        };
+        self.update_task(user_id, id, patch, false, expected_version).await
+    }
+
+    async fn change_description(
+        &self,
+        user_id: i64,
+        id: i64,
+        description: Option<String>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let patch = TaskPatchDto {
+            description: Some(description),
+            ..Default::default()
+        };
+        self.update_task(user_id, id, patch, false, expected_version).await
+    }
+
+    async fn change_due_date(
+        &self,
+        user_id: i64,
+        id: i64,
+        due_at: Option<DateTime<Utc>>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let patch = TaskPatchDto {
+            due_at: Some(due_at),
+            ..Default::default()
+        };
+        self.update_task(user_id, id, patch, false, expected_version).await
+    }
+
+    async fn change_priority(
+        &self,
+        user_id: i64,
+        id: i64,
+        priority: Priority,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let patch = TaskPatchDto {
+            priority: Some(priority),
+            ..Default::default()
+        };
+        self.update_task(user_id, id, patch, false, expected_version).await
+    }
+
+    async fn change_recurrence(
+        &self,
+        user_id: i64,
+        id: i64,
+        rule: Option<String>,
+        time_zone: Option<String>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let patch = TaskPatchDto {
+            recurrence_rule: Some(rule),
+            recurrence_time_zone: Some(time_zone),
+            ..Default::default()
+        };
+        self.update_task(user_id, id, patch, false, expected_version).await
+    }
+
+    async fn add_tag(
+        &self,
+        user_id: i64,
+        id: i64,
+        tag_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let task = self.authorize(user_id, id, WorkspaceAction::EditTasks).await?;
+        // tags of other workspaces are as good as missing
+        if self.tag_repository.find_by_id(tag_id).await?.is_none_or(|tag| tag.workspace_id != task.workspace_id) {
+            return Err(TaskError::TagNotFound(tag_id));
+        }
+        self.touch(task, expected_version).await?;
+        self.task_repository.add_tag(id, tag_id).await?;
+        self.find_changed_task(id).await
+    }
+
+    async fn remove_tag(
+        &self,
+        user_id: i64,
+        id: i64,
+        tag_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let task = self.authorize(user_id, id, WorkspaceAction::EditTasks).await?;
+        self.touch(task, expected_version).await?;
+        self.task_repository.remove_tag(id, tag_id).await?;
+        self.find_changed_task(id).await
+    }
+
+    async fn assign_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        assignee_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let task = self.authorize(user_id, id, WorkspaceAction::EditTasks).await?;
+        if self.workspace_repository.find_role(task.workspace_id, assignee_id).await?.is_none() {
+            return Err(TaskError::NotAMember(assignee_id));
+        }
+        self.touch(task, expected_version).await?;
+        self.task_repository.add_assignee(id, assignee_id).await?;
+        self.find_changed_task(id).await
+    }
+
+    async fn unassign_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        assignee_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let task = self.authorize(user_id, id, WorkspaceAction::EditTasks).await?;
+        self.touch(task, expected_version).await?;
+        self.task_repository.remove_assignee(id, assignee_id).await?;
+        self.find_changed_task(id).await
+    }
+
+    async fn add_blocker(
+        &self,
+        user_id: i64,
+        id: i64,
+        blocker_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let task = self.authorize(user_id, id, WorkspaceAction::EditTasks).await?;
+        // tasks of other workspaces cannot block, and are not revealed either
+        if self.find_task(blocker_id).await?.workspace_id != task.workspace_id {
+            return Err(TaskError::NotFound(blocker_id));
+        }
+        check_version(&task, expected_version)?;
+        self.task_repository
+            .transaction(Box::new(move |task_repository| {
+                Box::pin(async move {
+                    // concurrent requests could otherwise each add one half of a cycle
+                    task_repository.lock_dependencies().await?;
+                    let graph = DependencyGraph::new(task_repository.find_dependencies().await?);
+                    if graph.would_create_cycle(id, blocker_id) {
+                        return Err(TaskError::DependencyCycle { id, blocker_id }.into());
+                    }
+                    task_repository.save(task).await?;
+                    task_repository.add_blocker(id, blocker_id).await
+                })
+            }))
+            .await?;
+        self.find_changed_task(id).await
+    }
+
+    async fn remove_blocker(
+        &self,
+        user_id: i64,
+        id: i64,
+        blocker_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let task = self.authorize(user_id, id, WorkspaceAction::EditTasks).await?;
+        self.touch(task, expected_version).await?;
+        self.task_repository.remove_blocker(id, blocker_id).await?;
+        self.find_changed_task(id).await
+    }
+
+    async fn get_task(&self, user_id: i64, id: i64) -> Result<Task, TaskError> {
+        self.authorize(user_id, id, WorkspaceAction::ViewTasks).await
+    }
+
+    async fn find_tasks(
+        &self,
+        user_id: i64,
+        mut filter_and_sort: TaskFilterAndSortDto,
+    ) -> Result<TaskPageDto, TaskError> {
+        let workspace_ids = self.viewable_workspace_ids(user_id).await?;
+        filter_and_sort.filters.get_or_insert_default().viewable_workspace_ids = Some(workspace_ids);
+        Ok(self.task_repository.find_task_page(filter_and_sort).await?)
+    }
+
+    async fn find_task_tree(
+        &self,
+        user_id: i64,
+        mut filter_and_sort: TaskFilterAndSortDto,
+    ) -> Result<TaskPageDto, TaskError> {
+        let workspace_ids = self.viewable_workspace_ids(user_id).await?;
+        filter_and_sort.filters.get_or_insert_default().viewable_workspace_ids = Some(workspace_ids);
+        // pages are cut between top-level tasks so that no tree is split across pages
+        filter_and_sort.filters.get_or_insert_default().top_level = Some(true);
+        let mut page = self.task_repository.find_task_page(filter_and_sort).await?;
+        let mut trees = Vec::with_capacity(page.items.len());
+        for root in std::mem::take(&mut page.items) {
+            let mut tasks = self.find_descendants(root.id).await?;
+            tasks.insert(0, root);
+            trees.extend(build_task_tree(tasks));
+        }
+        page.items = trees;
+        Ok(page)
+    }
+
+    async fn find_children(&self, user_id: i64, id: i64) -> Result<Vec<Task>, TaskError> {
+        self.authorize(user_id, id, WorkspaceAction::ViewTasks).await?;
+        Ok(self.task_repository.find_children(id).await?)
+    }
+
+    async fn move_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        parent_id: Option<i64>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let patch = TaskPatchDto {
+            parent_id: Some(parent_id),
+            ..Default::default()
+        };
+        self.update_task(user_id, id, patch, false, expected_version).await
+    }
+
+    async fn change_project(
+        &self,
+        user_id: i64,
+        id: i64,
+        project_id: Option<i64>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError> {
+        let patch = TaskPatchDto {
+            project_id: Some(project_id),
+            ..Default::default()
+        };
+        self.update_task(user_id, id, patch, false, expected_version).await
+    }
+
+    async fn delete_task(&self, user_id: i64, id: i64, expected_version: Option<i64>) -> Result<Task, TaskError> {
+        let mut task = self.authorize(user_id, id, WorkspaceAction::EditTasks).await?;
+        check_version(&task, expected_version)?;
+        if task.deleted_at.is_none() {
+            task.deleted_at = Some(Utc::now());
+            task = self.task_repository.save(task).await?;
+            self.task_events.publish(TaskEventDto::Deleted { id, workspace_id: task.workspace_id });
+        }
+        Ok(task)
+    }
+
+    async fn restore_task(&self, user_id: i64, id: i64, expected_version: Option<i64>) -> Result<Task, TaskError> {
+        let mut task = self.authorize(user_id, id, WorkspaceAction::EditTasks).await?;
+        check_version(&task, expected_version)?;
+        if task.deleted_at.is_some() {
+            task.deleted_at = None;
+            task = self.task_repository.save(task).await?;
+            self.task_events.publish(TaskEventDto::Updated(task.clone()));
+        }
+        Ok(task)
+    }
+
+    async fn find_trashed_tasks(&self, user_id: i64) -> Result<Vec<Task>, TaskError> {
+        let filter_and_sort = TaskFilterAndSortDto {
+            filters: Some(TaskFilterAndSortFiltersDto {
+                deleted: Some(true),
+                viewable_workspace_ids: Some(self.viewable_workspace_ids(user_id).await?),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        Ok(self.task_repository.find_tasks(filter_and_sort).await?)
+    }
+
+    async fn purge_task(&self, user_id: i64, id: i64, expected_version: Option<i64>) -> Result<(), TaskError> {
+        // only tasks that are already in the trash can be removed permanently
+        let task = self.authorize(user_id, id, WorkspaceAction::PurgeTasks).await?;
+        if task.deleted_at.is_none() {
+            return Err(TaskError::NotFound(id));
+        }
+        check_version(&task, expected_version)?;
+        if !self.task_repository.delete_by_id(id).await? {
+            return Err(TaskError::NotFound(id));
+        }
+        self.task_events.publish(TaskEventDto::Deleted { id, workspace_id: task.workspace_id });
+        Ok(())
+    }
+
+    async fn bulk_update(&self, user_id: i64, request: BulkRequestDto) -> Result<Vec<BulkItemResultDto>, TaskError> {
+        let items: Vec<BulkItemDto> = match request {
+            BulkRequestDto::Items(items) => items,
+            BulkRequestDto::Selector { filter_and_sort, operation } => {
+                self.find_selected_tasks(user_id, *filter_and_sort)
+                    .await?
+                    .into_iter()
+                    .map(|task| BulkItemDto {
+                        id: task.id,
+                        operation: operation.clone(),
+                        // earlier operations may cascade to later tasks, so versions are not pinned
+                        expected_version: None,
+                    })
+                    .collect()
+            }
+        };
+        if items.len() > MAX_BULK_ITEMS {
+            return Err(TaskError::TooManyBulkItems { max: MAX_BULK_ITEMS });
+        }
+
+        let results = Arc::new(Mutex::new(Vec::with_capacity(items.len())));
+        let work_results = results.clone();
+        // events are held back until the transaction is committed
+        let events = Arc::new(TaskEventBuffer::default());
+        let service = TaskService { task_events: events.clone(), ..self.clone() };
+        let transaction_result = self
+            .task_repository
+            .transaction(Box::new(move |task_repository| {
+                Box::pin(async move {
+                    // the same rules apply, only the writes go through the transaction
+                    let service = TaskService { task_repository, ..service };
+                    let mut failures = 0;
+                    for item in items {
+                        let id = item.id;
+                        let outcome = match service.apply_bulk_operation(user_id, item).await {
+                            Ok(task) => BulkOutcomeDto::Applied(Box::new(task)),
+                            Err(error) => {
+                                failures += 1;
+                                BulkOutcomeDto::Failed(error)
+                            }
+                        };
+                        work_results
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .push(BulkItemResultDto { id, outcome });
+                    }
+                    if failures > 0 {
+                        anyhow::bail!("{failures} bulk operations failed");
+                    }
+                    Ok(())
+                })
+            }))
+            .await;
+
+        let mut results = std::mem::take(&mut *results.lock().unwrap_or_else(PoisonError::into_inner));
+        let any_failed = results
+            .iter()
+            .any(|result| matches!(result.outcome, BulkOutcomeDto::Failed(_)));
+        match transaction_result {
+            Ok(()) => {
+                for event in events.take() {
+                    self.task_events.publish(event);
+                }
+                Ok(results)
+            }
+            Err(_) if any_failed => {
+                for result in &mut results {
+                    if matches!(result.outcome, BulkOutcomeDto::Applied(_)) {
+                        result.outcome = BulkOutcomeDto::RolledBack;
+                    }
+                }
+                Ok(results)
+            }
+            Err(error) => Err(error.into()),
         }
     }
+}
+
+/// Collects events instead of publishing them.
+#[derive(Default)]
+struct TaskEventBuffer(Mutex<Vec<TaskEventDto>>);
+
+impl TaskEventBuffer {
+    fn take(&self) -> Vec<TaskEventDto> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
 
-    async fn get_task(&self, id: i64) -> anyhow::Result<Option<Task>> {
-        self.task_repository.find_by_id(id).await
+impl TaskEventPublisherTrait for TaskEventBuffer {
+    fn publish(&self, event: TaskEventDto) {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).push(event);
+    }
+}
+
+fn check_version(task: &Task, expected_version: Option<i64>) -> Result<(), TaskError> {
+    match expected_version {
+        Some(expected_version) if expected_version != task.version => Err(TaskError::VersionMismatch(task.id)),
+        _ => Ok(()),
+    }
+}
+
+/// Blank descriptions are stored as absent.
+fn normalize_description(description: Option<String>) -> Result<Option<String>, TaskError> {
+    Ok(description
+        .filter(|description| !description.trim().is_empty())
+        .map(|description| validate_description(&description))
+        .transpose()?)
+}
+
+fn parse_recurrence(rule: &str, time_zone: Option<&str>) -> Result<(RecurrenceRule, Tz), TaskError> {
+    let rule = rule
+        .parse::<RecurrenceRule>()
+        .map_err(|error| TaskError::InvalidRecurrence(error.to_string()))?;
+    let time_zone = match time_zone {
+        Some(time_zone) => time_zone
+            .parse::<Tz>()
+            .map_err(|_| TaskError::InvalidRecurrence(format!("unknown time zone `{time_zone}`")))?,
+        None => Tz::UTC,
+    };
+    Ok((rule, time_zone))
+}
+
+/// Validates a recurrence; a blank rule removes it together with its time zone.
+fn normalize_recurrence(
+    rule: Option<String>,
+    time_zone: Option<String>,
+) -> Result<(Option<String>, Option<String>), TaskError> {
+    let Some(rule) = rule.map(|rule| rule.trim().to_string()).filter(|rule| !rule.is_empty()) else {
+        return Ok((None, None));
+    };
+    let time_zone = time_zone.filter(|time_zone| !time_zone.trim().is_empty());
+    parse_recurrence(&rule, time_zone.as_deref())?;
+    Ok((Some(rule), time_zone))
+}
+
+/// Nests tasks under their parents, keeping the given order among siblings.
+/// Tasks whose parent is not part of the list become roots.
+fn build_task_tree(tasks: Vec<Task>) -> Vec<Task> {
+    let ids: HashSet<i64> = tasks.iter().map(|task| task.id).collect();
+    let mut children_by_parent: HashMap<i64, Vec<Task>> = HashMap::new();
+    let mut roots = Vec::new();
+    for task in tasks {
+        match task.parent_id.filter(|parent_id| ids.contains(parent_id)) {
+            Some(parent_id) => children_by_parent.entry(parent_id).or_default().push(task),
+            None => roots.push(task),
+        }
     }
 
-    async fn find_tasks(&self, filter_and_sort: TaskFilterAndSortDto) -> anyhow::Result<Vec<Task>> {
-        self.task_repository.find_tasks(filter_and_sort).await
+    fn attach_children(task: &mut Task, children_by_parent: &mut HashMap<i64, Vec<Task>>) {
+        if let Some(mut children) = children_by_parent.remove(&task.id) {
+            for child in &mut children {
+                attach_children(child, children_by_parent);
+            }
+            task.children = children;
+        }
+    }
+
+    for root in &mut roots {
+        attach_children(root, &mut children_by_parent);
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::task_error::TaskErrorKind;
+    use crate::task::task_repository_trait::TransactionWork;
+    use crate::workspace::workspace_dto::WorkspaceMembershipDto;
+    use domain::project::Project;
+    use domain::tag::Tag;
+    use domain::workspace::{Workspace, WorkspaceMember, WorkspaceRole};
+
+    const WORKSPACE_ID: i64 = 1;
+    const OTHER_WORKSPACE_ID: i64 = 2;
+    const MEMBER_ID: i64 = 10;
+    const VIEWER_ID: i64 = 11;
+    const OUTSIDER_ID: i64 = 12;
+    const TASK_ID: i64 = 100;
+    const OTHER_TASK_ID: i64 = 101;
+    const OTHER_TAG_ID: i64 = 200;
+    const OTHER_PROJECT_ID: i64 = 300;
+
+    /// `(workspace_id, user_id, role)`; the member belongs to both workspaces.
+    const MEMBERSHIPS: [(i64, i64, WorkspaceRole); 3] = [
+        (WORKSPACE_ID, MEMBER_ID, WorkspaceRole::Member),
+        (WORKSPACE_ID, VIEWER_ID, WorkspaceRole::Viewer),
+        (OTHER_WORKSPACE_ID, MEMBER_ID, WorkspaceRole::Member),
+    ];
+
+    /// Keeps tasks in a map and honours only the filters the service sets itself.
+    struct InMemoryTaskRepository {
+        tasks: Mutex<HashMap<i64, Task>>,
+    }
+
+    impl InMemoryTaskRepository {
+        fn matching(&self, filter_and_sort: &TaskFilterAndSortDto) -> Vec<Task> {
+            let filters = filter_and_sort.filters.clone().unwrap_or_default();
+            let mut tasks: Vec<Task> = self
+                .tasks
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .values()
+                .filter(|task| {
+                    let trashed = task.deleted_at.is_some();
+                    filters.include_deleted == Some(true) || trashed == filters.deleted.unwrap_or(false)
+                })
+                .filter(|task| {
+                    filters.viewable_workspace_ids.as_ref().is_none_or(|ids| ids.contains(&task.workspace_id))
+                })
+                .filter(|task| {
+                    filters.series_id.is_none_or(|id| task.id == id || task.series_id == Some(id))
+                })
+                .cloned()
+                .collect();
+            tasks.sort_by_key(|task| task.id);
+            tasks
+        }
+    }
+
+    #[async_trait]
+    impl TaskRepositoryTrait for InMemoryTaskRepository {
+        async fn save(&self, mut task: Task) -> anyhow::Result<Task> {
+            let mut tasks = self.tasks.lock().unwrap_or_else(PoisonError::into_inner);
+            if task.id == 0 {
+                task.id = tasks.keys().max().map_or(1, |id| id + 1);
+            }
+            task.version += 1;
+            tasks.insert(task.id, task.clone());
+            Ok(task)
+        }
+
+        async fn find_tasks(&self, filter_and_sort: TaskFilterAndSortDto) -> anyhow::Result<Vec<Task>> {
+            Ok(self.matching(&filter_and_sort))
+        }
+
+        /// Pages by id; the cursor is the id of the last task of the previous page.
+        async fn find_task_page(&self, filter_and_sort: TaskFilterAndSortDto) -> anyhow::Result<TaskPageDto> {
+            let after: i64 = filter_and_sort.cursor.as_deref().map_or(Ok(0), str::parse)?;
+            let page_size = filter_and_sort.page_size() as usize;
+            let mut items = self.matching(&filter_and_sort);
+            items.retain(|task| task.id > after);
+            let next_cursor = (items.len() > page_size).then(|| items[page_size - 1].id.to_string());
+            items.truncate(page_size);
+            Ok(TaskPageDto { total_estimate: items.len() as u64, items, next_cursor, ..Default::default() })
+        }
+
+        async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Task>> {
+            Ok(self.tasks.lock().unwrap_or_else(PoisonError::into_inner).get(&id).cloned())
+        }
+
+        async fn find_children(&self, parent_id: i64) -> anyhow::Result<Vec<Task>> {
+            let tasks = self.tasks.lock().unwrap_or_else(PoisonError::into_inner);
+            Ok(tasks.values().filter(|task| task.parent_id == Some(parent_id)).cloned().collect())
+        }
+
+        async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool> {
+            Ok(self.tasks.lock().unwrap_or_else(PoisonError::into_inner).remove(&id).is_some())
+        }
+
+        async fn add_tag(&self, _: i64, _: i64) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn remove_tag(&self, _: i64, _: i64) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn add_assignee(&self, _: i64, _: i64) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn remove_assignee(&self, _: i64, _: i64) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn find_dependencies(&self) -> anyhow::Result<Vec<(i64, i64)>> {
+            Ok(Vec::new())
+        }
+
+        async fn lock_dependencies(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn add_blocker(&self, _: i64, _: i64) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn remove_blocker(&self, _: i64, _: i64) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn transaction(&self, _: TransactionWork) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Knows a single tag, which belongs to the other workspace.
+    struct FixedTagRepository;
+
+    #[async_trait]
+    impl TagRepositoryTrait for FixedTagRepository {
+        async fn save(&self, tag: Tag) -> anyhow::Result<Tag> {
+            Ok(tag)
+        }
+
+        async fn find_by_workspace(&self, _: i64) -> anyhow::Result<Vec<Tag>> {
+            Ok(Vec::new())
+        }
+
+        async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Tag>> {
+            Ok((id == OTHER_TAG_ID).then(|| Tag { id, workspace_id: OTHER_WORKSPACE_ID, ..Default::default() }))
+        }
+
+        async fn delete_by_id(&self, _: i64) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+    }
+
+    /// Knows a single project, which belongs to the other workspace.
+    struct FixedProjectRepository;
+
+    #[async_trait]
+    impl ProjectRepositoryTrait for FixedProjectRepository {
+        async fn save(&self, project: Project) -> anyhow::Result<Project> {
+            Ok(project)
+        }
+
+        async fn find_by_workspace(&self, _: i64, _: bool) -> anyhow::Result<Vec<Project>> {
+            Ok(Vec::new())
+        }
+
+        async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Project>> {
+            let project = Project { id, workspace_id: OTHER_WORKSPACE_ID, ..Default::default() };
+            Ok((id == OTHER_PROJECT_ID).then_some(project))
+        }
+
+        async fn delete_by_id(&self, _: i64) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+    }
+
+    /// Knows the `MEMBERSHIPS` and refuses to change them.
+    struct FixedWorkspaceRepository;
+
+    #[async_trait]
+    impl WorkspaceRepositoryTrait for FixedWorkspaceRepository {
+        async fn create(&self, workspace: Workspace, _: i64) -> anyhow::Result<Workspace> {
+            Ok(workspace)
+        }
+
+        async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Workspace>> {
+            let exists = MEMBERSHIPS.iter().any(|(workspace_id, _, _)| *workspace_id == id);
+            Ok(exists.then(|| Workspace { id, ..Default::default() }))
+        }
+
+        async fn find_memberships(&self, user_id: i64) -> anyhow::Result<Vec<WorkspaceMembershipDto>> {
+            Ok(MEMBERSHIPS
+                .iter()
+                .filter(|(_, member_id, _)| *member_id == user_id)
+                .map(|(workspace_id, _, role)| WorkspaceMembershipDto {
+                    workspace: Workspace { id: *workspace_id, ..Default::default() },
+                    role: *role,
+                })
+                .collect())
+        }
+
+        async fn find_role(&self, workspace_id: i64, user_id: i64) -> anyhow::Result<Option<WorkspaceRole>> {
+            Ok(MEMBERSHIPS
+                .iter()
+                .find(|(id, member_id, _)| *id == workspace_id && *member_id == user_id)
+                .map(|(_, _, role)| *role))
+        }
+
+        async fn find_members(&self, _: i64) -> anyhow::Result<Vec<WorkspaceMember>> {
+            Ok(Vec::new())
+        }
+
+        async fn save_member(&self, member: WorkspaceMember) -> anyhow::Result<WorkspaceMember> {
+            Ok(member)
+        }
+
+        async fn delete_member(&self, _: i64, _: i64) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+    }
+
+    struct IgnoredTaskEvents;
+
+    impl TaskEventPublisherTrait for IgnoredTaskEvents {
+        fn publish(&self, _: TaskEventDto) {}
+    }
+
+    fn task(id: i64, workspace_id: i64) -> Task {
+        Task { id, title: "Water the plants".to_string(), workspace_id, ..Default::default() }
+    }
+
+    /// A service with `TASK_ID` in `WORKSPACE_ID` and `OTHER_TASK_ID` in `OTHER_WORKSPACE_ID`.
+    fn service() -> Arc<dyn TaskServiceTrait> {
+        service_with(vec![task(TASK_ID, WORKSPACE_ID), task(OTHER_TASK_ID, OTHER_WORKSPACE_ID)])
+    }
+
+    fn service_with(tasks: Vec<Task>) -> Arc<dyn TaskServiceTrait> {
+        let tasks = tasks.into_iter().map(|task| (task.id, task)).collect();
+        Arc::new(TaskService::new(
+            Arc::new(InMemoryTaskRepository { tasks: Mutex::new(tasks) }),
+            Arc::new(FixedTagRepository),
+            Arc::new(FixedProjectRepository),
+            Arc::new(FixedWorkspaceRepository),
+            Arc::new(IgnoredTaskEvents),
+            ParentCompletion::default(),
+        ))
+    }
+
+    fn retitle() -> TaskPatchDto {
+        TaskPatchDto { title: Some("Water the cactus".to_string()), ..Default::default() }
+    }
+
+    fn assert_forbidden(result: Result<Task, TaskError>) {
+        assert!(matches!(result, Err(TaskError::Forbidden(WorkspaceAction::EditTasks))), "{result:?}");
+        assert_eq!(result.unwrap_err().kind(), TaskErrorKind::Forbidden);
+    }
+
+    #[tokio::test]
+    async fn viewers_may_read_but_not_change_tasks() {
+        let service = service();
+
+        assert_eq!(service.get_task(VIEWER_ID, TASK_ID).await.unwrap().title, "Water the plants");
+        assert_forbidden(service.update_task(VIEWER_ID, TASK_ID, retitle(), false, None).await);
+        assert_forbidden(service.mark_done(VIEWER_ID, TASK_ID, false, None).await);
+        assert_forbidden(service.rename_task(VIEWER_ID, TASK_ID, "Water the cactus".to_string(), None).await);
+
+        let task = service.get_task(MEMBER_ID, TASK_ID).await.unwrap();
+        assert_eq!(task.title, "Water the plants");
+        assert!(!task.done);
+    }
+
+    #[tokio::test]
+    async fn members_may_change_tasks() {
+        let service = service();
+
+        let task = service.rename_task(MEMBER_ID, TASK_ID, "Water the cactus".to_string(), None).await.unwrap();
+        assert_eq!(task.title, "Water the cactus");
+        let task = service.mark_done(MEMBER_ID, TASK_ID, false, Some(task.version)).await.unwrap();
+        assert!(task.done);
+        let task = service.update_task(MEMBER_ID, TASK_ID, retitle(), false, Some(task.version)).await.unwrap();
+        assert_eq!(task.title, "Water the cactus");
+    }
+
+    #[tokio::test]
+    async fn tasks_are_not_found_for_non_members() {
+        let service = service();
+
+        let result = service.get_task(OUTSIDER_ID, TASK_ID).await;
+        assert!(matches!(result, Err(TaskError::NotFound(TASK_ID))), "{result:?}");
+        assert_eq!(result.unwrap_err().kind(), TaskErrorKind::NotFound);
+        // writes reveal no more than reads
+        let result = service.mark_done(OUTSIDER_ID, TASK_ID, false, None).await;
+        assert!(matches!(result, Err(TaskError::NotFound(TASK_ID))), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn listings_only_contain_tasks_the_policy_lets_the_user_view() {
+        let service = service();
+        let listed = async |user_id| {
+            let page = service.find_tasks(user_id, TaskFilterAndSortDto::default()).await.unwrap();
+            page.items.into_iter().map(|task| task.id).collect::<Vec<i64>>()
+        };
+
+        assert_eq!(listed(MEMBER_ID).await, [TASK_ID, OTHER_TASK_ID]);
+        assert_eq!(listed(VIEWER_ID).await, [TASK_ID]);
+        assert!(listed(OUTSIDER_ID).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn tags_and_projects_of_another_workspace_are_not_found() {
+        let service = service();
+
+        let result = service.add_tag(MEMBER_ID, TASK_ID, OTHER_TAG_ID, None).await;
+        assert!(matches!(result, Err(TaskError::TagNotFound(OTHER_TAG_ID))), "{result:?}");
+        let result = service.change_project(MEMBER_ID, TASK_ID, Some(OTHER_PROJECT_ID), None).await;
+        assert!(matches!(result, Err(TaskError::ProjectNotFound(OTHER_PROJECT_ID))), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn bulk_selectors_matching_too_many_tasks_are_refused() {
+        let tasks = (1..=2 * MAX_BULK_ITEMS as i64).map(|id| task(id, WORKSPACE_ID)).collect();
+        let service = service_with(tasks);
+
+        let request = BulkRequestDto::Selector {
+            filter_and_sort: Box::default(),
+            operation: BulkOperationDto::MarkDone { force: false },
+        };
+        let result = service.bulk_update(MEMBER_ID, request).await;
+        assert!(matches!(result, Err(TaskError::TooManyBulkItems { max: MAX_BULK_ITEMS })));
+    }
+
+    fn daily(id: i64, rule: &str, due_at: DateTime<Utc>) -> Task {
+        Task { recurrence_rule: Some(rule.to_string()), due_at: Some(due_at), ..task(id, WORKSPACE_ID) }
+    }
+
+    /// The ids of all tasks of the workspace, trashed ones included.
+    async fn all_task_ids(service: &Arc<dyn TaskServiceTrait>) -> Vec<i64> {
+        let page = service.find_tasks(MEMBER_ID, TaskFilterAndSortDto::default()).await.unwrap();
+        let trashed = service.find_trashed_tasks(MEMBER_ID).await.unwrap();
+        let mut ids: Vec<i64> = page.items.into_iter().chain(trashed).map(|task| task.id).collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn purging_the_first_task_does_not_restart_the_series() {
+        let start = Utc::now() + chrono::Duration::days(10);
+        // the first of the two occurrences has been purged, the second one is all that is left
+        let second = Task {
+            series_id: Some(1),
+            series_start: Some(start),
+            ..daily(2, "FREQ=DAILY;COUNT=2", start + chrono::Duration::days(1))
+        };
+        let service = service_with(vec![second]);
+
+        service.mark_done(MEMBER_ID, 2, false, None).await.unwrap();
+
+        assert_eq!(all_task_ids(&service).await, [2]);
+    }
+
+    #[tokio::test]
+    async fn occurrences_in_the_trash_are_not_scheduled_again() {
+        let service = service_with(vec![daily(1, "FREQ=DAILY", Utc::now() + chrono::Duration::days(10))]);
+
+        service.mark_done(MEMBER_ID, 1, false, None).await.unwrap();
+        assert_eq!(all_task_ids(&service).await, [1, 2]);
+        let next = service.get_task(MEMBER_ID, 2).await.unwrap();
+        assert_eq!(next.series_start, Some(service.get_task(MEMBER_ID, 1).await.unwrap().due_at.unwrap()));
+        service.delete_task(MEMBER_ID, 2, None).await.unwrap();
+        service.mark_undone(MEMBER_ID, 1, None).await.unwrap();
+        service.mark_done(MEMBER_ID, 1, false, None).await.unwrap();
+
+        assert_eq!(all_task_ids(&service).await, [1, 2]);
     }
 }
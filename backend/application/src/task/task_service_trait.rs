@@ -1,13 +1,149 @@
-use domain::task::Task;
+use domain::task::{Priority, Task};
 use async_trait::async_trait;
-use anyhow::Result;
+use chrono::{DateTime, Utc};
+use crate::task::bulk_task_dto::{BulkItemResultDto, BulkRequestDto};
+use crate::task::new_task_dto::NewTaskDto;
+use crate::task::task_error::TaskError;
 use crate::task::task_filter_and_sort_dto::TaskFilterAndSortDto;
+use crate::task::task_page_dto::TaskPageDto;
+use crate::task::task_patch_dto::TaskPatchDto;
 
+/// Operations on a missing task fail with `TaskError::NotFound`. Writes taking an
+/// `expected_version` fail with `TaskError::VersionMismatch` when the task has been changed
+/// since that version was read.
+///
+/// `user_id` is the signed-in user, whose role in the task's workspace is checked against the
+/// workspace policy: tasks of workspaces they do not belong to are not found, and actions their
+/// role does not allow fail with `TaskError::Forbidden`.
 #[async_trait]
 pub trait TaskServiceTrait: Send + Sync {
-    async fn create_task(&self, title: &str) -> Result<Task>;
-    async fn mark_done(&self, id: i64) -> Result<Option<Task>>;
-    async fn rename_task(&self, id: i64, new_title: String) -> Result<Option<Task>>;
-    async fn get_task(&self, id: i64) -> Result<Option<Task>>;
-    async fn find_tasks(&self, filter_and_sort: TaskFilterAndSortDto) -> Result<Vec<Task>>;
+    async fn create_task(&self, user_id: i64, new_task: NewTaskDto) -> Result<Task, TaskError>;
+    /// Validates and applies the given fields in one save. Completing a task with open
+    /// blockers is refused unless `force` is set.
+    async fn update_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        patch: TaskPatchDto,
+        force: bool,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    /// Completes the task. Open blockers prevent this unless `force` is set.
+    async fn mark_done(
+        &self,
+        user_id: i64,
+        id: i64,
+        force: bool,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn mark_undone(&self, user_id: i64, id: i64, expected_version: Option<i64>) -> Result<Task, TaskError>;
+    async fn rename_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        new_title: String,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn change_description(
+        &self,
+        user_id: i64,
+        id: i64,
+        description: Option<String>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn change_due_date(
+        &self,
+        user_id: i64,
+        id: i64,
+        due_at: Option<DateTime<Utc>>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn change_priority(
+        &self,
+        user_id: i64,
+        id: i64,
+        priority: Priority,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn change_recurrence(
+        &self,
+        user_id: i64,
+        id: i64,
+        rule: Option<String>,
+        time_zone: Option<String>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn add_tag(
+        &self,
+        user_id: i64,
+        id: i64,
+        tag_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn remove_tag(
+        &self,
+        user_id: i64,
+        id: i64,
+        tag_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn assign_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        assignee_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn unassign_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        assignee_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn add_blocker(
+        &self,
+        user_id: i64,
+        id: i64,
+        blocker_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn remove_blocker(
+        &self,
+        user_id: i64,
+        id: i64,
+        blocker_id: i64,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn get_task(&self, user_id: i64, id: i64) -> Result<Task, TaskError>;
+    async fn find_tasks(&self, user_id: i64, filter_and_sort: TaskFilterAndSortDto) -> Result<TaskPageDto, TaskError>;
+    /// Pages through top-level tasks, each carrying all of its subtasks.
+    async fn find_task_tree(
+        &self,
+        user_id: i64,
+        filter_and_sort: TaskFilterAndSortDto,
+    ) -> Result<TaskPageDto, TaskError>;
+    async fn find_children(&self, user_id: i64, id: i64) -> Result<Vec<Task>, TaskError>;
+    async fn move_task(
+        &self,
+        user_id: i64,
+        id: i64,
+        parent_id: Option<i64>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn change_project(
+        &self,
+        user_id: i64,
+        id: i64,
+        project_id: Option<i64>,
+        expected_version: Option<i64>,
+    ) -> Result<Task, TaskError>;
+    async fn delete_task(&self, user_id: i64, id: i64, expected_version: Option<i64>) -> Result<Task, TaskError>;
+    async fn restore_task(&self, user_id: i64, id: i64, expected_version: Option<i64>) -> Result<Task, TaskError>;
+    async fn find_trashed_tasks(&self, user_id: i64) -> Result<Vec<Task>, TaskError>;
+    /// Only tasks in the trash can be purged, others are reported as not found.
+    async fn purge_task(&self, user_id: i64, id: i64, expected_version: Option<i64>) -> Result<(), TaskError>;
+    /// Applies all operations in one transaction. If any of them fails, none is kept and the
+    /// others are reported as rolled back.
+    async fn bulk_update(&self, user_id: i64, request: BulkRequestDto) -> Result<Vec<BulkItemResultDto>, TaskError>;
 }
@@ -0,0 +1,6 @@
+pub mod webhook_service;
+pub mod webhook_repository_trait;
+pub mod webhook_service_trait;
+pub mod webhook_sender_trait;
+pub mod webhook_dto;
+pub mod webhook_error;
\ No newline at end of file
@@ -0,0 +1,19 @@
+use domain::webhook::WebhookEvent;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NewWebhookDto {
+    pub workspace_id: i64,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub secret: String,
+    pub active: bool,
+}
+
+/// Replaces a webhook's configuration; the secret is kept when none is given.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpdateWebhookDto {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub secret: Option<String>,
+    pub active: bool,
+}
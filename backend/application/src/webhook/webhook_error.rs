@@ -0,0 +1,47 @@
+use crate::workspace::workspace_policy::WorkspaceAction;
+use std::fmt;
+
+/// Webhook changes that are rejected before they are stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebhookError {
+    /// The user is no member of the workspace the webhook is to be registered in.
+    WorkspaceNotFound(i64),
+    /// The user's role in the webhook's workspace does not allow the action.
+    Forbidden(WorkspaceAction),
+    InvalidUrl(String),
+    UnresolvableHost(String),
+    /// The host resolves to a loopback, private or link-local address.
+    NonPublicAddress(String),
+    BlankSecret,
+}
+
+impl WebhookError {
+    /// Stable kebab-case identifier of the variant for clients that branch on the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            WebhookError::WorkspaceNotFound(_) => "workspace-not-found",
+            WebhookError::Forbidden(_) => "forbidden",
+            WebhookError::InvalidUrl(_) => "invalid-webhook-url",
+            WebhookError::UnresolvableHost(_) => "unresolvable-webhook-host",
+            WebhookError::NonPublicAddress(_) => "non-public-webhook-address",
+            WebhookError::BlankSecret => "blank-webhook-secret",
+        }
+    }
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookError::WorkspaceNotFound(workspace_id) => write!(f, "workspace {workspace_id} does not exist"),
+            WebhookError::Forbidden(action) => write!(f, "your role in the workspace does not allow you to {action}"),
+            WebhookError::InvalidUrl(url) => write!(f, "{url:?} is not an absolute http or https URL"),
+            WebhookError::UnresolvableHost(url) => write!(f, "the host of {url:?} cannot be resolved"),
+            WebhookError::NonPublicAddress(url) => {
+                write!(f, "{url:?} points to a loopback, private or link-local address")
+            }
+            WebhookError::BlankSecret => write!(f, "the webhook secret must not be blank"),
+        }
+    }
+}
+
+impl std::error::Error for WebhookError {}
@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::webhook::{Webhook, WebhookDelivery};
+
+#[async_trait]
+pub trait WebhookRepositoryTrait: Send + Sync {
+    async fn save(&self, webhook: Webhook) -> anyhow::Result<Webhook>;
+    async fn find_by_workspace(&self, workspace_id: i64) -> anyhow::Result<Vec<Webhook>>;
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Webhook>>;
+    /// Deletes the webhook along with its delivery log.
+    async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool>;
+    async fn save_delivery(&self, delivery: WebhookDelivery) -> anyhow::Result<WebhookDelivery>;
+    async fn find_delivery(&self, id: i64) -> anyhow::Result<Option<WebhookDelivery>>;
+    /// Newest first.
+    async fn find_deliveries(&self, webhook_id: i64, limit: u64) -> anyhow::Result<Vec<WebhookDelivery>>;
+    /// Pending deliveries whose next attempt is due at `now`, oldest first.
+    async fn find_due_deliveries(&self, now: DateTime<Utc>, limit: u64) -> anyhow::Result<Vec<WebhookDelivery>>;
+}
@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+use domain::webhook::{Webhook, WebhookDelivery};
+use std::net::IpAddr;
+
+#[async_trait]
+pub trait WebhookSenderTrait: Send + Sync {
+    /// POSTs the delivery's payload, signed with the webhook's secret, and returns the response status.
+    /// Fails when the receiver cannot be reached or does not answer in time.
+    async fn send(&self, webhook: &Webhook, delivery: &WebhookDelivery) -> anyhow::Result<u16>;
+
+    /// Addresses the host of the URL resolves to, or the host itself when it is an IP address.
+    async fn resolve(&self, url: &str) -> anyhow::Result<Vec<IpAddr>>;
+}
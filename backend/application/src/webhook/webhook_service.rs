@@ -0,0 +1,380 @@
+use crate::webhook::webhook_dto::{NewWebhookDto, UpdateWebhookDto};
+use crate::webhook::webhook_error::WebhookError;
+use crate::webhook::webhook_repository_trait::WebhookRepositoryTrait;
+use crate::webhook::webhook_sender_trait::WebhookSenderTrait;
+use crate::webhook::webhook_service_trait::WebhookServiceTrait;
+use crate::workspace::workspace_policy::{decide, Decision, WorkspaceAction};
+use crate::workspace::workspace_repository_trait::WorkspaceRepositoryTrait;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use domain::webhook::{DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Number of deliveries listed per webhook.
+const DELIVERY_LOG_LIMIT: u64 = 100;
+/// Number of due deliveries attempted per round.
+const DELIVERY_BATCH_SIZE: u64 = 50;
+
+/// When failed deliveries are retried: after `initial_backoff`, doubling up to `max_backoff`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Attempts including the first one, after which a delivery has failed.
+    pub max_attempts: i32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 8,
+            initial_backoff: Duration::from_secs(30),
+            max_backoff: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before the attempt that follows attempt number `attempts`.
+    pub fn backoff(&self, attempts: i32) -> Duration {
+        let doublings = attempts.saturating_sub(1).clamp(0, 30) as u32;
+        self.initial_backoff.saturating_mul(1 << doublings).min(self.max_backoff)
+    }
+}
+
+#[derive(Clone)]
+pub struct WebhookService {
+    webhook_repository: Arc<dyn WebhookRepositoryTrait>,
+    workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+    webhook_sender: Arc<dyn WebhookSenderTrait>,
+    retry_policy: RetryPolicy,
+}
+
+impl WebhookService {
+    pub fn new(
+        webhook_repository: Arc<dyn WebhookRepositoryTrait>,
+        workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+        webhook_sender: Arc<dyn WebhookSenderTrait>,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        Self { webhook_repository, workspace_repository, webhook_sender, retry_policy }
+    }
+
+    /// Checks that the user may manage the webhooks of the workspace, answering `Ok(false)` to
+    /// non-members, from whom the workspace and its webhooks are concealed.
+    async fn authorize_in(&self, user_id: i64, workspace_id: i64) -> anyhow::Result<bool> {
+        let role = self.workspace_repository.find_role(workspace_id, user_id).await?;
+        match decide(role, WorkspaceAction::ManageWebhooks) {
+            Decision::Allow => Ok(true),
+            Decision::Deny => Err(WebhookError::Forbidden(WorkspaceAction::ManageWebhooks).into()),
+            Decision::Conceal => Ok(false),
+        }
+    }
+
+    /// Loads the webhook if the user may manage the webhooks of its workspace.
+    async fn authorize(&self, user_id: i64, id: i64) -> anyhow::Result<Option<Webhook>> {
+        match self.webhook_repository.find_by_id(id).await? {
+            Some(webhook) if self.authorize_in(user_id, webhook.workspace_id).await? => Ok(Some(webhook)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Rejects URLs whose host resolves to an address inside our own network, so that webhooks
+    /// cannot be used to reach internal services.
+    async fn ensure_public(&self, url: &str) -> Result<(), WebhookError> {
+        let addresses = self
+            .webhook_sender
+            .resolve(url)
+            .await
+            .map_err(|_| WebhookError::UnresolvableHost(url.to_string()))?;
+        if addresses.is_empty() || !addresses.into_iter().all(is_public_address) {
+            return Err(WebhookError::NonPublicAddress(url.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Sends the delivery once and records the outcome, scheduling a retry unless it settled.
+    async fn attempt(&self, webhook: &Webhook, mut delivery: WebhookDelivery, now: DateTime<Utc>) -> anyhow::Result<WebhookDelivery> {
+        delivery.attempts += 1;
+        delivery.next_attempt_at = None;
+        // the host may resolve differently than when the webhook was registered
+        let outcome = match self.ensure_public(&webhook.url).await {
+            Ok(()) => self.webhook_sender.send(webhook, &delivery).await,
+            Err(error) => Err(error.into()),
+        };
+        match outcome {
+            Ok(status) if (200..300).contains(&status) => {
+                delivery.status = DeliveryStatus::Succeeded;
+                delivery.response_status = Some(status.into());
+                delivery.error = None;
+                delivery.delivered_at = Some(now);
+            }
+            outcome => {
+                (delivery.response_status, delivery.error) = match outcome {
+                    Ok(status) => (Some(status.into()), Some(format!("the receiver responded with status {status}"))),
+                    Err(error) => (None, Some(format!("{error:#}"))),
+                };
+                if delivery.attempts >= self.retry_policy.max_attempts {
+                    delivery.status = DeliveryStatus::Failed;
+                } else {
+                    let backoff = TimeDelta::from_std(self.retry_policy.backoff(delivery.attempts))?;
+                    delivery.next_attempt_at = Some(now + backoff);
+                }
+            }
+        }
+        self.webhook_repository.save_delivery(delivery).await
+    }
+}
+
+/// Whether the address is reachable from the internet, as opposed to loopback, private (RFC 1918
+/// and unique local), shared (carrier-grade NAT), link-local, reserved, benchmarking, unspecified,
+/// broadcast or multicast addresses. IPv6 addresses that embed an IPv4 address, mapped, compatible,
+/// NAT64 or 6to4, are judged by that address.
+pub fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => {
+            let [a, b, c, _] = address.octets();
+            !(address.is_loopback()
+                || address.is_private()
+                || address.is_link_local()
+                || address.is_multicast()
+                // 0.0.0.0/8 "this network", unspecified included
+                || a == 0
+                // 100.64.0.0/10 shared address space, where some clouds serve instance metadata
+                || (a == 100 && b & 0xc0 == 64)
+                // 192.0.0.0/24 IETF protocol assignments
+                || (a == 192 && b == 0 && c == 0)
+                // 198.18.0.0/15 benchmarking
+                || (a == 198 && b & 0xfe == 18)
+                // 240.0.0.0/4 reserved, broadcast included
+                || a >= 240)
+        }
+        IpAddr::V6(address) => match embedded_ipv4(address) {
+            Some(embedded) => is_public_address(IpAddr::V4(embedded)),
+            None => {
+                !(address.is_loopback()
+                    || address.is_unique_local()
+                    || address.is_unicast_link_local()
+                    || address.is_unspecified()
+                    || address.is_multicast())
+            }
+        },
+    }
+}
+
+/// The IPv4 address that IPv4-mapped (`::ffff:0:0/96`), IPv4-compatible (`::/96`), NAT64
+/// (`64:ff9b::/96`) and 6to4 (`2002::/16`) addresses lead to.
+fn embedded_ipv4(address: Ipv6Addr) -> Option<Ipv4Addr> {
+    let join = |high: u16, low: u16| Ipv4Addr::from((u32::from(high) << 16) | u32::from(low));
+    match address.segments() {
+        [0x64, 0xff9b, 0, 0, 0, 0, high, low] => Some(join(high, low)),
+        [0x2002, high, low, ..] => Some(join(high, low)),
+        // mapped and compatible addresses, `::` and `::1` included
+        _ => address.to_ipv4(),
+    }
+}
+
+fn validate(url: &str, secret: Option<&str>) -> Result<(), WebhookError> {
+    let host = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"));
+    if host.is_none_or(|host| host.is_empty() || host.starts_with('/')) || url.chars().any(char::is_whitespace) {
+        return Err(WebhookError::InvalidUrl(url.to_string()));
+    }
+    if secret.is_some_and(|secret| secret.trim().is_empty()) {
+        return Err(WebhookError::BlankSecret);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl WebhookServiceTrait for WebhookService {
+    async fn create_webhook(&self, user_id: i64, webhook: NewWebhookDto) -> anyhow::Result<Webhook> {
+        if !self.authorize_in(user_id, webhook.workspace_id).await? {
+            return Err(WebhookError::WorkspaceNotFound(webhook.workspace_id).into());
+        }
+        validate(&webhook.url, Some(&webhook.secret))?;
+        self.ensure_public(&webhook.url).await?;
+        let new_webhook = Webhook {
+            workspace_id: webhook.workspace_id,
+            url: webhook.url,
+            events: webhook.events,
+            secret: webhook.secret,
+            active: webhook.active,
+            ..Default::default()
+        };
+        self.webhook_repository.save(new_webhook).await
+    }
+
+    async fn update_webhook(&self, user_id: i64, id: i64, update: UpdateWebhookDto) -> anyhow::Result<Option<Webhook>> {
+        let Some(mut webhook) = self.authorize(user_id, id).await? else {
+            return Ok(None);
+        };
+        validate(&update.url, update.secret.as_deref())?;
+        self.ensure_public(&update.url).await?;
+        webhook.url = update.url;
+        webhook.events = update.events;
+        if let Some(secret) = update.secret {
+            webhook.secret = secret;
+        }
+        webhook.active = update.active;
+        let updated = self.webhook_repository.save(webhook).await?;
+        Ok(Some(updated))
+    }
+
+    async fn delete_webhook(&self, user_id: i64, id: i64) -> anyhow::Result<bool> {
+        if self.authorize(user_id, id).await?.is_none() {
+            return Ok(false);
+        }
+        self.webhook_repository.delete_by_id(id).await
+    }
+
+    async fn get_webhook(&self, user_id: i64, id: i64) -> anyhow::Result<Option<Webhook>> {
+        self.authorize(user_id, id).await
+    }
+
+    async fn find_webhooks(&self, user_id: i64) -> anyhow::Result<Vec<Webhook>> {
+        let mut webhooks = Vec::new();
+        for membership in self.workspace_repository.find_memberships(user_id).await? {
+            if decide(Some(membership.role), WorkspaceAction::ManageWebhooks) == Decision::Allow {
+                webhooks.extend(self.webhook_repository.find_by_workspace(membership.workspace.id).await?);
+            }
+        }
+        webhooks.sort_by_key(|webhook| webhook.id);
+        Ok(webhooks)
+    }
+
+    async fn find_deliveries(&self, user_id: i64, webhook_id: i64) -> anyhow::Result<Option<Vec<WebhookDelivery>>> {
+        if self.authorize(user_id, webhook_id).await?.is_none() {
+            return Ok(None);
+        }
+        let deliveries = self.webhook_repository.find_deliveries(webhook_id, DELIVERY_LOG_LIMIT).await?;
+        Ok(Some(deliveries))
+    }
+
+    async fn redeliver(
+        &self,
+        user_id: i64,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> anyhow::Result<Option<WebhookDelivery>> {
+        let Some(webhook) = self.authorize(user_id, webhook_id).await? else {
+            return Ok(None);
+        };
+        let Some(original) = self
+            .webhook_repository
+            .find_delivery(delivery_id)
+            .await?
+            .filter(|delivery| delivery.webhook_id == webhook_id)
+        else {
+            return Ok(None);
+        };
+        // saved without a due time so that the delivery worker leaves the first attempt to us
+        let redelivery = self
+            .webhook_repository
+            .save_delivery(WebhookDelivery {
+                webhook_id,
+                event: original.event,
+                payload: original.payload,
+                redelivery_of: Some(original.id),
+                ..Default::default()
+            })
+            .await?;
+        self.attempt(&webhook, redelivery, Utc::now()).await.map(Some)
+    }
+
+    async fn enqueue(
+        &self,
+        event: WebhookEvent,
+        workspace_id: i64,
+        payload: String,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<WebhookDelivery>> {
+        let mut deliveries = Vec::new();
+        for webhook in self.webhook_repository.find_by_workspace(workspace_id).await? {
+            if !webhook.receives(event) {
+                continue;
+            }
+            let delivery = WebhookDelivery {
+                webhook_id: webhook.id,
+                event,
+                payload: payload.clone(),
+                next_attempt_at: Some(now),
+                ..Default::default()
+            };
+            deliveries.push(self.webhook_repository.save_delivery(delivery).await?);
+        }
+        Ok(deliveries)
+    }
+
+    async fn deliver_due(&self, now: DateTime<Utc>) -> anyhow::Result<Vec<WebhookDelivery>> {
+        let due = self.webhook_repository.find_due_deliveries(now, DELIVERY_BATCH_SIZE).await?;
+        let mut webhooks: HashMap<i64, Option<Webhook>> = HashMap::new();
+        let mut attempted = Vec::with_capacity(due.len());
+        for mut delivery in due {
+            let webhook = match webhooks.entry(delivery.webhook_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(self.webhook_repository.find_by_id(delivery.webhook_id).await?),
+            };
+            match webhook {
+                Some(webhook) if webhook.active => attempted.push(self.attempt(webhook, delivery, now).await?),
+                // deactivated since the event was queued
+                _ => {
+                    delivery.status = DeliveryStatus::Failed;
+                    delivery.next_attempt_at = None;
+                    delivery.error = Some("the webhook is inactive".to_string());
+                    attempted.push(self.webhook_repository.save_delivery(delivery).await?);
+                }
+            }
+        }
+        Ok(attempted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_public_addresses_are_allowed() {
+        let table = [
+            ("93.184.215.14", true),
+            ("2606:2800:21f:cb07:6820:80da:af6b:8b2c", true),
+            ("127.0.0.1", false),
+            ("10.1.2.3", false),
+            ("172.16.0.1", false),
+            ("192.168.1.1", false),
+            ("169.254.169.254", false),
+            ("0.0.0.0", false),
+            ("::1", false),
+            ("fd00::1", false),
+            ("fe80::1", false),
+            ("::ffff:127.0.0.1", false),
+            ("::ffff:169.254.169.254", false),
+            ("0.1.2.3", false),
+            ("100.64.0.1", false),
+            ("100.100.100.200", false),
+            ("100.127.255.255", false),
+            ("100.128.0.1", true),
+            ("192.0.0.8", false),
+            ("198.18.0.1", false),
+            ("198.19.255.255", false),
+            ("198.20.0.1", true),
+            ("240.0.0.1", false),
+            ("255.255.255.255", false),
+            ("::10.0.0.1", false),
+            ("::169.254.169.254", false),
+            ("64:ff9b::10.0.0.1", false),
+            ("64:ff9b::a9fe:a9fe", false),
+            ("64:ff9b::93.184.215.14", true),
+            ("2002:a00:1::", false),
+            ("2002:c0a8:101::1", false),
+            ("2002:5db8:d70e::1", true),
+        ];
+        for (address, public) in table {
+            assert_eq!(is_public_address(address.parse().unwrap()), public, "{address}");
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::webhook::webhook_dto::{NewWebhookDto, UpdateWebhookDto};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::webhook::{Webhook, WebhookDelivery, WebhookEvent};
+
+/// Webhooks are managed by the admins and owners of their workspace; to anyone outside it they do
+/// not exist.
+#[async_trait]
+pub trait WebhookServiceTrait: Send + Sync {
+    /// Fails with a `WebhookError` for invalid configurations.
+    async fn create_webhook(&self, user_id: i64, webhook: NewWebhookDto) -> Result<Webhook>;
+    async fn update_webhook(&self, user_id: i64, id: i64, webhook: UpdateWebhookDto) -> Result<Option<Webhook>>;
+    async fn delete_webhook(&self, user_id: i64, id: i64) -> Result<bool>;
+    async fn get_webhook(&self, user_id: i64, id: i64) -> Result<Option<Webhook>>;
+    /// The webhooks of every workspace the user manages the webhooks of.
+    async fn find_webhooks(&self, user_id: i64) -> Result<Vec<Webhook>>;
+    /// The most recent deliveries of the webhook, newest first.
+    async fn find_deliveries(&self, user_id: i64, webhook_id: i64) -> Result<Option<Vec<WebhookDelivery>>>;
+    /// Sends the payload of an earlier delivery again as a new delivery, retried like any other.
+    async fn redeliver(&self, user_id: i64, webhook_id: i64, delivery_id: i64) -> Result<Option<WebhookDelivery>>;
+    /// Queues the event for every webhook of the task's workspace that receives it.
+    async fn enqueue(
+        &self,
+        event: WebhookEvent,
+        workspace_id: i64,
+        payload: String,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<WebhookDelivery>>;
+    /// Attempts the deliveries that are due and schedules retries for the failed ones.
+    async fn deliver_due(&self, now: DateTime<Utc>) -> Result<Vec<WebhookDelivery>>;
+}
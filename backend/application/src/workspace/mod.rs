@@ -0,0 +1,6 @@
+pub mod workspace_policy;
+pub mod workspace_error;
+pub mod workspace_dto;
+pub mod workspace_repository_trait;
+pub mod workspace_service_trait;
+pub mod workspace_service;
\ No newline at end of file
@@ -0,0 +1,8 @@
+use domain::workspace::{Workspace, WorkspaceRole};
+
+/// A workspace together with the role the user holds in it.
+#[derive(Debug, Clone)]
+pub struct WorkspaceMembershipDto {
+    pub workspace: Workspace,
+    pub role: WorkspaceRole,
+}
@@ -0,0 +1,48 @@
+use crate::workspace::workspace_policy::WorkspaceAction;
+use std::fmt;
+
+/// Workspace operations that are refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceError {
+    /// The workspace does not exist, or the user is no member of it.
+    NotFound(i64),
+    Forbidden(WorkspaceAction),
+    UserNotFound(i64),
+    MemberNotFound(i64),
+    LastOwner,
+    BlankName,
+    NameTooLong { max: usize },
+}
+
+impl WorkspaceError {
+    /// Stable kebab-case identifier of the variant for clients that branch on the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            WorkspaceError::NotFound(_) => "workspace-not-found",
+            WorkspaceError::Forbidden(_) => "forbidden",
+            WorkspaceError::UserNotFound(_) => "user-not-found",
+            WorkspaceError::MemberNotFound(_) => "member-not-found",
+            WorkspaceError::LastOwner => "last-owner",
+            WorkspaceError::BlankName => "blank-workspace-name",
+            WorkspaceError::NameTooLong { .. } => "workspace-name-too-long",
+        }
+    }
+}
+
+impl fmt::Display for WorkspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkspaceError::NotFound(id) => write!(f, "workspace {id} does not exist"),
+            WorkspaceError::Forbidden(action) => write!(f, "your role in the workspace does not allow you to {action}"),
+            WorkspaceError::UserNotFound(user_id) => write!(f, "user {user_id} does not exist"),
+            WorkspaceError::MemberNotFound(user_id) => write!(f, "user {user_id} is no member of the workspace"),
+            WorkspaceError::LastOwner => write!(f, "the workspace has to keep at least one owner"),
+            WorkspaceError::BlankName => write!(f, "the workspace name must not be blank"),
+            WorkspaceError::NameTooLong { max } => {
+                write!(f, "the workspace name must not be longer than {max} characters")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorkspaceError {}
@@ -0,0 +1,128 @@
+use domain::workspace::WorkspaceRole;
+use std::fmt;
+
+/// Something a user attempts in a workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceAction {
+    ViewTasks,
+    /// Creating, changing, trashing and restoring tasks, and managing projects and tags.
+    EditTasks,
+    /// Removing tasks from the trash for good.
+    PurgeTasks,
+    ViewMembers,
+    /// Adding and removing members and changing their roles.
+    ManageMembers,
+    /// Removing oneself from the workspace.
+    Leave,
+    /// Registering, changing and removing the webhooks task events are sent to.
+    ManageWebhooks,
+}
+
+impl WorkspaceAction {
+    /// The least privileged role that may take the action.
+    pub fn required_role(self) -> WorkspaceRole {
+        match self {
+            WorkspaceAction::ViewTasks | WorkspaceAction::ViewMembers | WorkspaceAction::Leave => WorkspaceRole::Viewer,
+            WorkspaceAction::EditTasks => WorkspaceRole::Member,
+            WorkspaceAction::PurgeTasks | WorkspaceAction::ManageWebhooks => WorkspaceRole::Admin,
+            WorkspaceAction::ManageMembers => WorkspaceRole::Owner,
+        }
+    }
+}
+
+impl fmt::Display for WorkspaceAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkspaceAction::ViewTasks => write!(f, "view tasks"),
+            WorkspaceAction::EditTasks => write!(f, "edit tasks"),
+            WorkspaceAction::PurgeTasks => write!(f, "purge tasks"),
+            WorkspaceAction::ViewMembers => write!(f, "view members"),
+            WorkspaceAction::ManageMembers => write!(f, "manage members"),
+            WorkspaceAction::Leave => write!(f, "leave the workspace"),
+            WorkspaceAction::ManageWebhooks => write!(f, "manage webhooks"),
+        }
+    }
+}
+
+/// Outcome of a policy check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    /// The user is a member, but their role falls short.
+    Deny,
+    /// The user is no member, so the workspace and its tasks are not revealed to them at all.
+    Conceal,
+}
+
+/// Decides whether a user with `role` in a workspace, `None` for outsiders, may take `action`.
+pub fn decide(role: Option<WorkspaceRole>, action: WorkspaceAction) -> Decision {
+    match role {
+        None => Decision::Conceal,
+        Some(role) if role >= action.required_role() => Decision::Allow,
+        Some(_) => Decision::Deny,
+    }
+}
+
+/// Whether changing a member's role from `current` to `new`, `None` meaning no membership, would
+/// leave the workspace without an owner. `owner_count` counts the owners before the change.
+pub fn removes_last_owner(current: Option<WorkspaceRole>, new: Option<WorkspaceRole>, owner_count: usize) -> bool {
+    current == Some(WorkspaceRole::Owner) && new != Some(WorkspaceRole::Owner) && owner_count <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Decision::{Allow, Conceal, Deny};
+    use WorkspaceRole::{Admin, Member, Owner, Viewer};
+
+    /// The columns of the decision table: outsiders first, then the roles by privilege.
+    const ROLES: [Option<WorkspaceRole>; 5] = [None, Some(Viewer), Some(Member), Some(Admin), Some(Owner)];
+
+    #[test]
+    fn decisions_follow_the_role_table() {
+        let table = [
+            // decisions for outsiders, viewers, members, admins and owners
+            (WorkspaceAction::ViewTasks, [Conceal, Allow, Allow, Allow, Allow]),
+            (WorkspaceAction::EditTasks, [Conceal, Deny, Allow, Allow, Allow]),
+            (WorkspaceAction::PurgeTasks, [Conceal, Deny, Deny, Allow, Allow]),
+            (WorkspaceAction::ViewMembers, [Conceal, Allow, Allow, Allow, Allow]),
+            (WorkspaceAction::ManageMembers, [Conceal, Deny, Deny, Deny, Allow]),
+            (WorkspaceAction::Leave, [Conceal, Allow, Allow, Allow, Allow]),
+            (WorkspaceAction::ManageWebhooks, [Conceal, Deny, Deny, Allow, Allow]),
+        ];
+        for (action, expected) in table {
+            for (role, expected) in ROLES.into_iter().zip(expected) {
+                assert_eq!(decide(role, action), expected, "{role:?} attempting to {action}");
+            }
+        }
+    }
+
+    #[test]
+    fn roles_are_ordered_by_privilege() {
+        assert!(Viewer < Member && Member < Admin && Admin < Owner);
+    }
+
+    #[test]
+    fn the_last_owner_cannot_step_down_or_leave() {
+        let table = [
+            // current role, new role, owners before the change, whether none would be left
+            (Some(Owner), Some(Admin), 1, true),
+            (Some(Owner), Some(Viewer), 1, true),
+            (Some(Owner), None, 1, true),
+            (Some(Owner), Some(Owner), 1, false),
+            (Some(Owner), Some(Member), 2, false),
+            (Some(Owner), None, 2, false),
+            (Some(Admin), None, 1, false),
+            (Some(Member), Some(Viewer), 1, false),
+            (None, Some(Owner), 1, false),
+            (None, Some(Member), 0, false),
+        ];
+        for (current, new, owner_count, expected) in table {
+            assert_eq!(
+                removes_last_owner(current, new, owner_count),
+                expected,
+                "{current:?} -> {new:?} with {owner_count} owners"
+            );
+        }
+    }
+}
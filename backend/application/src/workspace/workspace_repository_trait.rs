@@ -0,0 +1,20 @@
+use crate::workspace::workspace_dto::WorkspaceMembershipDto;
+use async_trait::async_trait;
+use domain::workspace::{Workspace, WorkspaceMember, WorkspaceRole};
+
+#[async_trait]
+pub trait WorkspaceRepositoryTrait: Send + Sync {
+    /// Inserts the workspace together with its first owner, in one transaction.
+    async fn create(&self, workspace: Workspace, owner_id: i64) -> anyhow::Result<Workspace>;
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Workspace>>;
+    /// The workspaces the user belongs to, oldest first.
+    async fn find_memberships(&self, user_id: i64) -> anyhow::Result<Vec<WorkspaceMembershipDto>>;
+    /// The user's role in the workspace, `None` for non-members.
+    async fn find_role(&self, workspace_id: i64, user_id: i64) -> anyhow::Result<Option<WorkspaceRole>>;
+    async fn find_members(&self, workspace_id: i64) -> anyhow::Result<Vec<WorkspaceMember>>;
+    /// Adds the member, or changes the role of an existing one. Fails with `WorkspaceError::LastOwner`
+    /// if no owner would be left, checked in the same transaction as the change.
+    async fn save_member(&self, member: WorkspaceMember) -> anyhow::Result<WorkspaceMember>;
+    /// Removes the member, `false` if there was none. Fails like `save_member` for the last owner.
+    async fn delete_member(&self, workspace_id: i64, user_id: i64) -> anyhow::Result<bool>;
+}
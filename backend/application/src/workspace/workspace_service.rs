@@ -0,0 +1,142 @@
+use crate::auth::user_repository_trait::UserRepositoryTrait;
+use crate::workspace::workspace_dto::WorkspaceMembershipDto;
+use crate::workspace::workspace_error::WorkspaceError;
+use crate::workspace::workspace_policy::{Decision, WorkspaceAction, decide};
+use crate::workspace::workspace_repository_trait::WorkspaceRepositoryTrait;
+use crate::workspace::workspace_service_trait::WorkspaceServiceTrait;
+use async_trait::async_trait;
+use domain::workspace::{Workspace, WorkspaceMember, WorkspaceRole};
+use std::sync::Arc;
+
+/// Upper bound for workspace names, counted in characters.
+const MAX_NAME_LENGTH: usize = 100;
+/// Name of the workspace created for users who create something without belonging to any.
+const PERSONAL_WORKSPACE_NAME: &str = "Personal";
+
+/// The workspaces of the user in which the policy allows the action.
+pub(crate) async fn allowed_workspace_ids(
+    workspace_repository: &dyn WorkspaceRepositoryTrait,
+    user_id: i64,
+    action: WorkspaceAction,
+) -> anyhow::Result<Vec<i64>> {
+    Ok(workspace_repository
+        .find_memberships(user_id)
+        .await?
+        .into_iter()
+        .filter(|membership| decide(Some(membership.role), action) == Decision::Allow)
+        .map(|membership| membership.workspace.id)
+        .collect())
+}
+
+/// Consults the policy: `Ok(false)` for non-members, from whom the workspace is concealed, and
+/// `WorkspaceError::Forbidden` for members whose role falls short.
+pub(crate) async fn authorize_member(
+    workspace_repository: &dyn WorkspaceRepositoryTrait,
+    user_id: i64,
+    workspace_id: i64,
+    action: WorkspaceAction,
+) -> anyhow::Result<bool> {
+    let role = workspace_repository.find_role(workspace_id, user_id).await?;
+    match decide(role, action) {
+        Decision::Allow => Ok(true),
+        Decision::Deny => Err(WorkspaceError::Forbidden(action).into()),
+        Decision::Conceal => Ok(false),
+    }
+}
+
+/// The oldest workspace the user may edit in, or a new one of their own if there is none.
+pub(crate) async fn default_workspace(
+    workspace_repository: &dyn WorkspaceRepositoryTrait,
+    user_id: i64,
+) -> anyhow::Result<i64> {
+    let memberships = workspace_repository.find_memberships(user_id).await?;
+    if let Some(membership) = memberships
+        .iter()
+        .find(|membership| decide(Some(membership.role), WorkspaceAction::EditTasks) == Decision::Allow)
+    {
+        return Ok(membership.workspace.id);
+    }
+    let workspace = Workspace { name: PERSONAL_WORKSPACE_NAME.to_string(), ..Default::default() };
+    Ok(workspace_repository.create(workspace, user_id).await?.id)
+}
+
+#[derive(Clone)]
+pub struct WorkspaceService {
+    workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+    user_repository: Arc<dyn UserRepositoryTrait>,
+}
+
+impl WorkspaceService {
+    pub fn new(
+        workspace_repository: Arc<dyn WorkspaceRepositoryTrait>,
+        user_repository: Arc<dyn UserRepositoryTrait>,
+    ) -> Self {
+        Self { workspace_repository, user_repository }
+    }
+
+    /// Consults the policy, so that outsiders learn nothing about the workspace.
+    async fn authorize(&self, user_id: i64, workspace_id: i64, action: WorkspaceAction) -> anyhow::Result<()> {
+        if !authorize_member(&*self.workspace_repository, user_id, workspace_id, action).await? {
+            return Err(WorkspaceError::NotFound(workspace_id).into());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WorkspaceServiceTrait for WorkspaceService {
+    async fn create_workspace(&self, user_id: i64, name: String) -> anyhow::Result<WorkspaceMembershipDto> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(WorkspaceError::BlankName.into());
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(WorkspaceError::NameTooLong { max: MAX_NAME_LENGTH }.into());
+        }
+        let workspace = self
+            .workspace_repository
+            .create(Workspace { name: name.to_string(), ..Default::default() }, user_id)
+            .await?;
+        Ok(WorkspaceMembershipDto { workspace, role: WorkspaceRole::Owner })
+    }
+
+    async fn find_workspaces(&self, user_id: i64) -> anyhow::Result<Vec<WorkspaceMembershipDto>> {
+        self.workspace_repository.find_memberships(user_id).await
+    }
+
+    async fn find_members(&self, user_id: i64, workspace_id: i64) -> anyhow::Result<Vec<WorkspaceMember>> {
+        self.authorize(user_id, workspace_id, WorkspaceAction::ViewMembers).await?;
+        self.workspace_repository.find_members(workspace_id).await
+    }
+
+    async fn is_allowed(&self, user_id: i64, workspace_id: i64, action: WorkspaceAction) -> anyhow::Result<bool> {
+        let role = self.workspace_repository.find_role(workspace_id, user_id).await?;
+        Ok(decide(role, action) == Decision::Allow)
+    }
+
+    async fn set_member_role(
+        &self,
+        user_id: i64,
+        workspace_id: i64,
+        member_id: i64,
+        role: WorkspaceRole,
+    ) -> anyhow::Result<WorkspaceMember> {
+        self.authorize(user_id, workspace_id, WorkspaceAction::ManageMembers).await?;
+        if self.user_repository.find_by_id(member_id).await?.is_none() {
+            return Err(WorkspaceError::UserNotFound(member_id).into());
+        }
+        // the repository refuses to demote the last owner, atomically with the change
+        self.workspace_repository
+            .save_member(WorkspaceMember { workspace_id, user_id: member_id, role, ..Default::default() })
+            .await
+    }
+
+    async fn remove_member(&self, user_id: i64, workspace_id: i64, member_id: i64) -> anyhow::Result<()> {
+        let action = if member_id == user_id { WorkspaceAction::Leave } else { WorkspaceAction::ManageMembers };
+        self.authorize(user_id, workspace_id, action).await?;
+        if !self.workspace_repository.delete_member(workspace_id, member_id).await? {
+            return Err(WorkspaceError::MemberNotFound(member_id).into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use crate::workspace::workspace_dto::WorkspaceMembershipDto;
+use crate::workspace::workspace_policy::WorkspaceAction;
+use anyhow::Result;
+use async_trait::async_trait;
+use domain::workspace::{WorkspaceMember, WorkspaceRole};
+
+/// Workspaces as seen by the signed-in user `user_id`. Refusals are reported as `WorkspaceError`,
+/// workspaces the user does not belong to as `WorkspaceError::NotFound`.
+#[async_trait]
+pub trait WorkspaceServiceTrait: Send + Sync {
+    /// Creates a workspace owned by the user.
+    async fn create_workspace(&self, user_id: i64, name: String) -> Result<WorkspaceMembershipDto>;
+    async fn find_workspaces(&self, user_id: i64) -> Result<Vec<WorkspaceMembershipDto>>;
+    async fn find_members(&self, user_id: i64, workspace_id: i64) -> Result<Vec<WorkspaceMember>>;
+    /// Whether the policy allows the user the action in the workspace, `false` for non-members.
+    /// Long-lived connections ask for every event, so that removed members stop receiving them.
+    async fn is_allowed(&self, user_id: i64, workspace_id: i64, action: WorkspaceAction) -> Result<bool>;
+    /// Adds a member or changes their role; owners only.
+    async fn set_member_role(
+        &self,
+        user_id: i64,
+        workspace_id: i64,
+        member_id: i64,
+        role: WorkspaceRole,
+    ) -> Result<WorkspaceMember>;
+    /// Removes a member; owners only, but anyone may remove themselves.
+    async fn remove_member(&self, user_id: i64, workspace_id: i64, member_id: i64) -> Result<()>;
+}
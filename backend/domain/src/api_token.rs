@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+
+/// Marks API tokens, so that they are told apart from access tokens and spotted by secret scanners.
+pub const API_TOKEN_PREFIX: &str = "tt_";
+
+/// What a credential may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenScope {
+    /// Reading only, any change is refused.
+    Read,
+    #[default]
+    ReadWrite,
+}
+
+/// A named credential for scripts and integrations, which authenticates like its user's session
+/// but does not have to be refreshed.
+#[derive(Debug, Default, Clone)]
+pub struct ApiToken {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    /// Only the hash is kept, the token is shown once when it is created.
+    pub token_hash: String,
+    pub scope: TokenScope,
+    /// Tokens without expiry stay valid until they are revoked.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
@@ -1 +1,9 @@
-pub mod task;
\ No newline at end of file
+pub mod task;
+pub mod tag;
+pub mod recurrence;
+pub mod project;
+pub mod validation;
+pub mod webhook;
+pub mod user;
+pub mod api_token;
+pub mod workspace;
\ No newline at end of file
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Default, Clone)]
+pub struct Project {
+    pub id: i64,
+    /// Only tasks of this workspace can be added to the project.
+    pub workspace_id: i64,
+    pub name: String,
+    /// Archived projects and their tasks are hidden from the default views.
+    pub archived_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Number of open tasks outside the trash, computed by the repository.
+    pub open_task_count: i64,
+}
@@ -0,0 +1,503 @@
+use chrono::{DateTime, Datelike, Days, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeDelta, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use std::fmt;
+use std::str::FromStr;
+
+/// Upper bound on the periods scanned, so that rules which never match cannot loop forever.
+const MAX_PERIODS: u32 = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A `BYDAY` entry such as `MO`, `1MO` (first Monday) or `-1FR` (last Friday).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayRule {
+    pub ordinal: Option<i8>,
+    pub weekday: Weekday,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    Date(NaiveDate),
+    /// Wall-clock time in the time zone of the series.
+    Local(NaiveDateTime),
+    Utc(DateTime<Utc>),
+}
+
+/// The subset of iCalendar (RFC 5545) recurrence rules supported for repeating tasks:
+/// `FREQ`, `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY`, `BYMONTHDAY`, `BYMONTH` and `WKST`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<Until>,
+    pub by_day: Vec<WeekdayRule>,
+    pub by_month_day: Vec<i8>,
+    pub by_month: Vec<u32>,
+    pub week_start: Weekday,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecurrenceRuleError {
+    MissingFrequency,
+    MalformedPart(String),
+    UnsupportedPart(String),
+    InvalidValue { part: String, value: String },
+    CountWithUntil,
+}
+
+impl fmt::Display for RecurrenceRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecurrenceRuleError::MissingFrequency => write!(f, "FREQ is required"),
+            RecurrenceRuleError::MalformedPart(part) => write!(f, "expected NAME=VALUE but got `{part}`"),
+            RecurrenceRuleError::UnsupportedPart(part) => write!(f, "{part} is not supported"),
+            RecurrenceRuleError::InvalidValue { part, value } => write!(f, "invalid {part} value `{value}`"),
+            RecurrenceRuleError::CountWithUntil => write!(f, "COUNT and UNTIL cannot be combined"),
+        }
+    }
+}
+
+impl std::error::Error for RecurrenceRuleError {}
+
+impl FromStr for RecurrenceRule {
+    type Err = RecurrenceRuleError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = Vec::new();
+        let mut by_month_day = Vec::new();
+        let mut by_month = Vec::new();
+        let mut week_start = Weekday::Mon;
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| RecurrenceRuleError::MalformedPart(part.to_string()))?;
+            let name = name.to_ascii_uppercase();
+            let value = value.to_ascii_uppercase();
+            let invalid = || RecurrenceRuleError::InvalidValue { part: name.clone(), value: value.clone() };
+
+            match name.as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid()),
+                    })
+                }
+                "INTERVAL" => interval = value.parse().ok().filter(|interval| *interval > 0).ok_or_else(invalid)?,
+                "COUNT" => count = Some(value.parse().ok().filter(|count| *count > 0).ok_or_else(invalid)?),
+                "UNTIL" => until = Some(parse_until(&value).ok_or_else(invalid)?),
+                "BYDAY" => by_day = parse_list(&value, parse_weekday_rule).ok_or_else(invalid)?,
+                "BYMONTHDAY" => {
+                    by_month_day = parse_list(&value, |day| {
+                        day.parse::<i8>().ok().filter(|day| *day != 0 && (-31..=31).contains(day))
+                    })
+                    .ok_or_else(invalid)?
+                }
+                "BYMONTH" => {
+                    by_month = parse_list(&value, |month| month.parse::<u32>().ok().filter(|month| (1..=12).contains(month)))
+                        .ok_or_else(invalid)?
+                }
+                "WKST" => week_start = parse_weekday(&value).ok_or_else(invalid)?,
+                _ => return Err(RecurrenceRuleError::UnsupportedPart(name)),
+            }
+        }
+
+        let frequency = frequency.ok_or(RecurrenceRuleError::MissingFrequency)?;
+        if count.is_some() && until.is_some() {
+            return Err(RecurrenceRuleError::CountWithUntil);
+        }
+        let has_ordinals = by_day.iter().any(|rule| rule.ordinal.is_some());
+        match frequency {
+            Frequency::Daily | Frequency::Weekly if has_ordinals => {
+                return Err(RecurrenceRuleError::UnsupportedPart("BYDAY with ordinals outside MONTHLY and YEARLY".to_string()));
+            }
+            Frequency::Yearly if has_ordinals && by_month.is_empty() => {
+                return Err(RecurrenceRuleError::UnsupportedPart("BYDAY with ordinals in YEARLY without BYMONTH".to_string()));
+            }
+            Frequency::Weekly if !by_month_day.is_empty() => {
+                return Err(RecurrenceRuleError::UnsupportedPart("BYMONTHDAY in WEEKLY".to_string()));
+            }
+            _ => {}
+        }
+
+        Ok(Self { frequency, interval, count, until, by_day, by_month_day, by_month, week_start })
+    }
+}
+
+impl RecurrenceRule {
+    /// Occurrences of a series starting at `start`, in order, evaluated on the wall clock of the
+    /// time zone of `start`. Occurrences keep the local time of `start` across DST changes.
+    pub fn occurrences(&self, start: DateTime<Tz>) -> impl Iterator<Item = DateTime<Tz>> + '_ {
+        let time_zone = start.timezone();
+        let local_start = start.naive_local();
+        (0..MAX_PERIODS)
+            .flat_map(move |period| self.period_dates(local_start.date(), period))
+            .map(move |date| resolve_local(&time_zone, date.and_time(local_start.time())))
+            .skip_while(move |occurrence| *occurrence < start)
+            .take_while(move |occurrence| self.until.is_none_or(|until| until.allows(occurrence)))
+            .take(self.count.map_or(usize::MAX, |count| count as usize))
+    }
+
+    /// The first occurrence of a series starting at `start` that lies strictly after `after`.
+    pub fn next_after(&self, start: DateTime<Tz>, after: DateTime<Utc>) -> Option<DateTime<Tz>> {
+        self.occurrences(start).find(|occurrence| *occurrence > after)
+    }
+
+    /// The occurrence that follows one due at `due_at` and completed at `completed_at`. Late
+    /// completions skip the occurrences that passed in the meantime, so the next one is not
+    /// already overdue.
+    pub fn next_after_completion(
+        &self,
+        start: DateTime<Tz>,
+        due_at: Option<DateTime<Utc>>,
+        completed_at: DateTime<Utc>,
+    ) -> Option<DateTime<Tz>> {
+        self.next_after(start, due_at.map_or(completed_at, |due_at| due_at.max(completed_at)))
+    }
+
+    /// Candidate dates of the `period`-th period after the one containing `start`, in order.
+    fn period_dates(&self, start: NaiveDate, period: u32) -> Vec<NaiveDate> {
+        let Some(step) = period.checked_mul(self.interval) else {
+            return Vec::new();
+        };
+        let mut dates: Vec<NaiveDate> = match self.frequency {
+            Frequency::Daily => start
+                .checked_add_days(Days::new(step.into()))
+                .filter(|date| self.matches_day(*date, start))
+                .into_iter()
+                .collect(),
+            Frequency::Weekly => {
+                let days_into_week = (7 + start.weekday().num_days_from_monday() - self.week_start.num_days_from_monday()) % 7;
+                (start - Days::new(days_into_week.into()))
+                    .checked_add_days(Days::new(u64::from(step) * 7))
+                    .into_iter()
+                    .flat_map(|week_start| (0..7).filter_map(move |offset| week_start.checked_add_days(Days::new(offset))))
+                    .filter(|date| self.matches_day(*date, start))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let months = i64::from(start.year()) * 12 + i64::from(start.month0()) + i64::from(step);
+                match i32::try_from(months.div_euclid(12)) {
+                    Ok(year) => self.month_dates(year, months.rem_euclid(12) as u32 + 1, start),
+                    Err(_) => Vec::new(),
+                }
+            }
+            Frequency::Yearly => {
+                let Some(year) = i32::try_from(step).ok().and_then(|step| start.year().checked_add(step)) else {
+                    return Vec::new();
+                };
+                let months = if self.by_month.is_empty() { vec![start.month()] } else { self.by_month.clone() };
+                months.into_iter().flat_map(|month| self.month_dates(year, month, start)).collect()
+            }
+        };
+        dates.retain(|date| self.by_month.is_empty() || self.by_month.contains(&date.month()));
+        dates.sort_unstable();
+        dates.dedup();
+        dates
+    }
+
+    fn month_dates(&self, year: i32, month: u32, start: NaiveDate) -> Vec<NaiveDate> {
+        (1..=days_in_month(year, month))
+            .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+            .filter(|date| self.matches_day(*date, start))
+            .collect()
+    }
+
+    /// Whether `date` is selected within its period. Without `BYDAY` and `BYMONTHDAY` the rule
+    /// repeats the weekday (weekly) or the day of the month (monthly, yearly) of `start`, so a
+    /// series starting on the 31st skips months that are shorter.
+    fn matches_day(&self, date: NaiveDate, start: NaiveDate) -> bool {
+        if self.by_day.is_empty() && self.by_month_day.is_empty() {
+            return match self.frequency {
+                Frequency::Daily => true,
+                Frequency::Weekly => date.weekday() == start.weekday(),
+                Frequency::Monthly | Frequency::Yearly => date.day() == start.day(),
+            };
+        }
+        let month_length = days_in_month(date.year(), date.month());
+        let matches_month_day = self.by_month_day.is_empty()
+            || self.by_month_day.iter().any(|month_day| {
+                let day = if *month_day > 0 {
+                    i64::from(*month_day)
+                } else {
+                    i64::from(month_length) + i64::from(*month_day) + 1
+                };
+                i64::from(date.day()) == day
+            });
+        let matches_weekday = self.by_day.is_empty()
+            || self.by_day.iter().any(|rule| {
+                rule.weekday == date.weekday()
+                    && match rule.ordinal {
+                        None => true,
+                        Some(ordinal) if ordinal > 0 => (date.day() - 1) / 7 + 1 == ordinal as u32,
+                        Some(ordinal) => (month_length - date.day()) / 7 + 1 == ordinal.unsigned_abs() as u32,
+                    }
+            });
+        matches_month_day && matches_weekday
+    }
+}
+
+impl Until {
+    fn allows(&self, occurrence: &DateTime<Tz>) -> bool {
+        match self {
+            Until::Date(date) => occurrence.date_naive() <= *date,
+            Until::Local(local) => occurrence.naive_local() <= *local,
+            Until::Utc(instant) => occurrence.with_timezone(&Utc) <= *instant,
+        }
+    }
+}
+
+/// Maps a wall-clock time to an instant as RFC 5545 prescribes: ambiguous times resolve to their
+/// first occurrence, and times skipped by a DST gap use the offset in effect before the gap.
+fn resolve_local(time_zone: &Tz, local: NaiveDateTime) -> DateTime<Tz> {
+    match time_zone.from_local_datetime(&local) {
+        LocalResult::Single(occurrence) => occurrence,
+        LocalResult::Ambiguous(earliest, _) => earliest,
+        LocalResult::None => {
+            let offset_before_gap = time_zone.offset_from_utc_datetime(&(local - TimeDelta::days(1))).fix();
+            let instant = local - TimeDelta::seconds(offset_before_gap.local_minus_utc().into());
+            time_zone.from_utc_datetime(&instant)
+        }
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first_of_next_month| first_of_next_month.pred_opt())
+        .map_or(31, |last_day| last_day.day())
+}
+
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    value.split(',').map(|item| parse(item.trim())).collect()
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_weekday_rule(value: &str) -> Option<WeekdayRule> {
+    let split = value.len().checked_sub(2)?;
+    let weekday = parse_weekday(value.get(split..)?)?;
+    let ordinal = match value.get(..split)? {
+        "" => None,
+        ordinal => Some(ordinal.parse::<i8>().ok().filter(|ordinal| *ordinal != 0 && (-5..=5).contains(ordinal))?),
+    };
+    Some(WeekdayRule { ordinal, weekday })
+}
+
+fn parse_until(value: &str) -> Option<Until> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok().map(|until| Until::Utc(until.and_utc()))
+    } else if value.contains('T') {
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok().map(Until::Local)
+    } else {
+        NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(Until::Date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::{America::New_York, Europe::Berlin};
+
+    fn rule(rule: &str) -> RecurrenceRule {
+        rule.parse().expect("valid rule")
+    }
+
+    fn local(time_zone: Tz, year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+        time_zone.with_ymd_and_hms(year, month, day, hour, minute, 0).earliest().expect("valid local time")
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    fn first(rule: &RecurrenceRule, start: DateTime<Tz>, count: usize) -> Vec<DateTime<Utc>> {
+        rule.occurrences(start).take(count).map(|occurrence| occurrence.with_timezone(&Utc)).collect()
+    }
+
+    #[test]
+    fn daily_keeps_local_time_across_spring_forward() {
+        let start = local(Berlin, 2025, 3, 29, 9, 0);
+        assert_eq!(
+            first(&rule("FREQ=DAILY"), start, 3),
+            vec![utc(2025, 3, 29, 8, 0), utc(2025, 3, 30, 7, 0), utc(2025, 3, 31, 7, 0)]
+        );
+    }
+
+    #[test]
+    fn weekly_keeps_local_time_across_fall_back() {
+        let start = local(New_York, 2025, 10, 27, 9, 0);
+        assert_eq!(
+            first(&rule("FREQ=WEEKLY"), start, 2),
+            vec![utc(2025, 10, 27, 13, 0), utc(2025, 11, 3, 14, 0)]
+        );
+    }
+
+    #[test]
+    fn time_skipped_by_dst_gap_uses_offset_before_gap() {
+        let start = local(Berlin, 2025, 3, 29, 2, 30);
+        let occurrences: Vec<_> = rule("FREQ=DAILY").occurrences(start).take(3).collect();
+        assert_eq!(occurrences[1].with_timezone(&Utc), utc(2025, 3, 30, 1, 30));
+        assert_eq!(occurrences[1].naive_local(), NaiveDate::from_ymd_opt(2025, 3, 30).unwrap().and_hms_opt(3, 30, 0).unwrap());
+        assert_eq!(occurrences[2].with_timezone(&Utc), utc(2025, 3, 31, 0, 30));
+    }
+
+    #[test]
+    fn ambiguous_time_resolves_to_first_occurrence() {
+        let start = local(Berlin, 2025, 10, 25, 2, 30);
+        assert_eq!(
+            first(&rule("FREQ=DAILY"), start, 2),
+            vec![utc(2025, 10, 25, 0, 30), utc(2025, 10, 26, 0, 30)]
+        );
+    }
+
+    #[test]
+    fn monthly_on_the_31st_skips_shorter_months() {
+        let start = local(Berlin, 2025, 1, 31, 10, 0);
+        let dates: Vec<_> = rule("FREQ=MONTHLY").occurrences(start).take(4).map(|o| o.date_naive()).collect();
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 3, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 5, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 7, 31).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn negative_month_day_is_the_last_day_of_each_month() {
+        let start = local(Berlin, 2028, 1, 31, 10, 0);
+        let dates: Vec<_> = rule("FREQ=MONTHLY;BYMONTHDAY=-1").occurrences(start).take(4).map(|o| o.date_naive()).collect();
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd_opt(2028, 1, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2028, 2, 29).unwrap(),
+                NaiveDate::from_ymd_opt(2028, 3, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2028, 4, 30).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn yearly_on_leap_day_only_occurs_in_leap_years() {
+        let start = local(Berlin, 2024, 2, 29, 10, 0);
+        let dates: Vec<_> = rule("FREQ=YEARLY").occurrences(start).take(2).map(|o| o.date_naive()).collect();
+        assert_eq!(dates, vec![NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(), NaiveDate::from_ymd_opt(2028, 2, 29).unwrap()]);
+    }
+
+    #[test]
+    fn last_friday_of_the_month() {
+        let start = local(Berlin, 2025, 1, 1, 10, 0);
+        let dates: Vec<_> = rule("FREQ=MONTHLY;BYDAY=-1FR").occurrences(start).take(2).map(|o| o.date_naive()).collect();
+        assert_eq!(dates, vec![NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(), NaiveDate::from_ymd_opt(2025, 2, 28).unwrap()]);
+    }
+
+    #[test]
+    fn weekly_on_several_days_with_interval() {
+        let start = local(Berlin, 2025, 6, 2, 9, 0);
+        let dates: Vec<_> = rule("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR").occurrences(start).take(4).map(|o| o.date_naive()).collect();
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 6, 6).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 6, 16).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 6, 20).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn count_and_until_end_the_series() {
+        let start = local(Berlin, 2025, 1, 1, 10, 0);
+        assert_eq!(rule("FREQ=DAILY;COUNT=3").occurrences(start).count(), 3);
+        assert_eq!(rule("FREQ=DAILY;UNTIL=20250105").occurrences(start).count(), 5);
+        assert_eq!(rule("FREQ=DAILY;UNTIL=20250105T085959Z").occurrences(start).count(), 4);
+    }
+
+    #[test]
+    fn next_after_skips_past_occurrences() {
+        let start = local(Berlin, 2025, 1, 6, 9, 0);
+        let next = rule("FREQ=WEEKLY").next_after(start, utc(2025, 1, 20, 8, 0));
+        assert_eq!(next.map(|next| next.with_timezone(&Utc)), Some(utc(2025, 1, 27, 8, 0)));
+        assert_eq!(rule("FREQ=WEEKLY;COUNT=2").next_after(start, utc(2025, 1, 20, 8, 0)), None);
+    }
+
+    #[test]
+    fn late_completion_skips_missed_occurrences() {
+        let start = local(Berlin, 2025, 1, 6, 9, 0);
+        let weekly = rule("FREQ=WEEKLY");
+        let next = |completed_at| weekly.next_after_completion(start, Some(utc(2025, 1, 6, 8, 0)), completed_at);
+        assert_eq!(next(utc(2025, 1, 5, 12, 0)).map(|next| next.with_timezone(&Utc)), Some(utc(2025, 1, 13, 8, 0)));
+        assert_eq!(next(utc(2025, 1, 22, 12, 0)).map(|next| next.with_timezone(&Utc)), Some(utc(2025, 1, 27, 8, 0)));
+        assert_eq!(
+            weekly.next_after_completion(start, None, utc(2025, 1, 22, 12, 0)).map(|next| next.with_timezone(&Utc)),
+            Some(utc(2025, 1, 27, 8, 0))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        assert_eq!("INTERVAL=2".parse::<RecurrenceRule>(), Err(RecurrenceRuleError::MissingFrequency));
+        assert_eq!(
+            "FREQ=HOURLY".parse::<RecurrenceRule>(),
+            Err(RecurrenceRuleError::InvalidValue { part: "FREQ".to_string(), value: "HOURLY".to_string() })
+        );
+        assert_eq!(
+            "FREQ=DAILY;BYSETPOS=1".parse::<RecurrenceRule>(),
+            Err(RecurrenceRuleError::UnsupportedPart("BYSETPOS".to_string()))
+        );
+        assert_eq!(
+            "FREQ=DAILY;COUNT=2;UNTIL=20250101".parse::<RecurrenceRule>(),
+            Err(RecurrenceRuleError::CountWithUntil)
+        );
+        assert!("FREQ=DAILY;INTERVAL=0".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=MONTHLY;BYMONTHDAY=32".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;BYDAY=1MO".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ".parse::<RecurrenceRule>().is_err());
+    }
+
+    #[test]
+    fn accepts_rrule_prefix_and_lowercase() {
+        let parsed = rule("RRULE:freq=monthly;byday=1mo,-1fr");
+        assert_eq!(parsed.frequency, Frequency::Monthly);
+        assert_eq!(
+            parsed.by_day,
+            vec![
+                WeekdayRule { ordinal: Some(1), weekday: Weekday::Mon },
+                WeekdayRule { ordinal: Some(-1), weekday: Weekday::Fri },
+            ]
+        );
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, Default, Clone)]
+pub struct Tag {
+    pub id: i64,
+    /// Only tasks of this workspace can be tagged with the tag.
+    pub workspace_id: i64,
+    pub name: String,
+    pub color: String,
+}
@@ -1,10 +1,54 @@
 use chrono::{DateTime, Utc};
+use crate::tag::Tag;
+use crate::user::User;
 
-#[derive(Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Task {
     pub id: i64,
     pub title: String,
+    pub description: Option<String>,
     pub done: bool,
+    pub priority: Priority,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub tags: Vec<Tag>,
+    pub parent_id: Option<i64>,
+    /// Subtasks, only populated when a task tree is requested.
+    pub children: Vec<Task>,
+    /// Tasks that have to be done before this one.
+    pub blocker_ids: Vec<i64>,
+    /// Whether any blocker is still open, computed by the repository.
+    pub blocked: bool,
+    /// iCalendar RRULE; completing the task schedules the next occurrence of the series.
+    pub recurrence_rule: Option<String>,
+    /// IANA time zone the rule is evaluated in, UTC when absent.
+    pub recurrence_time_zone: Option<String>,
+    /// First task of the recurring series this task was scheduled from, which may have been
+    /// purged since.
+    pub series_id: Option<i64>,
+    /// Due date, or creation time, of the first task of the series, from which COUNT and UNTIL
+    /// are counted.
+    pub series_start: Option<DateTime<Utc>>,
+    pub project_id: Option<i64>,
+    pub workspace_id: i64,
+    /// The user who created the task, absent for tasks from before there were users.
+    pub created_by: Option<i64>,
+    /// Users working on the task, ordered by display name.
+    pub assignees: Vec<User>,
+    /// Incremented on every write, so that concurrent changes can be detected.
+    pub version: i64,
+}
+
+/// Variants are declared from least to most important, so the derived ordering is the triage order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
 }
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+
+/// Shortest password accepted at registration, counted in characters.
+pub const MIN_PASSWORD_LENGTH: usize = 10;
+
+#[derive(Debug, Default, Clone)]
+pub struct User {
+    pub id: i64,
+    /// Lower-cased, so that it signs in however it is typed.
+    pub email: String,
+    pub display_name: String,
+    /// Argon2 hash in PHC string format, the password itself is never stored. Users provisioned
+    /// by single sign-on have none.
+    pub password_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Links a user to their account at an OpenID Connect provider, which signs them in instead of a
+/// password.
+#[derive(Debug, Default, Clone)]
+pub struct UserIdentity {
+    pub id: i64,
+    pub user_id: i64,
+    /// Issuer URL of the provider.
+    pub issuer: String,
+    /// The provider's stable identifier of the account, its `sub` claim.
+    pub subject: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A refresh token handed out at sign-in. Every refresh replaces it with a new token of the same
+/// family, so a replaced token that shows up again means it leaked.
+#[derive(Debug, Default, Clone)]
+pub struct RefreshToken {
+    pub id: i64,
+    pub user_id: i64,
+    /// Only the hash is kept, the token itself is known to the client alone.
+    pub token_hash: String,
+    /// Hash of the token the family started with at sign-in.
+    pub family: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// Set when the token was replaced, or its family signed out or was revoked.
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl RefreshToken {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+}
+
+/// Lower-cases and trims an email address, which needs a local part and a domain.
+pub fn normalize_email(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    let (local, domain) = email.split_once('@')?;
+    let valid = !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains('@')
+        && !email.chars().any(|character| character.is_whitespace() || character.is_control());
+    valid.then_some(email)
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+use unicode_normalization::UnicodeNormalization;
+
+/// Upper bound for titles, counted in characters after normalization.
+pub const MAX_TITLE_LENGTH: usize = 200;
+/// Upper bound for Markdown descriptions, counted in characters after normalization.
+pub const MAX_DESCRIPTION_LENGTH: usize = 20_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    Blank,
+    TooLong { max: usize },
+    ControlCharacter,
+}
+
+/// A rejected value, named after the API field it was sent in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub violation: Violation,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = self.field;
+        match self.violation {
+            Violation::Blank => write!(f, "{field} must not be blank"),
+            Violation::TooLong { max } => write!(f, "{field} must not be longer than {max} characters"),
+            Violation::ControlCharacter => write!(f, "{field} must not contain control characters"),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Trims and NFC-normalizes a title, which has to be a non-blank single line.
+pub fn validate_title(title: &str) -> Result<String, FieldError> {
+    let title: String = title.trim().nfc().collect();
+    let error = |violation| Err(FieldError { field: "title", violation });
+    if title.is_empty() {
+        return error(Violation::Blank);
+    }
+    if title.chars().any(char::is_control) {
+        return error(Violation::ControlCharacter);
+    }
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return error(Violation::TooLong { max: MAX_TITLE_LENGTH });
+    }
+    Ok(title)
+}
+
+/// NFC-normalizes a description. Line breaks and tabs are allowed, other control characters are not.
+pub fn validate_description(description: &str) -> Result<String, FieldError> {
+    let description: String = description.nfc().collect();
+    let error = |violation| Err(FieldError { field: "description", violation });
+    if description
+        .chars()
+        .any(|character| character.is_control() && !matches!(character, '\n' | '\r' | '\t'))
+    {
+        return error(Violation::ControlCharacter);
+    }
+    if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+        return error(Violation::TooLong { max: MAX_DESCRIPTION_LENGTH });
+    }
+    Ok(description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(result: Result<String, FieldError>) -> Violation {
+        result.expect_err("rejected").violation
+    }
+
+    #[test]
+    fn titles_are_trimmed_and_must_not_be_blank() {
+        assert_eq!(validate_title("  Water the plants \n").unwrap(), "Water the plants");
+        assert_eq!(violation(validate_title("")), Violation::Blank);
+        assert_eq!(violation(validate_title(" \t\n ")), Violation::Blank);
+    }
+
+    #[test]
+    fn titles_are_single_lines_without_control_characters() {
+        assert_eq!(violation(validate_title("Ring the bell\u{7}")), Violation::ControlCharacter);
+        assert_eq!(violation(validate_title("Water\nthe plants")), Violation::ControlCharacter);
+        assert_eq!(violation(validate_title("Water\tthe plants")), Violation::ControlCharacter);
+    }
+
+    #[test]
+    fn title_length_is_counted_in_characters() {
+        let longest = "ä".repeat(MAX_TITLE_LENGTH);
+        assert_eq!(validate_title(&longest).unwrap(), longest);
+        let error = validate_title(&"ä".repeat(MAX_TITLE_LENGTH + 1)).unwrap_err();
+        assert_eq!(error.violation, Violation::TooLong { max: MAX_TITLE_LENGTH });
+        assert_eq!(error.to_string(), "title must not be longer than 200 characters");
+    }
+
+    #[test]
+    fn length_is_counted_after_normalization() {
+        // "a" followed by a combining diaeresis composes into the single character "ä"
+        let decomposed = "a\u{308}".repeat(MAX_TITLE_LENGTH);
+        assert_eq!(decomposed.chars().count(), 2 * MAX_TITLE_LENGTH);
+        let title = validate_title(&decomposed).unwrap();
+        assert_eq!(title, "ä".repeat(MAX_TITLE_LENGTH));
+        assert_eq!(validate_description("a\u{308}").unwrap(), "ä");
+    }
+
+    #[test]
+    fn descriptions_keep_line_breaks_and_tabs() {
+        let description = "  - soil\r\n\t- water\n";
+        assert_eq!(validate_description(description).unwrap(), description);
+        assert_eq!(validate_description("").unwrap(), "");
+        assert_eq!(violation(validate_description("Ring the bell\u{7}")), Violation::ControlCharacter);
+        let error = validate_description(&"x".repeat(MAX_DESCRIPTION_LENGTH + 1)).unwrap_err();
+        assert_eq!(error.field, "description");
+        assert_eq!(error.violation, Violation::TooLong { max: MAX_DESCRIPTION_LENGTH });
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+
+/// Task events a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WebhookEvent {
+    #[default]
+    TaskCreated,
+    TaskUpdated,
+    /// The task was moved to the trash or purged.
+    TaskDeleted,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 3] = [WebhookEvent::TaskCreated, WebhookEvent::TaskUpdated, WebhookEvent::TaskDeleted];
+
+    /// Name used in payloads, headers and storage.
+    pub fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::TaskCreated => "taskCreated",
+            WebhookEvent::TaskUpdated => "taskUpdated",
+            WebhookEvent::TaskDeleted => "taskDeleted",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<WebhookEvent> {
+        WebhookEvent::ALL.into_iter().find(|event| event.name() == name)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Webhook {
+    pub id: i64,
+    /// Workspace whose task events the webhook receives.
+    pub workspace_id: i64,
+    /// HTTP(S) endpoint the events are POSTed to.
+    pub url: String,
+    /// Events the webhook receives, all of them when empty.
+    pub events: Vec<WebhookEvent>,
+    /// Key of the HMAC-SHA256 payload signature, never returned by the API.
+    pub secret: String,
+    /// Inactive webhooks keep their configuration and delivery log but receive nothing.
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn receives(&self, event: WebhookEvent) -> bool {
+        self.active && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or for a retry.
+    #[default]
+    Pending,
+    Succeeded,
+    /// Given up after the last retry.
+    Failed,
+}
+
+/// One event sent to one webhook, with the outcome of its latest attempt.
+#[derive(Debug, Default, Clone)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: WebhookEvent,
+    /// JSON body, fixed when the event happened so that every attempt sends the same bytes.
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// When the next attempt is due, absent once the delivery is settled or being attempted.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// HTTP status of the latest response, absent when the receiver could not be reached.
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    /// Delivery this one was manually redelivered from.
+    pub redelivery_of: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
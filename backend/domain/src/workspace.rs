@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+
+/// A shared space of tasks; users see and change the tasks of the workspaces they belong to.
+#[derive(Debug, Default, Clone)]
+pub struct Workspace {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What a member may do in a workspace. Variants are declared from least to most privileged, so
+/// the derived ordering compares privileges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum WorkspaceRole {
+    /// Reads tasks only.
+    Viewer,
+    /// Creates and changes tasks.
+    #[default]
+    Member,
+    /// Also purges tasks from the trash and manages webhooks.
+    Admin,
+    /// Also manages who belongs to the workspace. Every workspace keeps at least one owner.
+    Owner,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct WorkspaceMember {
+    pub workspace_id: i64,
+    pub user_id: i64,
+    pub role: WorkspaceRole,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,79 @@
+use crate::api::authentication::Authenticated;
+use crate::api::problem::{json_error_handler, path_error_handler, problem_response};
+use crate::mapper::api_token_mapper::{ToApiTokenApiModel, ToCreatedApiTokenApiModel, to_new_api_token_dto};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse, Responder, Scope};
+use application::auth::api_token_error::ApiTokenError;
+use application::auth::api_token_service_trait::ApiTokenServiceTrait;
+use openapi_client::models::{ApiToken as ApiTokenApiModel, NewApiToken};
+use std::sync::Arc;
+
+/// The signed-in user's personal API tokens.
+#[derive(Clone)]
+pub struct ApiTokenController {
+    api_token_service: Arc<dyn ApiTokenServiceTrait>,
+}
+
+impl ApiTokenController {
+    pub fn new(api_token_service: Arc<dyn ApiTokenServiceTrait>) -> Self {
+        Self { api_token_service }
+    }
+
+    pub fn configure(&self) -> Scope {
+        web::scope("/me/tokens")
+            .app_data(web::Data::new(self.api_token_service.clone()))
+            .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+            .app_data(web::PathConfig::default().error_handler(path_error_handler))
+            .route("", web::get().to(Self::list_tokens))
+            .route("", web::post().to(Self::create_token))
+            .route("/{id}", web::delete().to(Self::revoke_token))
+    }
+
+    async fn list_tokens(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn ApiTokenServiceTrait>>,
+    ) -> impl Responder {
+        match service.find_tokens(principal.user_id).await {
+            Ok(api_tokens) => {
+                let api_tokens: Vec<ApiTokenApiModel> =
+                    api_tokens.into_iter().map(ToApiTokenApiModel::to_api_model).collect();
+                HttpResponse::Ok().json(api_tokens)
+            }
+            Err(e) => error_response("listing", e),
+        }
+    }
+
+    async fn create_token(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn ApiTokenServiceTrait>>,
+        payload: web::Json<NewApiToken>,
+    ) -> impl Responder {
+        match service.create_token(principal.user_id, to_new_api_token_dto(payload.into_inner())).await {
+            Ok(created) => HttpResponse::Created().json(created.to_api_model()),
+            Err(e) => error_response("creating", e),
+        }
+    }
+
+    async fn revoke_token(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn ApiTokenServiceTrait>>,
+    ) -> impl Responder {
+        match service.revoke_token(principal.user_id, path.into_inner()).await {
+            Ok(true) => HttpResponse::NoContent().finish(),
+            Ok(false) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("revoking", e),
+        }
+    }
+}
+
+/// Rejected tokens are reported as problems, anything else is logged.
+fn error_response(action: &str, error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<ApiTokenError>() {
+        Some(error) => problem_response(StatusCode::UNPROCESSABLE_ENTITY, error.code(), error.to_string()),
+        None => {
+            eprintln!("Error {action} API token: {:?}", error);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::api::problem::{json_error_handler, problem_response};
+use crate::mapper::user_mapper::ToAuthSessionApiModel;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse, Responder, Scope};
+use application::auth::auth_dto::{LoginDto, RegisterDto};
+use application::auth::auth_error::AuthError;
+use application::auth::auth_service_trait::AuthServiceTrait;
+use openapi_client::models::{LoginRequest, RefreshRequest, RegisterRequest};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct AuthController {
+    auth_service: Arc<dyn AuthServiceTrait>,
+}
+
+impl AuthController {
+    pub fn new(auth_service: Arc<dyn AuthServiceTrait>) -> Self {
+        Self { auth_service }
+    }
+
+    pub fn configure(&self) -> Scope {
+        web::scope("/auth")
+            .app_data(web::Data::new(self.auth_service.clone()))
+            .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+            .route("/register", web::post().to(Self::register))
+            .route("/login", web::post().to(Self::login))
+            .route("/refresh", web::post().to(Self::refresh))
+            .route("/logout", web::post().to(Self::logout))
+    }
+
+    async fn register(
+        service: web::Data<Arc<dyn AuthServiceTrait>>,
+        payload: web::Json<RegisterRequest>,
+    ) -> impl Responder {
+        let payload = payload.into_inner();
+        let registration = RegisterDto {
+            email: payload.email,
+            password: payload.password,
+            display_name: payload.display_name,
+        };
+        match service.register(registration).await {
+            Ok(session) => HttpResponse::Created().json(session.to_api_model()),
+            Err(e) => error_response("registering", e),
+        }
+    }
+
+    async fn login(
+        service: web::Data<Arc<dyn AuthServiceTrait>>,
+        payload: web::Json<LoginRequest>,
+    ) -> impl Responder {
+        let payload = payload.into_inner();
+        let login = LoginDto { email: payload.email, password: payload.password };
+        match service.login(login).await {
+            Ok(session) => HttpResponse::Ok().json(session.to_api_model()),
+            Err(e) => error_response("signing in", e),
+        }
+    }
+
+    async fn refresh(
+        service: web::Data<Arc<dyn AuthServiceTrait>>,
+        payload: web::Json<RefreshRequest>,
+    ) -> impl Responder {
+        match service.refresh(&payload.refresh_token).await {
+            Ok(session) => HttpResponse::Ok().json(session.to_api_model()),
+            Err(e) => error_response("refreshing the session of", e),
+        }
+    }
+
+    async fn logout(
+        service: web::Data<Arc<dyn AuthServiceTrait>>,
+        payload: web::Json<RefreshRequest>,
+    ) -> impl Responder {
+        match service.logout(&payload.refresh_token).await {
+            Ok(()) => HttpResponse::NoContent().finish(),
+            Err(e) => error_response("signing out", e),
+        }
+    }
+}
+
+/// Rejected credentials are reported as problems, anything else is logged.
+fn error_response(action: &str, error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<AuthError>() {
+        Some(error) => {
+            let status = match error {
+                AuthError::EmailTaken => StatusCode::CONFLICT,
+                AuthError::InvalidCredentials | AuthError::InvalidRefreshToken => StatusCode::UNAUTHORIZED,
+                _ => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            problem_response(status, error.code(), error.to_string())
+        }
+        None => {
+            eprintln!("Error {action} user: {:?}", error);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::api::problem::problem_response;
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::error::InternalError;
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::{Method, StatusCode};
+use actix_web::middleware::Next;
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use application::auth::api_token_service_trait::ApiTokenServiceTrait;
+use application::auth::auth_service_trait::AuthServiceTrait;
+use application::auth::principal::Principal;
+use domain::api_token::{API_TOKEN_PREFIX, TokenScope};
+use serde::Deserialize;
+use std::future::{ready, Ready};
+use std::sync::Arc;
+
+/// Header scripts may send their API token in, as an alternative to `Authorization: Bearer`.
+const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Endpoints that take a POST to read, which read-only API tokens may call as well.
+const READING_POSTS: [&str; 1] = ["/tasks/filter"];
+
+/// Endpoints for `EventSource` and `WebSocket` clients, the only ones that take the access token
+/// as query parameter. Elsewhere it would only end up in logs, `Referer` headers and history.
+const QUERY_TOKEN_PATHS: [&str; 2] = ["/tasks/events", "/collaboration/ws"];
+
+/// The signed-in user making the request. Extracting it rejects anonymous requests with 401.
+#[derive(Debug, Clone)]
+pub struct Authenticated(pub Principal);
+
+impl FromRequest for Authenticated {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(authenticate(request).map(Authenticated))
+    }
+}
+
+/// Middleware for scopes that are closed to anonymous requests. The principal is left in the
+/// request extensions for the `Authenticated` extractor.
+pub async fn require_authentication(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let principal = authenticate(request.request());
+    proceed(request, next, principal).await
+}
+
+/// Like `require_authentication`, but also lets scripts in with an API token, passed as bearer
+/// token or in the `X-API-Key` header. Read-only tokens are refused anything but reads with 403.
+pub async fn require_authentication_or_api_token(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let principal = match authenticate_api_token(request.request()).await {
+        Ok(Some(principal)) if principal.scope == TokenScope::Read && !reads(&request) => Err(insufficient_scope()),
+        Ok(Some(principal)) => Ok(principal),
+        Ok(None) => authenticate(request.request()),
+        Err(error) => Err(error),
+    };
+    proceed(request, next, principal).await
+}
+
+async fn proceed<B: MessageBody>(
+    request: ServiceRequest,
+    next: Next<B>,
+    principal: Result<Principal, actix_web::Error>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    match principal {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            Ok(next.call(request).await?.map_into_left_body())
+        }
+        // answered here rather than failed, so that the CORS headers are still added
+        Err(error) => Ok(request.error_response(error).map_into_right_body()),
+    }
+}
+
+fn reads(request: &ServiceRequest) -> bool {
+    request.method().is_safe() || (request.method() == Method::POST && READING_POSTS.contains(&request.path()))
+}
+
+#[derive(Deserialize)]
+struct AccessTokenQuery {
+    access_token: Option<String>,
+}
+
+/// The token from the `Authorization` header, or from the `access_token` query parameter for
+/// `EventSource` and `WebSocket` clients, which cannot set headers.
+fn bearer_token(request: &HttpRequest) -> Option<String> {
+    let header = request.headers().get(header::AUTHORIZATION).and_then(|value| value.to_str().ok());
+    if let Some(header) = header {
+        let (scheme, token) = header.split_once(' ')?;
+        return scheme.eq_ignore_ascii_case("bearer").then(|| token.trim().to_string());
+    }
+    if request.method() != Method::GET || !QUERY_TOKEN_PATHS.contains(&request.path()) {
+        return None;
+    }
+    web::Query::<AccessTokenQuery>::from_query(request.query_string())
+        .ok()?
+        .into_inner()
+        .access_token
+}
+
+/// The API token from the `X-API-Key` header, or a bearer token that is recognizably one.
+fn api_token(request: &HttpRequest) -> Option<String> {
+    if let Some(api_key) = request.headers().get(API_KEY_HEADER) {
+        return api_key.to_str().ok().map(|api_key| api_key.trim().to_string());
+    }
+    bearer_token(request).filter(|token| token.starts_with(API_TOKEN_PREFIX))
+}
+
+fn authenticate(request: &HttpRequest) -> Result<Principal, actix_web::Error> {
+    if let Some(principal) = request.extensions().get::<Principal>() {
+        return Ok(principal.clone());
+    }
+    let Some(auth_service) = request.app_data::<web::Data<Arc<dyn AuthServiceTrait>>>() else {
+        return Err(not_configured(request));
+    };
+    bearer_token(request)
+        .and_then(|access_token| auth_service.authenticate(&access_token))
+        .ok_or_else(unauthorized)
+}
+
+/// The principal of the request's API token, or none if it was sent without one.
+async fn authenticate_api_token(request: &HttpRequest) -> Result<Option<Principal>, actix_web::Error> {
+    let Some(token) = api_token(request) else {
+        return Ok(None);
+    };
+    let Some(api_token_service) = request.app_data::<web::Data<Arc<dyn ApiTokenServiceTrait>>>().cloned() else {
+        return Err(not_configured(request));
+    };
+    match api_token_service.authenticate(&token).await {
+        Ok(Some(principal)) => Ok(Some(principal)),
+        Ok(None) => Err(unauthorized()),
+        Err(e) => {
+            eprintln!("Error authenticating API token: {:?}", e);
+            Err(InternalError::new("authentication failed", StatusCode::INTERNAL_SERVER_ERROR).into())
+        }
+    }
+}
+
+fn not_configured(request: &HttpRequest) -> actix_web::Error {
+    eprintln!("No authentication service is configured for {}", request.path());
+    InternalError::new("authentication is not configured", StatusCode::INTERNAL_SERVER_ERROR).into()
+}
+
+fn unauthorized() -> actix_web::Error {
+    let mut response = problem_response(
+        StatusCode::UNAUTHORIZED,
+        "unauthenticated",
+        "a valid access token is required".to_string(),
+    );
+    response
+        .headers_mut()
+        .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    InternalError::from_response("unauthenticated", response).into()
+}
+
+fn insufficient_scope() -> actix_web::Error {
+    let response = problem_response(
+        StatusCode::FORBIDDEN,
+        "insufficient-scope",
+        "the API token is read-only".to_string(),
+    );
+    InternalError::from_response("insufficient scope", response).into()
+}
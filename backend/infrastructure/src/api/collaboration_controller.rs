@@ -0,0 +1,99 @@
+use crate::api::authentication::Authenticated;
+use crate::event::collaboration_broker::CollaborationBroker;
+use crate::mapper::collaboration_message_mapper::{ClientMessage, ServerMessage};
+use actix_web::{rt, web, HttpRequest, HttpResponse, Scope};
+use actix_ws::Message;
+use application::workspace::workspace_policy::WorkspaceAction;
+use application::workspace::workspace_service_trait::WorkspaceServiceTrait;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct CollaborationController {
+    broker: Arc<CollaborationBroker>,
+    workspace_service: Arc<dyn WorkspaceServiceTrait>,
+}
+
+impl CollaborationController {
+    pub fn new(broker: Arc<CollaborationBroker>, workspace_service: Arc<dyn WorkspaceServiceTrait>) -> Self {
+        Self { broker, workspace_service }
+    }
+
+    pub fn configure(&self) -> Scope {
+        web::scope("/collaboration")
+            .app_data(web::Data::new(self.broker.clone()))
+            .app_data(web::Data::new(self.workspace_service.clone()))
+            .route("/ws", web::get().to(Self::connect))
+    }
+
+    /// Upgrades to a WebSocket that exchanges JSON messages with the collaboration hub. Other
+    /// clients see the signed-in user's display name.
+    async fn connect(
+        Authenticated(principal): Authenticated,
+        req: HttpRequest,
+        body: web::Payload,
+        broker: web::Data<Arc<CollaborationBroker>>,
+        workspace_service: web::Data<Arc<dyn WorkspaceServiceTrait>>,
+    ) -> actix_web::Result<HttpResponse> {
+        // workspaces joined later are picked up on reconnect, as for the task event stream
+        let workspace_ids: HashSet<i64> = match workspace_service.find_workspaces(principal.user_id).await {
+            Ok(memberships) => memberships.into_iter().map(|membership| membership.workspace.id).collect(),
+            Err(e) => {
+                eprintln!("Error finding workspaces of collaborator: {:?}", e);
+                return Ok(HttpResponse::InternalServerError().finish());
+            }
+        };
+        let (response, session, mut messages) = actix_ws::handle(&req, body)?;
+        let broker = broker.get_ref().clone();
+        let (client_id, mut outgoing) = broker.connect(principal.display_name, workspace_ids);
+
+        let mut outgoing_session = session.clone();
+        let workspace_service = workspace_service.get_ref().clone();
+        let user_id = principal.user_id;
+        rt::spawn(async move {
+            while let Some(message) = outgoing.recv().await {
+                // asked again for every change, so that removed or demoted members stop hearing of them
+                if let Some(workspace_id) = message.task_workspace_id() {
+                    match workspace_service.is_allowed(user_id, workspace_id, WorkspaceAction::ViewTasks).await {
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(e) => {
+                            eprintln!("Error checking access to task change: {:?}", e);
+                            continue;
+                        }
+                    }
+                }
+                let text = match serde_json::to_string(&ServerMessage::from(message)) {
+                    Ok(text) => text,
+                    Err(error) => {
+                        eprintln!("Collaboration message could not be serialized: {:?}", error);
+                        continue;
+                    }
+                };
+                if outgoing_session.text(text).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut session = session;
+        rt::spawn(async move {
+            while let Some(Ok(message)) = messages.recv().await {
+                match message {
+                    Message::Text(text) => match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(message) => broker.handle(client_id, message.into()),
+                        Err(error) => broker.reject(client_id, error.to_string()),
+                    },
+                    Message::Ping(bytes) if session.pong(&bytes).await.is_err() => break,
+                    Message::Close(_) => break,
+                    _ => {}
+                }
+            }
+            // dropping the connection's queue also ends the sending task
+            broker.disconnect(client_id);
+            let _ = session.close(None).await;
+        });
+
+        Ok(response)
+    }
+}
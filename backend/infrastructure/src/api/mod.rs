@@ -1 +1,12 @@
 pub mod task_controller;
+pub mod tag_controller;
+pub mod project_controller;
+pub mod problem;
+pub mod collaboration_controller;
+
+pub mod webhook_controller;
+pub mod auth_controller;
+pub mod authentication;
+pub mod api_token_controller;
+pub mod oidc_controller;
+pub mod workspace_controller;
\ No newline at end of file
@@ -0,0 +1,127 @@
+use crate::api::problem::{json_error_handler, problem_response};
+use crate::auth::oidc_client::{OidcClient, OidcError, LOGIN_TIMEOUT};
+use crate::mapper::user_mapper::ToAuthSessionApiModel;
+use actix_web::cookie::{time, Cookie, SameSite};
+use actix_web::http::{header, StatusCode};
+use actix_web::{web, HttpRequest, HttpResponse, Responder, Scope};
+use application::auth::auth_error::AuthError;
+use application::auth::auth_service_trait::AuthServiceTrait;
+use openapi_client::models::OidcCodeExchange;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Single sign-on at an OpenID Connect provider. The browser is sent to the provider and back to
+/// the callback, which redirects to the frontend with `#code=...`, a one-time code the frontend
+/// exchanges for the session at `/auth/oidc/session`, or with `#error=<code>`. The state of the
+/// login is also kept in a cookie, so that the callback only completes logins the same browser
+/// started.
+#[derive(Clone)]
+pub struct OidcController {
+    oidc_client: Arc<OidcClient>,
+    auth_service: Arc<dyn AuthServiceTrait>,
+}
+
+/// Carries the state from the login to the callback.
+const STATE_COOKIE: &str = "oidc_state";
+
+#[derive(Deserialize)]
+struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+impl OidcController {
+    pub fn new(oidc_client: Arc<OidcClient>, auth_service: Arc<dyn AuthServiceTrait>) -> Self {
+        Self { oidc_client, auth_service }
+    }
+
+    pub fn configure(&self) -> Scope {
+        web::scope("/auth/oidc")
+            .app_data(web::Data::new(self.oidc_client.clone()))
+            .app_data(web::Data::new(self.auth_service.clone()))
+            .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+            .route("/login", web::get().to(Self::login))
+            .route("/callback", web::get().to(Self::callback))
+            .route("/session", web::post().to(Self::session))
+    }
+
+    async fn login(client: web::Data<Arc<OidcClient>>) -> impl Responder {
+        let (authorization_url, state) = client.start_login();
+        let mut response = redirect(&authorization_url);
+        let max_age = time::Duration::seconds(LOGIN_TIMEOUT.as_secs() as i64);
+        if let Err(e) = response.add_cookie(&state_cookie(&client, state).max_age(max_age).finish()) {
+            eprintln!("Error setting the single sign-on state cookie: {:?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+        response
+    }
+
+    async fn callback(
+        request: HttpRequest,
+        query: web::Query<CallbackQuery>,
+        client: web::Data<Arc<OidcClient>>,
+        service: web::Data<Arc<dyn AuthServiceTrait>>,
+    ) -> impl Responder {
+        let query = query.into_inner();
+        // a login started in another browser must not sign this one in (login CSRF)
+        let cookie_state = request.cookie(STATE_COOKIE).map(|cookie| cookie.value().to_string());
+        let state = query.state.filter(|state| cookie_state.as_ref() == Some(state));
+        let identity = match (query.error, query.code, state) {
+            (Some(error), _, state) => {
+                if let Some(state) = state {
+                    client.abandon_login(&state);
+                }
+                Err(OidcError::AuthorizationDenied(error))
+            }
+            (None, Some(code), Some(state)) => client.complete_login(&code, &state).await,
+            _ => Err(OidcError::InvalidState),
+        };
+        let fragment = match identity {
+            Ok(identity) => match service.sign_in_with_identity(identity).await {
+                Ok(session) => format!("code={}", client.hand_off(session)),
+                Err(e) => match e.downcast_ref::<AuthError>() {
+                    Some(error) => format!("error={}", error.code()),
+                    None => {
+                        eprintln!("Error signing in user with single sign-on: {:?}", e);
+                        "error=sso-failed".to_string()
+                    }
+                },
+            },
+            Err(error) => {
+                eprintln!("Single sign-on failed: {error}");
+                format!("error={}", error.code())
+            }
+        };
+        let mut response = redirect(&format!("{}#{fragment}", client.post_login_url()));
+        // the state is of no use once the provider sent the browser back
+        let _ = response.add_removal_cookie(&state_cookie(&client, String::new()).finish());
+        response
+    }
+
+    async fn session(client: web::Data<Arc<OidcClient>>, payload: web::Json<OidcCodeExchange>) -> impl Responder {
+        match client.take_session(&payload.code) {
+            Some(session) => HttpResponse::Ok().json(session.to_api_model()),
+            None => problem_response(
+                StatusCode::UNAUTHORIZED,
+                "invalid-sso-code",
+                "the code is unknown, was exchanged already or has expired".to_string(),
+            ),
+        }
+    }
+}
+
+fn state_cookie(client: &OidcClient, state: String) -> actix_web::cookie::CookieBuilder<'static> {
+    Cookie::build(STATE_COOKIE, state)
+        .path("/auth/oidc")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .secure(client.redirect_url().starts_with("https://"))
+}
+
+fn redirect(location: &str) -> HttpResponse {
+    HttpResponse::Found()
+        .insert_header((header::LOCATION, location))
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .finish()
+}
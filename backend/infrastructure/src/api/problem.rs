@@ -0,0 +1,122 @@
+use actix_web::error::{InternalError, JsonPayloadError, PathError, QueryPayloadError};
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use application::task::task_error::{TaskError, TaskErrorKind};
+use serde::Serialize;
+use std::fmt;
+
+/// RFC 7807 problem details, sent as `application/problem+json`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProblemDetails {
+    #[serde(rename = "type")]
+    problem_type: &'static str,
+    title: &'static str,
+    status: u16,
+    detail: String,
+    /// Machine-readable reason, see `TaskError::code`.
+    code: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    invalid_params: Vec<InvalidParam>,
+}
+
+/// Field-level reason of a validation failure, so that clients can show it next to the input.
+#[derive(Serialize)]
+struct InvalidParam {
+    name: &'static str,
+    reason: String,
+}
+
+impl ProblemDetails {
+    fn new(status: StatusCode, code: &'static str, detail: String) -> Self {
+        ProblemDetails {
+            problem_type: "about:blank",
+            title: status.canonical_reason().unwrap_or("Error"),
+            status: status.as_u16(),
+            detail,
+            code,
+            invalid_params: Vec::new(),
+        }
+    }
+
+    /// Describes a task error, also for the failed items of bulk requests.
+    pub(crate) fn from_task_error(error: &TaskError) -> Self {
+        // storage failures are logged but not disclosed to clients
+        let detail = match error {
+            TaskError::Storage(error) => {
+                eprintln!("Task storage failure: {:?}", error);
+                "the task could not be read or written, please try again later".to_string()
+            }
+            task_error => task_error.to_string(),
+        };
+        let mut problem = ProblemDetails::new(task_error_status(error), error.code(), detail);
+        if let TaskError::InvalidField(error) = error {
+            problem.invalid_params.push(InvalidParam { name: error.field, reason: error.to_string() });
+        }
+        problem
+    }
+
+    fn into_response(self) -> HttpResponse {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        HttpResponse::build(status).content_type("application/problem+json").json(self)
+    }
+}
+
+pub fn problem_response(status: StatusCode, code: &'static str, detail: String) -> HttpResponse {
+    ProblemDetails::new(status, code, detail).into_response()
+}
+
+/// Request bodies, queries, paths and headers that cannot be parsed are reported as problems too.
+pub fn bad_request(error: impl fmt::Display + fmt::Debug + 'static) -> actix_web::Error {
+    let response = problem_response(StatusCode::BAD_REQUEST, "bad-request", error.to_string());
+    InternalError::from_response(error, response).into()
+}
+
+pub fn json_error_handler(error: JsonPayloadError, _: &HttpRequest) -> actix_web::Error {
+    bad_request(error)
+}
+
+pub fn query_error_handler(error: QueryPayloadError, _: &HttpRequest) -> actix_web::Error {
+    bad_request(error)
+}
+
+pub fn path_error_handler(error: PathError, _: &HttpRequest) -> actix_web::Error {
+    bad_request(error)
+}
+
+/// Lets task handlers return `TaskError`s with `?`.
+#[derive(Debug)]
+pub struct TaskProblem(TaskError);
+
+impl From<TaskError> for TaskProblem {
+    fn from(error: TaskError) -> Self {
+        TaskProblem(error)
+    }
+}
+
+impl fmt::Display for TaskProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+fn task_error_status(error: &TaskError) -> StatusCode {
+    match error.kind() {
+        TaskErrorKind::NotFound => StatusCode::NOT_FOUND,
+        TaskErrorKind::Validation => StatusCode::UNPROCESSABLE_ENTITY,
+        TaskErrorKind::Conflict => StatusCode::CONFLICT,
+        TaskErrorKind::Forbidden => StatusCode::FORBIDDEN,
+        TaskErrorKind::PreconditionFailed => StatusCode::PRECONDITION_FAILED,
+        TaskErrorKind::Storage => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+impl ResponseError for TaskProblem {
+    fn status_code(&self) -> StatusCode {
+        task_error_status(&self.0)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ProblemDetails::from_task_error(&self.0).into_response()
+    }
+}
@@ -0,0 +1,154 @@
+use crate::api::authentication::Authenticated;
+use crate::api::problem::problem_response;
+use crate::mapper::project_mapper::ToProjectApiModel;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse, Responder, Scope};
+use application::project::project_service_trait::ProjectServiceTrait;
+use application::workspace::workspace_error::WorkspaceError;
+use openapi_client::models::{NewProject, Project as ProjectApiModel, UpdateProject};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListProjectsParams {
+    include_archived: Option<bool>,
+}
+
+#[derive(Clone)]
+pub struct ProjectController {
+    project_service: Arc<dyn ProjectServiceTrait>,
+}
+
+impl ProjectController {
+    pub fn new(project_service: Arc<dyn ProjectServiceTrait>) -> Self {
+        Self { project_service }
+    }
+
+    pub fn configure(&self) -> Scope {
+        web::scope("/projects")
+            .app_data(web::Data::new(self.project_service.clone()))
+            .route("", web::get().to(Self::list_projects))
+            .route("", web::post().to(Self::create_project))
+            .route("/{id}", web::get().to(Self::get_project))
+            .route("/{id}", web::put().to(Self::update_project))
+            .route("/{id}", web::delete().to(Self::delete_project))
+            .route("/{id}/archive", web::post().to(Self::archive_project))
+            .route("/{id}/unarchive", web::post().to(Self::unarchive_project))
+    }
+
+    async fn list_projects(
+        Authenticated(principal): Authenticated,
+        params: web::Query<ListProjectsParams>,
+        service: web::Data<Arc<dyn ProjectServiceTrait>>,
+    ) -> impl Responder {
+        let include_archived = params.include_archived.unwrap_or(false);
+        match service.find_projects(principal.user_id, include_archived).await {
+            Ok(projects) => {
+                let api_projects: Vec<ProjectApiModel> =
+                    projects.into_iter().map(ToProjectApiModel::to_api_model).collect();
+                HttpResponse::Ok().json(api_projects)
+            }
+            Err(e) => {
+                eprintln!("Error listing projects: {:?}", e);
+                HttpResponse::InternalServerError().finish()
+            }
+        }
+    }
+
+    async fn create_project(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn ProjectServiceTrait>>,
+        payload: web::Json<NewProject>,
+    ) -> impl Responder {
+        let payload = payload.into_inner();
+        match service.create_project(principal.user_id, payload.workspace_id, payload.name).await {
+            Ok(project) => HttpResponse::Created().json(ToProjectApiModel::to_api_model(project)),
+            Err(e) => error_response("creating", e),
+        }
+    }
+
+    async fn get_project(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn ProjectServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.get_project(principal.user_id, id).await {
+            Ok(Some(project)) => HttpResponse::Ok().json(ToProjectApiModel::to_api_model(project)),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("fetching", e),
+        }
+    }
+
+    async fn update_project(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        payload: web::Json<UpdateProject>,
+        service: web::Data<Arc<dyn ProjectServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.rename_project(principal.user_id, id, payload.into_inner().name).await {
+            Ok(Some(project)) => HttpResponse::Ok().json(ToProjectApiModel::to_api_model(project)),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("updating", e),
+        }
+    }
+
+    async fn delete_project(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn ProjectServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.delete_project(principal.user_id, id).await {
+            Ok(true) => HttpResponse::NoContent().finish(),
+            Ok(false) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("deleting", e),
+        }
+    }
+
+    async fn archive_project(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn ProjectServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.archive_project(principal.user_id, id).await {
+            Ok(Some(project)) => HttpResponse::Ok().json(ToProjectApiModel::to_api_model(project)),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("archiving", e),
+        }
+    }
+
+    async fn unarchive_project(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn ProjectServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.unarchive_project(principal.user_id, id).await {
+            Ok(Some(project)) => HttpResponse::Ok().json(ToProjectApiModel::to_api_model(project)),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("unarchiving", e),
+        }
+    }
+}
+
+/// Refused requests are reported as problems, anything else is logged.
+fn error_response(action: &str, error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<WorkspaceError>() {
+        Some(error) => {
+            let status = match error {
+                WorkspaceError::Forbidden(_) => StatusCode::FORBIDDEN,
+                // only the workspace named in the body can be missing
+                _ => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            problem_response(status, error.code(), error.to_string())
+        }
+        None => {
+            eprintln!("Error {action} project: {:?}", error);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
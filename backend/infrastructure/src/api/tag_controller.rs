@@ -0,0 +1,119 @@
+use crate::api::authentication::Authenticated;
+use crate::api::problem::problem_response;
+use crate::mapper::tag_mapper::ToTagApiModel;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse, Responder, Scope};
+use application::tag::tag_service_trait::TagServiceTrait;
+use application::workspace::workspace_error::WorkspaceError;
+use openapi_client::models::{NewTag, Tag as TagApiModel, UpdateTag};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct TagController {
+    tag_service: Arc<dyn TagServiceTrait>,
+}
+
+impl TagController {
+    pub fn new(tag_service: Arc<dyn TagServiceTrait>) -> Self {
+        Self { tag_service }
+    }
+
+    pub fn configure(&self) -> Scope {
+        web::scope("/tags")
+            .app_data(web::Data::new(self.tag_service.clone()))
+            .route("", web::get().to(Self::list_tags))
+            .route("", web::post().to(Self::create_tag))
+            .route("/{id}", web::get().to(Self::get_tag))
+            .route("/{id}", web::put().to(Self::update_tag))
+            .route("/{id}", web::delete().to(Self::delete_tag))
+    }
+
+    async fn list_tags(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn TagServiceTrait>>,
+    ) -> impl Responder {
+        match service.find_tags(principal.user_id).await {
+            Ok(tags) => {
+                let api_tags: Vec<TagApiModel> =
+                    tags.into_iter().map(ToTagApiModel::to_api_model).collect();
+                HttpResponse::Ok().json(api_tags)
+            }
+            Err(e) => {
+                eprintln!("Error listing tags: {:?}", e);
+                HttpResponse::InternalServerError().finish()
+            }
+        }
+    }
+
+    async fn create_tag(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn TagServiceTrait>>,
+        payload: web::Json<NewTag>,
+    ) -> impl Responder {
+        let payload = payload.into_inner();
+        match service.create_tag(principal.user_id, payload.workspace_id, payload.name, payload.color).await {
+            Ok(tag) => HttpResponse::Created().json(ToTagApiModel::to_api_model(tag)),
+            Err(e) => error_response("creating", e),
+        }
+    }
+
+    async fn get_tag(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn TagServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.get_tag(principal.user_id, id).await {
+            Ok(Some(tag)) => HttpResponse::Ok().json(ToTagApiModel::to_api_model(tag)),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("fetching", e),
+        }
+    }
+
+    async fn update_tag(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        payload: web::Json<UpdateTag>,
+        service: web::Data<Arc<dyn TagServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        let payload = payload.into_inner();
+
+        match service.update_tag(principal.user_id, id, payload.name, payload.color).await {
+            Ok(Some(tag)) => HttpResponse::Ok().json(ToTagApiModel::to_api_model(tag)),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("updating", e),
+        }
+    }
+
+    async fn delete_tag(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn TagServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.delete_tag(principal.user_id, id).await {
+            Ok(true) => HttpResponse::NoContent().finish(),
+            Ok(false) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("deleting", e),
+        }
+    }
+}
+
+/// Refused requests are reported as problems, anything else is logged.
+fn error_response(action: &str, error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<WorkspaceError>() {
+        Some(error) => {
+            let status = match error {
+                WorkspaceError::Forbidden(_) => StatusCode::FORBIDDEN,
+                // only the workspace named in the body can be missing
+                _ => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            problem_response(status, error.code(), error.to_string())
+        }
+        None => {
+            eprintln!("Error {action} tag: {:?}", error);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
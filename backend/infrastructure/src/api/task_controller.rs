@@ -1,103 +1,508 @@
+use crate::mapper::bulk_task_mapper::{BulkTaskRequest, ToBulkRequestDto, ToBulkTaskResponse};
+use crate::mapper::new_task_mapper::ToNewTaskDto;
+use crate::mapper::priority_mapper::PriorityMapper;
 use crate::mapper::task_filter_and_sort_mapper::ToTaskFilterAndSortDto;
 use crate::mapper::task_mapper::ToApiModel;
-use actix_web::{web, HttpResponse, Responder, Scope};
+use crate::mapper::task_page_mapper::ToTaskPageApiModel;
+use crate::mapper::task_patch_mapper::{TaskPatch, ToTaskPatchDto};
+use crate::api::authentication::Authenticated;
+use crate::api::problem::{bad_request, json_error_handler, path_error_handler, query_error_handler, TaskProblem};
+use crate::event::task_event_broadcaster::{TaskEventBroadcaster, TaskEventMessage, TaskEventSubscription};
+use actix_web::dev::Payload;
+use actix_web::http::header::{self, CacheControl, CacheDirective, EntityTag, ETag};
+use actix_web::web::Bytes;
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse, HttpResponseBuilder, Scope};
+use application::task::task_error::TaskError;
 use application::task::task_service_trait::TaskServiceTrait;
-use openapi_client::models::{NewTask, RenameTask, Task as TaskApiModel, TaskFilterAndSort};
+use application::workspace::workspace_policy::WorkspaceAction;
+use application::workspace::workspace_service_trait::WorkspaceServiceTrait;
+use futures_util::{stream, Stream, StreamExt};
+use domain::task::Task;
+use openapi_client::models::{
+    ChangeTaskDescription, ChangeTaskDueDate, ChangeTaskPriority, ChangeTaskProject, ChangeTaskRecurrence, MoveTask, NewTask, RenameTask, Task as TaskApiModel,
+    TaskFilterAndSort,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::future::{ready, Ready};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+
+type TaskResponse = Result<HttpResponse, TaskProblem>;
+
+/// Comments are sent this often on idle event streams, so that proxies keep them open.
+const EVENT_STREAM_HEARTBEAT: Duration = Duration::from_secs(15);
+/// Tells event stream subscribers that they missed events and have to refetch.
+const RESET_EVENT: &[u8] = b"event: reset\ndata: {}\n\n";
+
+#[derive(Deserialize)]
+struct ForceParams {
+    #[serde(default)]
+    force: bool,
+}
+
+/// Version from the `If-Match` header that a write expects to replace; absent or `*` accepts any version.
+struct IfMatch(Option<i64>);
+
+impl FromRequest for IfMatch {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            parse_if_match(req)
+                .map(IfMatch)
+                .ok_or_else(|| bad_request("If-Match must be \"*\" or a single task ETag")),
+        )
+    }
+}
+
+fn parse_if_match(req: &HttpRequest) -> Option<Option<i64>> {
+    let Some(value) = req.headers().get(header::IF_MATCH) else {
+        return Some(None);
+    };
+    let value = value.to_str().ok()?.trim();
+    if value == "*" {
+        return Some(None);
+    }
+    let value = value.strip_prefix("W/").unwrap_or(value);
+    value.strip_prefix('"')?.strip_suffix('"')?.parse().ok().map(Some)
+}
 
 #[derive(Clone)]
 pub struct TaskController {
     task_service: Arc<dyn TaskServiceTrait>,
+    workspace_service: Arc<dyn WorkspaceServiceTrait>,
+    task_events: Arc<TaskEventBroadcaster>,
 }
 
 impl TaskController {
-    pub fn new(task_service: Arc<dyn TaskServiceTrait>) -> Self {
-        Self { task_service }
+    pub fn new(
+        task_service: Arc<dyn TaskServiceTrait>,
+        workspace_service: Arc<dyn WorkspaceServiceTrait>,
+        task_events: Arc<TaskEventBroadcaster>,
+    ) -> Self {
+        Self { task_service, workspace_service, task_events }
     }
 
     pub fn configure(&self) -> Scope {
         web::scope("/tasks")
             .app_data(web::Data::new(self.task_service.clone()))
+            .app_data(web::Data::new(self.workspace_service.clone()))
+            .app_data(web::Data::new(self.task_events.clone()))
+            .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(query_error_handler))
+            .app_data(web::PathConfig::default().error_handler(path_error_handler))
             .route("/filter", web::post().to(Self::filter_tasks))
+            .route("/bulk", web::post().to(Self::bulk_update))
+            .route("/events", web::get().to(Self::stream_events))
+            .route("/trash", web::get().to(Self::list_trashed_tasks))
+            .route("/trash/{id}", web::delete().to(Self::purge_task))
             .route("", web::post().to(Self::create_task))
             .route("/{id}", web::get().to(Self::get_task))
+            .route("/{id}", web::patch().to(Self::patch_task))
+            .route("/{id}", web::delete().to(Self::delete_task))
             .route("/{id}/done", web::put().to(Self::mark_done))
+            .route("/{id}/undone", web::put().to(Self::mark_undone))
             .route("/{id}/title", web::put().to(Self::rename_task))
+            .route("/{id}/description", web::put().to(Self::change_description))
+            .route("/{id}/due", web::put().to(Self::change_due_date))
+            .route("/{id}/priority", web::put().to(Self::change_priority))
+            .route("/{id}/recurrence", web::put().to(Self::change_recurrence))
+            .route("/{id}/project", web::put().to(Self::change_project))
+            .route("/{id}/restore", web::post().to(Self::restore_task))
+            .route("/{id}/parent", web::put().to(Self::move_task))
+            .route("/{id}/children", web::get().to(Self::list_children))
+            .route("/{id}/tags/{tag_id}", web::put().to(Self::add_tag))
+            .route("/{id}/tags/{tag_id}", web::delete().to(Self::remove_tag))
+            .route("/{id}/assignees/{user_id}", web::put().to(Self::assign_task))
+            .route("/{id}/assignees/{user_id}", web::delete().to(Self::unassign_task))
+            .route("/{id}/blockers/{blocker_id}", web::post().to(Self::add_blocker))
+            .route("/{id}/blockers/{blocker_id}", web::delete().to(Self::remove_blocker))
     }
 
-    async fn filter_tasks(service: web::Data<Arc<dyn TaskServiceTrait>>,
-                          payload: web::Json<TaskFilterAndSort>) -> impl Responder {
-        match service.find_tasks(ToTaskFilterAndSortDto::to_dto(payload.into_inner())).await {
-            Ok(tasks) => {
-                let api_tasks: Vec<TaskApiModel> =
-                    tasks.into_iter().map(ToApiModel::to_api_model).collect();
-                HttpResponse::Ok().json(api_tasks)
-            }
-            Err(e) => {
-                eprintln!("Error listing tasks: {:?}", e);
-                HttpResponse::InternalServerError().finish()
-            }
+    async fn filter_tasks(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+        payload: web::Json<TaskFilterAndSort>,
+    ) -> actix_web::Result<HttpResponse> {
+        let payload = payload.into_inner();
+        let tree = payload.tree == Some(true);
+        let filter_and_sort = ToTaskFilterAndSortDto::to_dto(payload, principal.user_id).map_err(bad_request)?;
+        let page = if tree {
+            service.find_task_tree(principal.user_id, filter_and_sort).await
+        } else {
+            service.find_tasks(principal.user_id, filter_and_sort).await
         }
+        .map_err(TaskProblem::from)?;
+        Ok(HttpResponse::Ok().json(ToTaskPageApiModel::to_api_model(page)))
+    }
+
+    async fn bulk_update(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+        payload: web::Json<BulkTaskRequest>,
+    ) -> actix_web::Result<HttpResponse> {
+        let request = payload.into_inner().to_dto(principal.user_id).map_err(bad_request)?;
+        let results = service.bulk_update(principal.user_id, request).await.map_err(TaskProblem::from)?;
+        Ok(HttpResponse::Ok().json(results.to_api_model()))
+    }
+
+    async fn stream_events(
+        Authenticated(principal): Authenticated,
+        req: HttpRequest,
+        task_events: web::Data<Arc<TaskEventBroadcaster>>,
+        workspace_service: web::Data<Arc<dyn WorkspaceServiceTrait>>,
+    ) -> actix_web::Result<HttpResponse> {
+        let last_event_id = match req.headers().get("Last-Event-ID") {
+            Some(value) => Some(
+                value
+                    .to_str()
+                    .ok()
+                    .and_then(|value| value.trim().parse().ok())
+                    .ok_or_else(|| bad_request("Last-Event-ID must be the id of an event"))?,
+            ),
+            None => None,
+        };
+        // workspaces joined later are picked up on reconnect; leaving one takes effect at once
+        let workspace_ids: HashSet<i64> = workspace_service
+            .find_workspaces(principal.user_id)
+            .await
+            .map_err(|e| TaskProblem::from(TaskError::from(e)))?
+            .into_iter()
+            .map(|membership| membership.workspace.id)
+            .collect();
+        let subscription = task_events.subscribe(last_event_id);
+        let viewer = EventViewer {
+            user_id: principal.user_id,
+            workspace_ids,
+            workspace_service: workspace_service.get_ref().clone(),
+        };
+        Ok(HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+            .streaming(event_stream(subscription, viewer)))
     }
 
     async fn create_task(
+        Authenticated(principal): Authenticated,
         service: web::Data<Arc<dyn TaskServiceTrait>>,
         payload: web::Json<NewTask>,
-    ) -> impl Responder {
-        match service.create_task(&payload.title).await {
-            Ok(task) => HttpResponse::Created().json(ToApiModel::to_api_model(task)),
-            Err(e) => {
-                eprintln!("Error creating task: {:?}", e);
-                HttpResponse::InternalServerError().finish()
-            }
-        }
+    ) -> TaskResponse {
+        let mut new_task = ToNewTaskDto::to_dto(payload.into_inner());
+        new_task.created_by = Some(principal.user_id);
+        let task = service.create_task(principal.user_id, new_task).await?;
+        Ok(task_response(HttpResponse::Created(), task))
     }
 
     async fn get_task(
+        Authenticated(principal): Authenticated,
         path: web::Path<i64>,
         service: web::Data<Arc<dyn TaskServiceTrait>>,
-    ) -> impl Responder {
-        let id = path.into_inner();
-        match service.get_task(id).await {
-            Ok(Some(task)) => HttpResponse::Ok().json(ToApiModel::to_api_model(task)),
-            Ok(None) => HttpResponse::NotFound().finish(),
-            Err(e) => {
-                eprintln!("Error fetching task: {:?}", e);
-                HttpResponse::InternalServerError().finish()
-            }
-        }
+    ) -> TaskResponse {
+        let task = service.get_task(principal.user_id, path.into_inner()).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn patch_task(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        params: web::Query<ForceParams>,
+        payload: web::Json<TaskPatch>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let patch = payload.into_inner().to_dto();
+        let task = service.update_task(principal.user_id, path.into_inner(), patch, params.force, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
     }
 
     async fn mark_done(
+        Authenticated(principal): Authenticated,
         path: web::Path<i64>,
+        params: web::Query<ForceParams>,
+        if_match: IfMatch,
         service: web::Data<Arc<dyn TaskServiceTrait>>,
-    ) -> impl Responder {
-        let id = path.into_inner();
-        match service.mark_done(id).await {
-            Ok(Some(task)) => HttpResponse::Ok().json(ToApiModel::to_api_model(task)),
-            Ok(None) => HttpResponse::NotFound().finish(),
-            Err(e) => {
-                eprintln!("Error marking task as done: {:?}", e);
-                HttpResponse::InternalServerError().finish()
-            }
-        }
+    ) -> TaskResponse {
+        let task = service.mark_done(principal.user_id, path.into_inner(), params.force, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn mark_undone(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let task = service.mark_undone(principal.user_id, path.into_inner(), if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
     }
 
-    pub async fn rename_task(
+    async fn rename_task(
+        Authenticated(principal): Authenticated,
         path: web::Path<i64>,
         new_title: web::Json<RenameTask>,
+        if_match: IfMatch,
         service: web::Data<Arc<dyn TaskServiceTrait>>,
-    ) -> impl Responder {
-        let id = path.into_inner();
+    ) -> TaskResponse {
         let new_title = new_title.into_inner().title;
+        let task = service.rename_task(principal.user_id, path.into_inner(), new_title, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn change_description(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        payload: web::Json<ChangeTaskDescription>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let description = payload.into_inner().description;
+        let task = service.change_description(principal.user_id, path.into_inner(), description, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn change_due_date(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        payload: web::Json<ChangeTaskDueDate>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let due_at = payload.into_inner().due_at;
+        let task = service.change_due_date(principal.user_id, path.into_inner(), due_at, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn change_priority(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        payload: web::Json<ChangeTaskPriority>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let priority = PriorityMapper::from(payload.into_inner().priority).into();
+        let task = service.change_priority(principal.user_id, path.into_inner(), priority, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn change_recurrence(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        payload: web::Json<ChangeTaskRecurrence>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let ChangeTaskRecurrence { rule, time_zone } = payload.into_inner();
+        let task = service.change_recurrence(principal.user_id, path.into_inner(), rule, time_zone, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn delete_task(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let task = service.delete_task(principal.user_id, path.into_inner(), if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn restore_task(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let task = service.restore_task(principal.user_id, path.into_inner(), if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn move_task(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        payload: web::Json<MoveTask>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let parent_id = payload.into_inner().parent_id;
+        let task = service.move_task(principal.user_id, path.into_inner(), parent_id, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn change_project(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        payload: web::Json<ChangeTaskProject>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let project_id = payload.into_inner().project_id;
+        let task = service.change_project(principal.user_id, path.into_inner(), project_id, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn list_children(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let children = service.find_children(principal.user_id, path.into_inner()).await?;
+        let api_tasks: Vec<TaskApiModel> = children.into_iter().map(ToApiModel::to_api_model).collect();
+        Ok(HttpResponse::Ok().json(api_tasks))
+    }
+
+    async fn list_trashed_tasks(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let tasks = service.find_trashed_tasks(principal.user_id).await?;
+        let api_tasks: Vec<TaskApiModel> = tasks.into_iter().map(ToApiModel::to_api_model).collect();
+        Ok(HttpResponse::Ok().json(api_tasks))
+    }
+
+    async fn purge_task(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        service.purge_task(principal.user_id, path.into_inner(), if_match.0).await?;
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    async fn add_tag(
+        Authenticated(principal): Authenticated,
+        path: web::Path<(i64, i64)>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let (id, tag_id) = path.into_inner();
+        let task = service.add_tag(principal.user_id, id, tag_id, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn remove_tag(
+        Authenticated(principal): Authenticated,
+        path: web::Path<(i64, i64)>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let (id, tag_id) = path.into_inner();
+        let task = service.remove_tag(principal.user_id, id, tag_id, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn assign_task(
+        Authenticated(principal): Authenticated,
+        path: web::Path<(i64, i64)>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let (id, user_id) = path.into_inner();
+        let task = service.assign_task(principal.user_id, id, user_id, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn unassign_task(
+        Authenticated(principal): Authenticated,
+        path: web::Path<(i64, i64)>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let (id, user_id) = path.into_inner();
+        let task = service.unassign_task(principal.user_id, id, user_id, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn add_blocker(
+        Authenticated(principal): Authenticated,
+        path: web::Path<(i64, i64)>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let (id, blocker_id) = path.into_inner();
+        let task = service.add_blocker(principal.user_id, id, blocker_id, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+
+    async fn remove_blocker(
+        Authenticated(principal): Authenticated,
+        path: web::Path<(i64, i64)>,
+        if_match: IfMatch,
+        service: web::Data<Arc<dyn TaskServiceTrait>>,
+    ) -> TaskResponse {
+        let (id, blocker_id) = path.into_inner();
+        let task = service.remove_blocker(principal.user_id, id, blocker_id, if_match.0).await?;
+        Ok(task_response(HttpResponse::Ok(), task))
+    }
+}
+
+/// Task responses carry the version as `ETag` so that clients can send it back in `If-Match`.
+fn task_response(mut response: HttpResponseBuilder, task: Task) -> HttpResponse {
+    response
+        .insert_header(ETag(EntityTag::new_strong(task.version.to_string())))
+        .json(ToApiModel::to_api_model(task))
+}
+
+/// The subscriber of an event stream.
+struct EventViewer {
+    user_id: i64,
+    /// The workspaces the user belonged to when subscribing; events of others are never passed on.
+    workspace_ids: HashSet<i64>,
+    workspace_service: Arc<dyn WorkspaceServiceTrait>,
+}
 
-        match service.rename_task(id, new_title).await {
-            Ok(Some(task)) => HttpResponse::Ok().json(ToApiModel::to_api_model(task)),
-            Ok(None) => HttpResponse::NotFound().finish(),
+impl EventViewer {
+    /// Asks the policy again for every event, so that removed or demoted members stop receiving
+    /// events of the workspace without having to reconnect.
+    async fn may_view(&self, message: &TaskEventMessage) -> bool {
+        if !self.workspace_ids.contains(&message.workspace_id) {
+            return false;
+        }
+        match self.workspace_service.is_allowed(self.user_id, message.workspace_id, WorkspaceAction::ViewTasks).await {
+            Ok(allowed) => allowed,
             Err(e) => {
-                eprintln!("Error renaming task: {:?}", e);
-                HttpResponse::InternalServerError().finish()
+                eprintln!("Error checking access to task event: {:?}", e);
+                false
             }
         }
     }
 }
+
+/// Server-Sent Events: the missed events, or a reset if they are unknown, followed by live events.
+/// Only events of tasks the viewer may view are passed on.
+fn event_stream(
+    subscription: TaskEventSubscription,
+    viewer: EventViewer,
+) -> impl Stream<Item = Result<Bytes, Infallible>> + 'static {
+    let viewer = Arc::new(viewer);
+    // `None` stands for a reset
+    let missed: Vec<Option<Arc<TaskEventMessage>>> = match subscription.missed {
+        Some(missed) => missed.into_iter().map(Some).collect(),
+        None => vec![None],
+    };
+    // the only error is that the subscriber fell behind and events were dropped for it
+    let live = BroadcastStream::new(subscription.receiver).map(Result::ok);
+    let events = stream::iter(missed).chain(live).filter_map(move |message| {
+        let viewer = viewer.clone();
+        async move {
+            match message {
+                Some(message) => viewer.may_view(&message).await.then(|| event_frame(&message)),
+                None => Some(Bytes::from_static(RESET_EVENT)),
+            }
+        }
+    });
+    // the events end with the broadcast, marked by `None` so that the heartbeat ends with them
+    let events = events.map(Some).chain(stream::once(ready(None)));
+    let heartbeat =
+        IntervalStream::new(interval(EVENT_STREAM_HEARTBEAT)).map(|_| Some(Bytes::from_static(b": heartbeat\n\n")));
+    stream::select(events, heartbeat).take_while(|frame| ready(frame.is_some())).filter_map(ready).map(Ok)
+}
+
+fn event_frame(message: &TaskEventMessage) -> Bytes {
+    Bytes::from(format!("id: {}\nevent: {}\ndata: {}\n\n", message.id, message.event, message.data))
+}
@@ -0,0 +1,146 @@
+use crate::api::authentication::Authenticated;
+use crate::api::problem::{json_error_handler, path_error_handler, problem_response};
+use crate::mapper::webhook_mapper::{NewWebhook, UpdateWebhook, WebhookApiModel, WebhookDeliveryApiModel};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse, Responder, Scope};
+use application::webhook::webhook_error::WebhookError;
+use application::webhook::webhook_service_trait::WebhookServiceTrait;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct WebhookController {
+    webhook_service: Arc<dyn WebhookServiceTrait>,
+}
+
+impl WebhookController {
+    pub fn new(webhook_service: Arc<dyn WebhookServiceTrait>) -> Self {
+        Self { webhook_service }
+    }
+
+    pub fn configure(&self) -> Scope {
+        web::scope("/webhooks")
+            .app_data(web::Data::new(self.webhook_service.clone()))
+            .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+            .app_data(web::PathConfig::default().error_handler(path_error_handler))
+            .route("", web::get().to(Self::list_webhooks))
+            .route("", web::post().to(Self::create_webhook))
+            .route("/{id}", web::get().to(Self::get_webhook))
+            .route("/{id}", web::put().to(Self::update_webhook))
+            .route("/{id}", web::delete().to(Self::delete_webhook))
+            .route("/{id}/deliveries", web::get().to(Self::list_deliveries))
+            .route("/{id}/deliveries/{delivery_id}/redeliver", web::post().to(Self::redeliver))
+    }
+
+    async fn list_webhooks(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn WebhookServiceTrait>>,
+    ) -> impl Responder {
+        match service.find_webhooks(principal.user_id).await {
+            Ok(webhooks) => {
+                let api_webhooks: Vec<WebhookApiModel> = webhooks.into_iter().map(Into::into).collect();
+                HttpResponse::Ok().json(api_webhooks)
+            }
+            Err(e) => {
+                eprintln!("Error listing webhooks: {:?}", e);
+                HttpResponse::InternalServerError().finish()
+            }
+        }
+    }
+
+    async fn create_webhook(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn WebhookServiceTrait>>,
+        payload: web::Json<NewWebhook>,
+    ) -> impl Responder {
+        match service.create_webhook(principal.user_id, payload.into_inner().into()).await {
+            Ok(webhook) => HttpResponse::Created().json(WebhookApiModel::from(webhook)),
+            Err(e) => error_response("creating", e),
+        }
+    }
+
+    async fn get_webhook(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn WebhookServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.get_webhook(principal.user_id, id).await {
+            Ok(Some(webhook)) => HttpResponse::Ok().json(WebhookApiModel::from(webhook)),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("fetching", e),
+        }
+    }
+
+    async fn update_webhook(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        payload: web::Json<UpdateWebhook>,
+        service: web::Data<Arc<dyn WebhookServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.update_webhook(principal.user_id, id, payload.into_inner().into()).await {
+            Ok(Some(webhook)) => HttpResponse::Ok().json(WebhookApiModel::from(webhook)),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("updating", e),
+        }
+    }
+
+    async fn delete_webhook(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn WebhookServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.delete_webhook(principal.user_id, id).await {
+            Ok(true) => HttpResponse::NoContent().finish(),
+            Ok(false) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("deleting", e),
+        }
+    }
+
+    async fn list_deliveries(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn WebhookServiceTrait>>,
+    ) -> impl Responder {
+        let id = path.into_inner();
+        match service.find_deliveries(principal.user_id, id).await {
+            Ok(Some(deliveries)) => {
+                let api_deliveries: Vec<WebhookDeliveryApiModel> = deliveries.into_iter().map(Into::into).collect();
+                HttpResponse::Ok().json(api_deliveries)
+            }
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("listing deliveries of", e),
+        }
+    }
+
+    async fn redeliver(
+        Authenticated(principal): Authenticated,
+        path: web::Path<(i64, i64)>,
+        service: web::Data<Arc<dyn WebhookServiceTrait>>,
+    ) -> impl Responder {
+        let (id, delivery_id) = path.into_inner();
+        match service.redeliver(principal.user_id, id, delivery_id).await {
+            Ok(Some(delivery)) => HttpResponse::Created().json(WebhookDeliveryApiModel::from(delivery)),
+            Ok(None) => HttpResponse::NotFound().finish(),
+            Err(e) => error_response("redelivering", e),
+        }
+    }
+}
+
+/// Rejected changes are reported as problems, anything else is logged.
+fn error_response(action: &str, error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<WebhookError>() {
+        Some(error) => {
+            let status = match error {
+                WebhookError::Forbidden(_) => StatusCode::FORBIDDEN,
+                _ => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            problem_response(status, error.code(), error.to_string())
+        }
+        None => {
+            eprintln!("Error {action} webhook: {:?}", error);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
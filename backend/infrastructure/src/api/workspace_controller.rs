@@ -0,0 +1,123 @@
+use crate::api::authentication::Authenticated;
+use crate::api::problem::{json_error_handler, path_error_handler, problem_response};
+use crate::mapper::workspace_mapper::{ToWorkspaceMemberApiModel, ToWorkspaceMembershipApiModel, to_workspace_role};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse, Responder, Scope};
+use application::workspace::workspace_error::WorkspaceError;
+use application::workspace::workspace_service_trait::WorkspaceServiceTrait;
+use openapi_client::models::{
+    NewWorkspace, SetWorkspaceMemberRole, WorkspaceMember as WorkspaceMemberApiModel,
+    WorkspaceMembership as WorkspaceMembershipApiModel,
+};
+use std::sync::Arc;
+
+/// The signed-in user's workspaces and who belongs to them.
+#[derive(Clone)]
+pub struct WorkspaceController {
+    workspace_service: Arc<dyn WorkspaceServiceTrait>,
+}
+
+impl WorkspaceController {
+    pub fn new(workspace_service: Arc<dyn WorkspaceServiceTrait>) -> Self {
+        Self { workspace_service }
+    }
+
+    pub fn configure(&self) -> Scope {
+        web::scope("/workspaces")
+            .app_data(web::Data::new(self.workspace_service.clone()))
+            .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+            .app_data(web::PathConfig::default().error_handler(path_error_handler))
+            .route("", web::get().to(Self::list_workspaces))
+            .route("", web::post().to(Self::create_workspace))
+            .route("/{id}/members", web::get().to(Self::list_members))
+            .route("/{id}/members/{user_id}", web::put().to(Self::set_member_role))
+            .route("/{id}/members/{user_id}", web::delete().to(Self::remove_member))
+    }
+
+    async fn list_workspaces(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn WorkspaceServiceTrait>>,
+    ) -> impl Responder {
+        match service.find_workspaces(principal.user_id).await {
+            Ok(memberships) => {
+                let memberships: Vec<WorkspaceMembershipApiModel> =
+                    memberships.into_iter().map(ToWorkspaceMembershipApiModel::to_api_model).collect();
+                HttpResponse::Ok().json(memberships)
+            }
+            Err(e) => error_response("listing", e),
+        }
+    }
+
+    async fn create_workspace(
+        Authenticated(principal): Authenticated,
+        service: web::Data<Arc<dyn WorkspaceServiceTrait>>,
+        payload: web::Json<NewWorkspace>,
+    ) -> impl Responder {
+        match service.create_workspace(principal.user_id, payload.into_inner().name).await {
+            Ok(membership) => HttpResponse::Created().json(membership.to_api_model()),
+            Err(e) => error_response("creating", e),
+        }
+    }
+
+    async fn list_members(
+        Authenticated(principal): Authenticated,
+        path: web::Path<i64>,
+        service: web::Data<Arc<dyn WorkspaceServiceTrait>>,
+    ) -> impl Responder {
+        match service.find_members(principal.user_id, path.into_inner()).await {
+            Ok(members) => {
+                let members: Vec<WorkspaceMemberApiModel> =
+                    members.into_iter().map(ToWorkspaceMemberApiModel::to_api_model).collect();
+                HttpResponse::Ok().json(members)
+            }
+            Err(e) => error_response("listing members of", e),
+        }
+    }
+
+    async fn set_member_role(
+        Authenticated(principal): Authenticated,
+        path: web::Path<(i64, i64)>,
+        service: web::Data<Arc<dyn WorkspaceServiceTrait>>,
+        payload: web::Json<SetWorkspaceMemberRole>,
+    ) -> impl Responder {
+        let (id, user_id) = path.into_inner();
+        let role = to_workspace_role(payload.into_inner().role);
+        match service.set_member_role(principal.user_id, id, user_id, role).await {
+            Ok(member) => HttpResponse::Ok().json(member.to_api_model()),
+            Err(e) => error_response("changing a member of", e),
+        }
+    }
+
+    async fn remove_member(
+        Authenticated(principal): Authenticated,
+        path: web::Path<(i64, i64)>,
+        service: web::Data<Arc<dyn WorkspaceServiceTrait>>,
+    ) -> impl Responder {
+        let (id, user_id) = path.into_inner();
+        match service.remove_member(principal.user_id, id, user_id).await {
+            Ok(()) => HttpResponse::NoContent().finish(),
+            Err(e) => error_response("removing a member of", e),
+        }
+    }
+}
+
+/// Refusals are reported as problems, anything else is logged.
+fn error_response(action: &str, error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<WorkspaceError>() {
+        Some(error) => {
+            let status = match error {
+                WorkspaceError::NotFound(_) | WorkspaceError::UserNotFound(_) | WorkspaceError::MemberNotFound(_) => {
+                    StatusCode::NOT_FOUND
+                }
+                WorkspaceError::Forbidden(_) => StatusCode::FORBIDDEN,
+                WorkspaceError::LastOwner => StatusCode::CONFLICT,
+                WorkspaceError::BlankName | WorkspaceError::NameTooLong { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            problem_response(status, error.code(), error.to_string())
+        }
+        None => {
+            eprintln!("Error {action} workspace: {:?}", error);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::anyhow;
+use application::auth::password_hasher_trait::PasswordHasherTrait;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use async_trait::async_trait;
+
+/// Argon2id with the crate's recommended parameters. Hashing takes a noticeable amount of CPU
+/// time on purpose, so it runs on the blocking thread pool.
+#[derive(Clone, Default)]
+pub struct Argon2PasswordHasher;
+
+#[async_trait]
+impl PasswordHasherTrait for Argon2PasswordHasher {
+    async fn hash_password(&self, password: &str) -> anyhow::Result<String> {
+        let password = password.to_string();
+        actix_web::rt::task::spawn_blocking(move || {
+            let salt = SaltString::generate(&mut OsRng);
+            Argon2::default()
+                .hash_password(password.as_bytes(), &salt)
+                .map(|hash| hash.to_string())
+                .map_err(|error| anyhow!("hashing the password failed: {error}"))
+        })
+        .await?
+    }
+
+    async fn verify_password(&self, password: &str, password_hash: &str) -> bool {
+        let password = password.to_string();
+        let password_hash = password_hash.to_string();
+        actix_web::rt::task::spawn_blocking(move || {
+            PasswordHash::new(&password_hash).is_ok_and(|hash| {
+                Argon2::default().verify_password(password.as_bytes(), &hash).is_ok()
+            })
+        })
+        .await
+        .unwrap_or(false)
+    }
+}
@@ -0,0 +1,84 @@
+use application::auth::principal::Principal;
+use application::auth::token_issuer_trait::TokenIssuerTrait;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Utc};
+use domain::api_token::TokenScope;
+use domain::user::User;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Serialize, Deserialize)]
+struct AccessTokenClaims {
+    /// The user id.
+    sub: String,
+    email: String,
+    name: String,
+    iat: i64,
+    exp: i64,
+}
+
+/// Access tokens are HS256-signed JWTs, refresh and API tokens random strings stored as SHA-256 hashes.
+pub struct JwtTokenIssuer {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    validation: Validation,
+}
+
+impl JwtTokenIssuer {
+    pub fn new(secret: &[u8]) -> Self {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_required_spec_claims(&["exp", "sub"]);
+        JwtTokenIssuer {
+            encoding_key: EncodingKey::from_secret(secret),
+            decoding_key: DecodingKey::from_secret(secret),
+            validation,
+        }
+    }
+
+    /// Signs with a secret of its own, so that tokens do not outlive the process.
+    pub fn with_random_secret() -> Self {
+        Self::new(&random_bytes())
+    }
+}
+
+fn random_bytes() -> [u8; 32] {
+    let mut bytes = [0; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+impl TokenIssuerTrait for JwtTokenIssuer {
+    fn issue_access_token(&self, user: &User, expires_at: DateTime<Utc>) -> anyhow::Result<String> {
+        let claims = AccessTokenClaims {
+            sub: user.id.to_string(),
+            email: user.email.clone(),
+            name: user.display_name.clone(),
+            iat: Utc::now().timestamp(),
+            exp: expires_at.timestamp(),
+        };
+        Ok(jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &self.encoding_key)?)
+    }
+
+    fn verify_access_token(&self, access_token: &str) -> Option<Principal> {
+        let claims = jsonwebtoken::decode::<AccessTokenClaims>(access_token, &self.decoding_key, &self.validation)
+            .ok()?
+            .claims;
+        Some(Principal {
+            user_id: claims.sub.parse().ok()?,
+            email: claims.email,
+            display_name: claims.name,
+            scope: TokenScope::ReadWrite,
+        })
+    }
+
+    fn new_opaque_token(&self) -> String {
+        URL_SAFE_NO_PAD.encode(random_bytes())
+    }
+
+    fn hash_opaque_token(&self, token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+}
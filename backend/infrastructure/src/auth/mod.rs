@@ -0,0 +1,3 @@
+pub mod argon2_password_hasher;
+pub mod jwt_token_issuer;
+pub mod oidc_client;
\ No newline at end of file
@@ -0,0 +1,355 @@
+use application::auth::auth_dto::{AuthSessionDto, ExternalIdentityDto};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Header, Validation};
+use reqwest::Url;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// How long a user may take at the provider before the login has to be started over.
+pub const LOGIN_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Logins that have not come back yet, beyond which the oldest ones are forgotten.
+const MAX_PENDING_LOGINS: usize = 10_000;
+/// Handed-off sessions that have not been taken yet, beyond which the oldest ones are forgotten.
+const MAX_PENDING_SESSIONS: usize = 10_000;
+/// How long the frontend has to exchange the code it was sent back with for the session.
+const HANDOFF_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Signing algorithms accepted for ID tokens. Symmetric ones are left out, as they would turn the
+/// client secret into a signing key.
+const ID_TOKEN_ALGORITHMS: [Algorithm; 8] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
+/// The OpenID Connect provider and this application's registration with it.
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// Issuer URL, which serves the discovery document at `/.well-known/openid-configuration`.
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Absent for public clients, which rely on PKCE alone.
+    pub client_secret: Option<String>,
+    /// The callback URL registered with the provider.
+    pub redirect_url: String,
+    /// Frontend page users are sent back to, with the outcome of the login in the fragment.
+    pub post_login_url: String,
+    /// Keys are fetched again for tokens signed with an unknown key, but not more often than this.
+    pub jwks_refresh_interval: Duration,
+}
+
+/// Why a login at the provider did not produce an identity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OidcError {
+    /// The state is unknown, was used already or the login took too long.
+    InvalidState,
+    /// The provider redirected back with an error, e.g. because the user declined.
+    AuthorizationDenied(String),
+    TokenExchangeFailed(String),
+    InvalidIdToken(String),
+}
+
+impl OidcError {
+    /// Stable kebab-case identifier of the variant for clients that branch on the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            OidcError::InvalidState => "invalid-sso-state",
+            OidcError::AuthorizationDenied(_) => "sso-authorization-denied",
+            OidcError::TokenExchangeFailed(_) => "sso-token-exchange-failed",
+            OidcError::InvalidIdToken(_) => "invalid-id-token",
+        }
+    }
+}
+
+impl fmt::Display for OidcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OidcError::InvalidState => write!(f, "the login is unknown, finished or has expired"),
+            OidcError::AuthorizationDenied(error) => write!(f, "the identity provider refused the login: {error}"),
+            OidcError::TokenExchangeFailed(reason) => write!(f, "the authorization code could not be redeemed: {reason}"),
+            OidcError::InvalidIdToken(reason) => write!(f, "the ID token is invalid: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for OidcError {}
+
+/// The parts of the provider's discovery document the authorization-code flow needs.
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    /// Some providers send it as a string.
+    email_verified: Option<Value>,
+    name: Option<String>,
+}
+
+/// A login that was sent to the provider and has not come back yet, keyed by its state.
+struct PendingLogin {
+    code_verifier: String,
+    nonce: String,
+    started_at: Instant,
+}
+
+/// A session of a completed login, waiting for the frontend to pick it up with its one-time code.
+struct PendingSession {
+    session: AuthSessionDto,
+    created_at: Instant,
+}
+
+struct KeyCache {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Signs users in at an OpenID Connect provider with the authorization-code flow and PKCE.
+pub struct OidcClient {
+    config: OidcConfig,
+    metadata: ProviderMetadata,
+    authorization_endpoint: Url,
+    http_client: reqwest::Client,
+    key_cache: RwLock<KeyCache>,
+    pending_logins: Mutex<HashMap<String, PendingLogin>>,
+    pending_sessions: Mutex<HashMap<String, PendingSession>>,
+}
+
+impl OidcClient {
+    /// Reads the provider's discovery document and signing keys.
+    pub async fn discover(config: OidcConfig) -> anyhow::Result<Self> {
+        let http_client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?;
+        let discovery_url = format!("{}/.well-known/openid-configuration", config.issuer_url.trim_end_matches('/'));
+        let metadata: ProviderMetadata = get_json(&http_client, &discovery_url).await?;
+        if metadata.issuer != config.issuer_url {
+            anyhow::bail!("the provider at {} calls itself {}", config.issuer_url, metadata.issuer);
+        }
+        let authorization_endpoint = Url::parse(&metadata.authorization_endpoint)?;
+        let keys = get_json(&http_client, &metadata.jwks_uri).await?;
+        Ok(OidcClient {
+            config,
+            metadata,
+            authorization_endpoint,
+            http_client,
+            key_cache: RwLock::new(KeyCache { keys, fetched_at: Instant::now() }),
+            pending_logins: Mutex::default(),
+            pending_sessions: Mutex::default(),
+        })
+    }
+
+    pub fn post_login_url(&self) -> &str {
+        &self.config.post_login_url
+    }
+
+    /// The callback URL registered with the provider.
+    pub fn redirect_url(&self) -> &str {
+        &self.config.redirect_url
+    }
+
+    /// Starts a login and returns the provider URL to send the user to, along with the state the
+    /// provider sends them back with.
+    pub fn start_login(&self) -> (String, String) {
+        let state = random_token();
+        let nonce = random_token();
+        let code_verifier = random_token();
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+        let mut url = self.authorization_endpoint.clone();
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_url)
+            .append_pair("scope", "openid email profile")
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &code_challenge)
+            .append_pair("code_challenge_method", "S256");
+        let mut pending_logins = self.pending_logins.lock().unwrap_or_else(PoisonError::into_inner);
+        // abandoned logins are dropped here rather than by a timer
+        pending_logins.retain(|_, login| login.started_at.elapsed() < LOGIN_TIMEOUT);
+        make_room(&mut pending_logins, MAX_PENDING_LOGINS, |login| login.started_at);
+        pending_logins.insert(state.clone(), PendingLogin { code_verifier, nonce, started_at: Instant::now() });
+        (url.into(), state)
+    }
+
+    /// Keeps the session of a completed login and returns the one-time code it can be taken with,
+    /// so that the tokens never appear in a URL.
+    pub fn hand_off(&self, session: AuthSessionDto) -> String {
+        let code = random_token();
+        let mut pending_sessions = self.pending_sessions.lock().unwrap_or_else(PoisonError::into_inner);
+        pending_sessions.retain(|_, pending| pending.created_at.elapsed() < HANDOFF_TIMEOUT);
+        make_room(&mut pending_sessions, MAX_PENDING_SESSIONS, |pending| pending.created_at);
+        pending_sessions.insert(code.clone(), PendingSession { session, created_at: Instant::now() });
+        code
+    }
+
+    /// The session handed off with the code, which can be taken once.
+    pub fn take_session(&self, code: &str) -> Option<AuthSessionDto> {
+        self.pending_sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(code)
+            .filter(|pending| pending.created_at.elapsed() < HANDOFF_TIMEOUT)
+            .map(|pending| pending.session)
+    }
+
+    /// Forgets the login, e.g. after the provider reported an error for it.
+    pub fn abandon_login(&self, state: &str) {
+        self.pending_logins.lock().unwrap_or_else(PoisonError::into_inner).remove(state);
+    }
+
+    /// Completes a login with the code and state the provider redirected back with. Each state can
+    /// be completed once.
+    pub async fn complete_login(&self, code: &str, state: &str) -> Result<ExternalIdentityDto, OidcError> {
+        let login = self
+            .pending_logins
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(state)
+            .filter(|login| login.started_at.elapsed() < LOGIN_TIMEOUT)
+            .ok_or(OidcError::InvalidState)?;
+        let id_token = self.redeem_code(code, &login.code_verifier).await?;
+        let claims = self.validate_id_token(&id_token, &login.nonce).await?;
+        let email_verified = matches!(&claims.email_verified, Some(Value::Bool(true)))
+            || matches!(&claims.email_verified, Some(Value::String(verified)) if verified == "true");
+        Ok(ExternalIdentityDto {
+            issuer: claims.iss,
+            subject: claims.sub,
+            email: claims.email,
+            email_verified,
+            name: claims.name,
+        })
+    }
+
+    async fn redeem_code(&self, code: &str, code_verifier: &str) -> Result<String, OidcError> {
+        let form = [
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.config.redirect_url),
+            ("client_id", &self.config.client_id),
+            ("code_verifier", code_verifier),
+        ];
+        let mut request = self.http_client.post(&self.metadata.token_endpoint).form(&form);
+        if let Some(client_secret) = &self.config.client_secret {
+            request = request.basic_auth(&self.config.client_id, Some(client_secret));
+        }
+        let failed = |error: reqwest::Error| OidcError::TokenExchangeFailed(error.to_string());
+        let response = request.send().await.map_err(failed)?;
+        let status = response.status();
+        let body = response.bytes().await.map_err(failed)?;
+        if !status.is_success() {
+            let error = serde_json::from_slice::<Value>(&body)
+                .ok()
+                .and_then(|body| body["error"].as_str().map(str::to_string))
+                .unwrap_or_else(|| status.to_string());
+            return Err(OidcError::TokenExchangeFailed(error));
+        }
+        serde_json::from_slice::<TokenResponse>(&body)
+            .map(|tokens| tokens.id_token)
+            .map_err(|error| OidcError::TokenExchangeFailed(error.to_string()))
+    }
+
+    async fn validate_id_token(&self, id_token: &str, nonce: &str) -> Result<IdTokenClaims, OidcError> {
+        let invalid = |reason: &str| OidcError::InvalidIdToken(reason.to_string());
+        let header = jsonwebtoken::decode_header(id_token).map_err(|error| invalid(&error.to_string()))?;
+        if !ID_TOKEN_ALGORITHMS.contains(&header.alg) {
+            return Err(invalid("it is not signed with an asymmetric algorithm"));
+        }
+        let key = match self.find_key(&header) {
+            Some(key) => key,
+            // the provider may have rotated its keys since they were fetched
+            None => {
+                self.refresh_keys().await;
+                self.find_key(&header).ok_or_else(|| invalid("it is signed with an unknown key"))?
+            }
+        };
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.metadata.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        let claims = jsonwebtoken::decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|error| invalid(&error.to_string()))?
+            .claims;
+        // ties the token to the login it was requested for, so it cannot be replayed into another
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(invalid("the nonce does not match"));
+        }
+        Ok(claims)
+    }
+
+    fn find_key(&self, header: &Header) -> Option<DecodingKey> {
+        let key_cache = self.key_cache.read().unwrap_or_else(PoisonError::into_inner);
+        let jwk = match &header.kid {
+            Some(kid) => key_cache.keys.find(kid),
+            // without a key id only a lone key is unambiguous
+            None if key_cache.keys.keys.len() == 1 => key_cache.keys.keys.first(),
+            None => None,
+        }?;
+        if matches!(jwk.algorithm, AlgorithmParameters::OctetKey(_)) {
+            return None;
+        }
+        DecodingKey::from_jwk(jwk).ok()
+    }
+
+    async fn refresh_keys(&self) {
+        let fetched_at = self.key_cache.read().unwrap_or_else(PoisonError::into_inner).fetched_at;
+        if fetched_at.elapsed() < self.config.jwks_refresh_interval {
+            return;
+        }
+        match get_json::<JwkSet>(&self.http_client, &self.metadata.jwks_uri).await {
+            Ok(keys) => {
+                *self.key_cache.write().unwrap_or_else(PoisonError::into_inner) = KeyCache { keys, fetched_at: Instant::now() }
+            }
+            Err(e) => eprintln!("Error fetching the keys of the identity provider: {:?}", e),
+        }
+    }
+}
+
+/// Forgets the oldest entry once there are `max`, so that entries which are never taken cannot grow
+/// the map without bound.
+fn make_room<T>(entries: &mut HashMap<String, T>, max: usize, created_at: impl Fn(&T) -> Instant) {
+    if entries.len() < max {
+        return;
+    }
+    let oldest = entries.iter().min_by_key(|(_, entry)| created_at(entry)).map(|(key, _)| key.clone());
+    if let Some(oldest) = oldest {
+        entries.remove(&oldest);
+    }
+}
+
+async fn get_json<T: DeserializeOwned>(http_client: &reqwest::Client, url: &str) -> anyhow::Result<T> {
+    let response = http_client.get(url).send().await?.error_for_status()?;
+    Ok(serde_json::from_slice(&response.bytes().await?)?)
+}
+
+fn random_token() -> String {
+    let mut bytes = [0; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
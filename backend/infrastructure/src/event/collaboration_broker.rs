@@ -0,0 +1,84 @@
+use application::collaboration::collaboration_hub::{CollaborationHub, Outbox};
+use application::collaboration::collaboration_message_dto::{ClientId, ClientMessageDto, ServerMessageDto};
+use application::task::task_event_dto::TaskEventDto;
+use application::task::task_event_publisher_trait::TaskEventPublisherTrait;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+struct BrokerState {
+    hub: CollaborationHub,
+    connections: HashMap<ClientId, mpsc::UnboundedSender<ServerMessageDto>>,
+}
+
+/// Connects the collaboration hub to the WebSocket sessions, one outgoing queue per client.
+pub struct CollaborationBroker {
+    state: Mutex<BrokerState>,
+}
+
+impl CollaborationBroker {
+    pub fn new(lock_timeout: Duration) -> Self {
+        Self {
+            state: Mutex::new(BrokerState { hub: CollaborationHub::new(lock_timeout), connections: HashMap::new() }),
+        }
+    }
+
+    /// Registers a client that hears about tasks of the given workspaces; its messages arrive on
+    /// the returned receiver until it disconnects.
+    pub fn connect(&self, user: String, workspace_ids: HashSet<i64>) -> (ClientId, mpsc::UnboundedReceiver<ServerMessageDto>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut state = self.lock_state();
+        let (client_id, outbox) = state.hub.connect(user, workspace_ids);
+        state.connections.insert(client_id, sender);
+        state.deliver(outbox);
+        (client_id, receiver)
+    }
+
+    pub fn handle(&self, client_id: ClientId, message: ClientMessageDto) {
+        let mut state = self.lock_state();
+        let outbox = state.hub.handle(client_id, message, Instant::now());
+        state.deliver(outbox);
+    }
+
+    /// Tells a single client that its message was rejected.
+    pub fn reject(&self, client_id: ClientId, reason: String) {
+        self.lock_state().deliver(vec![(client_id, ServerMessageDto::Error(reason))]);
+    }
+
+    pub fn disconnect(&self, client_id: ClientId) {
+        let mut state = self.lock_state();
+        state.connections.remove(&client_id);
+        let outbox = state.hub.disconnect(client_id);
+        state.deliver(outbox);
+    }
+
+    pub fn expire_locks(&self) {
+        let mut state = self.lock_state();
+        let outbox = state.hub.expire_locks(Instant::now());
+        state.deliver(outbox);
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, BrokerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl BrokerState {
+    fn deliver(&self, outbox: Outbox) {
+        for (client_id, message) in outbox {
+            // a closed connection is cleaned up when its session ends
+            if let Some(connection) = self.connections.get(&client_id) {
+                let _ = connection.send(message);
+            }
+        }
+    }
+}
+
+impl TaskEventPublisherTrait for CollaborationBroker {
+    fn publish(&self, event: TaskEventDto) {
+        let state = self.lock_state();
+        let outbox = state.hub.task_event(&event);
+        state.deliver(outbox);
+    }
+}
@@ -0,0 +1,22 @@
+use application::task::task_event_dto::TaskEventDto;
+use application::task::task_event_publisher_trait::TaskEventPublisherTrait;
+use std::sync::Arc;
+
+/// Hands every event to each of the publishers, in order.
+pub struct FanOutTaskEventPublisher {
+    publishers: Vec<Arc<dyn TaskEventPublisherTrait>>,
+}
+
+impl FanOutTaskEventPublisher {
+    pub fn new(publishers: Vec<Arc<dyn TaskEventPublisherTrait>>) -> Self {
+        Self { publishers }
+    }
+}
+
+impl TaskEventPublisherTrait for FanOutTaskEventPublisher {
+    fn publish(&self, event: TaskEventDto) {
+        for publisher in &self.publishers {
+            publisher.publish(event.clone());
+        }
+    }
+}
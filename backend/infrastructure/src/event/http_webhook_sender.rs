@@ -0,0 +1,87 @@
+use application::webhook::webhook_sender_trait::WebhookSenderTrait;
+use application::webhook::webhook_service::is_public_address;
+use async_trait::async_trait;
+use domain::webhook::{Webhook, WebhookDelivery};
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Url;
+use sha2::Sha256;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+/// `sha256=` followed by the hex HMAC-SHA256 of the body, keyed with the webhook's secret.
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Resolves host names to their public addresses only, so that a host which starts resolving to an
+/// internal address after it was checked is not connected to.
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public_address(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// POSTs deliveries with reqwest; redirects are not followed.
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookSender {
+    pub fn new(timeout: Duration) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicAddressResolver))
+            .user_agent("task-tracker-webhooks")
+            .build()?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl WebhookSenderTrait for HttpWebhookSender {
+    async fn send(&self, webhook: &Webhook, delivery: &WebhookDelivery) -> anyhow::Result<u16> {
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, delivery.event.name())
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .header(SIGNATURE_HEADER, signature(&webhook.secret, delivery.payload.as_bytes()))
+            .body(delivery.payload.clone())
+            .send()
+            .await?;
+        Ok(response.status().as_u16())
+    }
+
+    async fn resolve(&self, url: &str) -> anyhow::Result<Vec<IpAddr>> {
+        let url = Url::parse(url)?;
+        let host = url.host_str().ok_or_else(|| anyhow::anyhow!("{url} has no host"))?;
+        // IPv6 hosts are bracketed in URLs
+        if let Ok(address) = host.trim_start_matches('[').trim_end_matches(']').parse() {
+            return Ok(vec![address]);
+        }
+        let port = url.port_or_known_default().unwrap_or(0);
+        Ok(tokio::net::lookup_host((host, port)).await?.map(|address| address.ip()).collect())
+    }
+}
@@ -0,0 +1,6 @@
+pub mod task_event_broadcaster;
+pub mod collaboration_broker;
+pub mod fan_out_task_event_publisher;
+pub mod http_webhook_sender;
+pub mod webhook_task_event_publisher;
+pub mod webhook_delivery_worker;
\ No newline at end of file
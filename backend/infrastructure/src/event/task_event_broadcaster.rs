@@ -0,0 +1,101 @@
+use crate::mapper::task_mapper::ToApiModel;
+use application::task::task_event_dto::TaskEventDto;
+use application::task::task_event_publisher_trait::TaskEventPublisherTrait;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::broadcast;
+
+/// One published event, already serialized for the `GET /tasks/events` stream.
+pub struct TaskEventMessage {
+    pub id: u64,
+    pub event: &'static str,
+    pub data: String,
+    /// Workspace of the task, so that streams only carry what their subscriber may see.
+    pub workspace_id: i64,
+}
+
+/// Where a subscriber starts: the events it missed, followed by everything published later.
+pub struct TaskEventSubscription {
+    /// `None` if the missed events are no longer known, so the subscriber has to refetch.
+    pub missed: Option<Vec<Arc<TaskEventMessage>>>,
+    pub receiver: broadcast::Receiver<Arc<TaskEventMessage>>,
+}
+
+struct History {
+    next_id: u64,
+    recent: VecDeque<Arc<TaskEventMessage>>,
+}
+
+/// Fans task events out to all subscribers and keeps the most recent ones for resuming.
+pub struct TaskEventBroadcaster {
+    sender: broadcast::Sender<Arc<TaskEventMessage>>,
+    history: Mutex<History>,
+    capacity: usize,
+}
+
+impl TaskEventBroadcaster {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            history: Mutex::new(History { next_id: 1, recent: VecDeque::with_capacity(capacity) }),
+            capacity,
+        }
+    }
+
+    /// Subscribes after the event with id `last_event_id`, or from now on without one.
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> TaskEventSubscription {
+        // subscribing under the lock keeps events from falling between history and channel
+        let history = self.history.lock().unwrap_or_else(PoisonError::into_inner);
+        let receiver = self.sender.subscribe();
+        let missed = match last_event_id {
+            None => Some(Vec::new()),
+            // ids from before a restart or older than the history cannot be resumed
+            Some(last_event_id) if last_event_id >= history.next_id => None,
+            Some(last_event_id) => {
+                let oldest_id = history.recent.front().map_or(history.next_id, |message| message.id);
+                (last_event_id + 1 >= oldest_id).then(|| {
+                    history
+                        .recent
+                        .iter()
+                        .filter(|message| message.id > last_event_id)
+                        .cloned()
+                        .collect()
+                })
+            }
+        };
+        TaskEventSubscription { missed, receiver }
+    }
+}
+
+impl TaskEventPublisherTrait for TaskEventBroadcaster {
+    fn publish(&self, event: TaskEventDto) {
+        let (event, workspace_id, data) = match event {
+            TaskEventDto::Created(task) => {
+                ("taskCreated", task.workspace_id, serde_json::to_string(&task.to_api_model()))
+            }
+            TaskEventDto::Updated(task) => {
+                ("taskUpdated", task.workspace_id, serde_json::to_string(&task.to_api_model()))
+            }
+            TaskEventDto::Deleted { id, workspace_id } => ("taskDeleted", workspace_id, Ok(json!({ "id": id }).to_string())),
+        };
+        let data = match data {
+            Ok(data) => data,
+            Err(error) => {
+                eprintln!("Task event could not be serialized: {:?}", error);
+                return;
+            }
+        };
+
+        let mut history = self.history.lock().unwrap_or_else(PoisonError::into_inner);
+        let message = Arc::new(TaskEventMessage { id: history.next_id, event, data, workspace_id });
+        history.next_id += 1;
+        if history.recent.len() == self.capacity {
+            history.recent.pop_front();
+        }
+        history.recent.push_back(message.clone());
+        // without subscribers the event is only kept in the history
+        let _ = self.sender.send(message);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::event::webhook_task_event_publisher::{QueuedWebhookEvent, WebhookTaskEventPublisher};
+use application::webhook::webhook_service_trait::WebhookServiceTrait;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+
+/// Number of events waiting to be queued before publishers have to wait for room.
+const EVENT_QUEUE_CAPACITY: usize = 1024;
+
+/// Queues published task events for the webhooks and attempts the deliveries that are due,
+/// right after an event and every `poll_interval` for retries.
+pub struct WebhookDeliveryWorker {
+    webhook_service: Arc<dyn WebhookServiceTrait>,
+    events: mpsc::Receiver<QueuedWebhookEvent>,
+    poll_interval: Duration,
+}
+
+impl WebhookDeliveryWorker {
+    pub fn new(webhook_service: Arc<dyn WebhookServiceTrait>, poll_interval: Duration) -> (Self, WebhookTaskEventPublisher) {
+        let (sender, events) = mpsc::channel(EVENT_QUEUE_CAPACITY);
+        let worker = Self { webhook_service, events, poll_interval };
+        (worker, WebhookTaskEventPublisher::new(sender))
+    }
+
+    /// Runs until the publisher is dropped. Deliveries run in a task of their own, so that slow
+    /// receivers do not hold up queueing new events.
+    pub async fn run(mut self) {
+        let queued = Arc::new(Notify::new());
+        let deliveries = actix_web::rt::spawn(deliver_due(self.webhook_service.clone(), self.poll_interval, queued.clone()));
+        while let Some((event, workspace_id, payload)) = self.events.recv().await {
+            match self.webhook_service.enqueue(event, workspace_id, payload, Utc::now()).await {
+                Ok(_) => queued.notify_one(),
+                Err(error) => eprintln!("Error queueing webhook deliveries: {:?}", error),
+            }
+        }
+        deliveries.abort();
+    }
+}
+
+/// Attempts the due deliveries whenever events were queued, and every `poll_interval` for retries.
+async fn deliver_due(webhook_service: Arc<dyn WebhookServiceTrait>, poll_interval: Duration, queued: Arc<Notify>) {
+    let mut poll = tokio::time::interval(poll_interval);
+    loop {
+        tokio::select! {
+            _ = queued.notified() => {}
+            _ = poll.tick() => {}
+        }
+        if let Err(error) = webhook_service.deliver_due(Utc::now()).await {
+            eprintln!("Error delivering webhooks: {:?}", error);
+        }
+    }
+}
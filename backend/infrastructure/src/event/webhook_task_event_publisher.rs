@@ -0,0 +1,41 @@
+use crate::mapper::webhook_mapper::WebhookPayload;
+use application::task::task_event_dto::TaskEventDto;
+use application::task::task_event_publisher_trait::TaskEventPublisherTrait;
+use chrono::Utc;
+use domain::webhook::WebhookEvent;
+use tokio::sync::mpsc;
+
+/// An event with the ID of the task's workspace and the JSON body it is delivered with.
+pub type QueuedWebhookEvent = (WebhookEvent, i64, String);
+
+/// Hands task events to the `WebhookDeliveryWorker`, which stores and sends them.
+pub struct WebhookTaskEventPublisher {
+    events: mpsc::Sender<QueuedWebhookEvent>,
+}
+
+impl WebhookTaskEventPublisher {
+    pub(crate) fn new(events: mpsc::Sender<QueuedWebhookEvent>) -> Self {
+        Self { events }
+    }
+}
+
+impl TaskEventPublisherTrait for WebhookTaskEventPublisher {
+    fn publish(&self, event: TaskEventDto) {
+        // the body is fixed now, so that retries send what the task looked like at the time
+        let (event, workspace_id, payload) = WebhookPayload::from_task_event(event, Utc::now());
+        match serde_json::to_string(&payload) {
+            Ok(payload) => {
+                // a full queue is waited for in a task of its own rather than in the request that
+                // published the event, and nothing is dropped; a closed one means shutting down
+                let queued = (event, workspace_id, payload);
+                if let Err(mpsc::error::TrySendError::Full(queued)) = self.events.try_send(queued) {
+                    let events = self.events.clone();
+                    actix_web::rt::spawn(async move {
+                        let _ = events.send(queued).await;
+                    });
+                }
+            }
+            Err(error) => eprintln!("Webhook payload could not be serialized: {:?}", error),
+        }
+    }
+}
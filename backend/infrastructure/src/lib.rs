@@ -0,0 +1,5 @@
+pub mod api;
+pub mod auth;
+pub mod event;
+pub mod mapper;
+pub mod persistence;
\ No newline at end of file
@@ -1,20 +1,60 @@
 use actix_cors::Cors;
 use actix_files::Files;
-use actix_web::{middleware, App, HttpServer};
+use actix_web::middleware::from_fn;
+use actix_web::{middleware, web, App, HttpServer};
 use sea_orm::{Database};
 use std::sync::Arc;
+use std::time::Duration;
 use sea_orm_migration::prelude::*;
 
-use api::task_controller::TaskController;
+use infrastructure::api::api_token_controller::ApiTokenController;
+use infrastructure::api::auth_controller::AuthController;
+use infrastructure::api::authentication::{require_authentication, require_authentication_or_api_token};
+use infrastructure::api::collaboration_controller::CollaborationController;
+use infrastructure::api::oidc_controller::OidcController;
+use infrastructure::api::project_controller::ProjectController;
+use infrastructure::api::tag_controller::TagController;
+use infrastructure::api::task_controller::TaskController;
+use infrastructure::api::webhook_controller::WebhookController;
+use infrastructure::api::workspace_controller::WorkspaceController;
+use application::auth::api_token_repository_trait::ApiTokenRepositoryTrait;
+use application::auth::api_token_service::ApiTokenService;
+use application::auth::api_token_service_trait::ApiTokenServiceTrait;
+use application::auth::auth_service::{AuthService, SessionPolicy};
+use application::auth::auth_service_trait::AuthServiceTrait;
+use application::auth::user_repository_trait::UserRepositoryTrait;
+use application::project::project_repository_trait::ProjectRepositoryTrait;
+use application::project::project_service::ProjectService;
+use application::project::project_service_trait::ProjectServiceTrait;
+use application::tag::tag_repository_trait::TagRepositoryTrait;
+use application::tag::tag_service::TagService;
+use application::tag::tag_service_trait::TagServiceTrait;
+use application::task::task_event_publisher_trait::TaskEventPublisherTrait;
 use application::task::task_repository_trait::TaskRepositoryTrait;
-use application::task::task_service::TaskService;
+use application::task::task_service::{ParentCompletion, TaskService};
 use application::task::task_service_trait::TaskServiceTrait;
-use persistence::repository::task_repository::TaskRepository;
-use crate::persistence::migration::lib::Migrator;
-
-pub mod api;
-pub mod mapper;
-pub mod persistence;
+use application::webhook::webhook_repository_trait::WebhookRepositoryTrait;
+use application::webhook::webhook_service::{RetryPolicy, WebhookService};
+use application::webhook::webhook_service_trait::WebhookServiceTrait;
+use application::workspace::workspace_repository_trait::WorkspaceRepositoryTrait;
+use application::workspace::workspace_service::WorkspaceService;
+use application::workspace::workspace_service_trait::WorkspaceServiceTrait;
+use infrastructure::persistence::repository::api_token_repository::ApiTokenRepository;
+use infrastructure::persistence::repository::project_repository::ProjectRepository;
+use infrastructure::persistence::repository::tag_repository::TagRepository;
+use infrastructure::persistence::repository::task_repository::TaskRepository;
+use infrastructure::persistence::repository::user_repository::UserRepository;
+use infrastructure::persistence::repository::webhook_repository::WebhookRepository;
+use infrastructure::persistence::repository::workspace_repository::WorkspaceRepository;
+use infrastructure::auth::argon2_password_hasher::Argon2PasswordHasher;
+use infrastructure::auth::jwt_token_issuer::JwtTokenIssuer;
+use infrastructure::auth::oidc_client::{OidcClient, OidcConfig};
+use infrastructure::event::collaboration_broker::CollaborationBroker;
+use infrastructure::event::fan_out_task_event_publisher::FanOutTaskEventPublisher;
+use infrastructure::event::http_webhook_sender::HttpWebhookSender;
+use infrastructure::event::task_event_broadcaster::TaskEventBroadcaster;
+use infrastructure::event::webhook_delivery_worker::WebhookDeliveryWorker;
+use infrastructure::persistence::migration::lib::Migrator;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -29,20 +69,132 @@ async fn main() -> std::io::Result<()> {
         .await
         .expect("Failed to run migrations");
 
-    let task_repository: Arc<dyn TaskRepositoryTrait> = Arc::new(TaskRepository::new(database_connection));
-    let task_service: Arc<dyn TaskServiceTrait> = Arc::new(TaskService::new(task_repository));
-    let task_controller: Arc<TaskController> = Arc::new(TaskController::new(task_service));
+    let task_repository: Arc<dyn TaskRepositoryTrait> = Arc::new(TaskRepository::new(database_connection.clone()));
+    let tag_repository: Arc<dyn TagRepositoryTrait> = Arc::new(TagRepository::new(database_connection.clone()));
+    let project_repository: Arc<dyn ProjectRepositoryTrait> = Arc::new(ProjectRepository::new(database_connection.clone()));
+    let webhook_repository: Arc<dyn WebhookRepositoryTrait> = Arc::new(WebhookRepository::new(database_connection.clone()));
+    let user_repository: Arc<dyn UserRepositoryTrait> = Arc::new(UserRepository::new(database_connection.clone()));
+    let api_token_repository: Arc<dyn ApiTokenRepositoryTrait> = Arc::new(ApiTokenRepository::new(database_connection.clone()));
+    let workspace_repository: Arc<dyn WorkspaceRepositoryTrait> = Arc::new(WorkspaceRepository::new(database_connection));
+    // PARENT_COMPLETION=cascade completes open subtasks along with their parent
+    let parent_completion = match std::env::var("PARENT_COMPLETION").as_deref() {
+        Ok("cascade") => ParentCompletion::Cascade,
+        _ => ParentCompletion::RequireSubtasksDone,
+    };
+    // recent events are kept so that reconnecting clients can catch up
+    let task_events = Arc::new(TaskEventBroadcaster::new(1024));
+    // soft locks are released unless the editing client renews them in time
+    let collaboration_broker = Arc::new(CollaborationBroker::new(Duration::from_secs(30)));
+    let webhook_sender = Arc::new(HttpWebhookSender::new(Duration::from_secs(10)).expect("Failed to create webhook client"));
+    let webhook_service: Arc<dyn WebhookServiceTrait> =
+        Arc::new(WebhookService::new(webhook_repository, workspace_repository.clone(), webhook_sender, RetryPolicy::default()));
+    // failed deliveries are retried when the worker polls
+    let (webhook_delivery_worker, webhook_events) = WebhookDeliveryWorker::new(webhook_service.clone(), Duration::from_secs(5));
+    let task_event_publisher = Arc::new(FanOutTaskEventPublisher::new(vec![
+        task_events.clone() as Arc<dyn TaskEventPublisherTrait>,
+        collaboration_broker.clone(),
+        Arc::new(webhook_events),
+    ]));
+    let task_service: Arc<dyn TaskServiceTrait> = Arc::new(TaskService::new(
+        task_repository,
+        tag_repository.clone(),
+        project_repository.clone(),
+        workspace_repository.clone(),
+        task_event_publisher,
+        parent_completion,
+    ));
+    let tag_service: Arc<dyn TagServiceTrait> = Arc::new(TagService::new(tag_repository, workspace_repository.clone()));
+    let project_service: Arc<dyn ProjectServiceTrait> =
+        Arc::new(ProjectService::new(project_repository, workspace_repository.clone()));
+    let workspace_service: Arc<dyn WorkspaceServiceTrait> =
+        Arc::new(WorkspaceService::new(workspace_repository, user_repository.clone()));
+    let task_controller: Arc<TaskController> =
+        Arc::new(TaskController::new(task_service, workspace_service.clone(), task_events));
+    let workspace_controller: Arc<WorkspaceController> = Arc::new(WorkspaceController::new(workspace_service.clone()));
+    let tag_controller: Arc<TagController> = Arc::new(TagController::new(tag_service));
+    let project_controller: Arc<ProjectController> = Arc::new(ProjectController::new(project_service));
+    let collaboration_controller: Arc<CollaborationController> =
+        Arc::new(CollaborationController::new(collaboration_broker.clone(), workspace_service.clone()));
+    let webhook_controller: Arc<WebhookController> = Arc::new(WebhookController::new(webhook_service));
+    // JWT_SECRET keeps sessions valid across restarts
+    let token_issuer = Arc::new(match std::env::var("JWT_SECRET") {
+        Ok(secret) if !secret.is_empty() => JwtTokenIssuer::new(secret.as_bytes()),
+        _ => {
+            println!("JWT_SECRET is not set, sign-ins end when the server stops");
+            JwtTokenIssuer::with_random_secret()
+        }
+    });
+    let auth_service: Arc<dyn AuthServiceTrait> = Arc::new(AuthService::new(
+        user_repository.clone(),
+        Arc::new(Argon2PasswordHasher),
+        token_issuer.clone(),
+        SessionPolicy::default(),
+    ));
+    let api_token_service: Arc<dyn ApiTokenServiceTrait> =
+        Arc::new(ApiTokenService::new(api_token_repository, user_repository, token_issuer));
+    let auth_controller: Arc<AuthController> = Arc::new(AuthController::new(auth_service.clone()));
+    // single sign-on is offered once OIDC_ISSUER_URL and OIDC_CLIENT_ID name a provider
+    let oidc_controller: Option<Arc<OidcController>> = match oidc_config() {
+        Some(config) => {
+            let oidc_client = OidcClient::discover(config)
+                .await
+                .expect("Failed to discover the OpenID Connect provider");
+            Some(Arc::new(OidcController::new(Arc::new(oidc_client), auth_service.clone())))
+        }
+        None => None,
+    };
+    let api_token_controller: Arc<ApiTokenController> = Arc::new(ApiTokenController::new(api_token_service.clone()));
+    // the Trunk dev server; ALLOWED_ORIGINS takes a comma-separated list instead
+    let allowed_origins: Vec<String> = match std::env::var("ALLOWED_ORIGINS") {
+        Ok(origins) => origins
+            .split(',')
+            .map(|origin| origin.trim().to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect(),
+        Err(_) => vec!["http://localhost:3000".to_string(), "http://127.0.0.1:3000".to_string()],
+    };
+
+    actix_web::rt::spawn(webhook_delivery_worker.run());
+
+    actix_web::rt::spawn(async move {
+        let mut sweep = actix_web::rt::time::interval(Duration::from_secs(5));
+        loop {
+            sweep.tick().await;
+            collaboration_broker.expire_locks();
+        }
+    });
 
     HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
+        let cors = allowed_origins
+            .iter()
+            .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
             .allow_any_method()
-            .allow_any_header();
+            .allow_any_header()
+            .expose_headers(["ETag"]);
+        // event streams pass their access token in the query string, so only the path is logged
+        let logger = middleware::Logger::new(r#"%a "%{method}xi %U" %s %b "%{Referer}i" "%{User-Agent}i" %T"#)
+            .custom_request_replace("method", |request| request.method().to_string());
 
         App::new()
-            .wrap(middleware::Logger::default())
+            .wrap(logger)
             .wrap(cors)
-            .service(task_controller.configure())
+            .app_data(web::Data::new(auth_service.clone()))
+            .app_data(web::Data::new(api_token_service.clone()))
+            // registered before /auth, which would otherwise take its requests
+            .configure(|config| {
+                if let Some(oidc_controller) = &oidc_controller {
+                    config.service(oidc_controller.configure());
+                }
+            })
+            .service(auth_controller.configure())
+            .service(api_token_controller.configure().wrap(from_fn(require_authentication)))
+            // scripts and integrations work on tasks with API tokens
+            .service(task_controller.configure().wrap(from_fn(require_authentication_or_api_token)))
+            .service(workspace_controller.configure().wrap(from_fn(require_authentication)))
+            .service(tag_controller.configure().wrap(from_fn(require_authentication)))
+            .service(project_controller.configure().wrap(from_fn(require_authentication)))
+            .service(collaboration_controller.configure().wrap(from_fn(require_authentication)))
+            .service(webhook_controller.configure().wrap(from_fn(require_authentication)))
             .service(Files::new("/openapi", "../../openapi").index_file("openapi.yaml"))
             .service(Files::new("/swagger-ui", "../../target/static/swagger-ui").index_file("index.html"))
             .service(
@@ -55,3 +207,17 @@ async fn main() -> std::io::Result<()> {
         .run()
         .await
 }
+
+fn oidc_config() -> Option<OidcConfig> {
+    let issuer_url = std::env::var("OIDC_ISSUER_URL").ok().filter(|url| !url.is_empty())?;
+    let client_id = std::env::var("OIDC_CLIENT_ID").ok().filter(|id| !id.is_empty())?;
+    Some(OidcConfig {
+        issuer_url,
+        client_id,
+        client_secret: std::env::var("OIDC_CLIENT_SECRET").ok().filter(|secret| !secret.is_empty()),
+        redirect_url: std::env::var("OIDC_REDIRECT_URL")
+            .unwrap_or_else(|_| "http://127.0.0.1:8080/auth/oidc/callback".to_string()),
+        post_login_url: std::env::var("OIDC_POST_LOGIN_URL").unwrap_or_else(|_| "http://127.0.0.1:8080/".to_string()),
+        jwks_refresh_interval: Duration::from_secs(60),
+    })
+}
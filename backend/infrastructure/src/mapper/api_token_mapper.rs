@@ -0,0 +1,88 @@
+use o2o::o2o;
+
+use crate::persistence::entity::api_token::{Model as ApiTokenPersistenceModel, TokenScope as ApiTokenPersistenceScope};
+use application::auth::api_token_dto::{CreatedApiTokenDto, NewApiTokenDto};
+use domain::api_token::{ApiToken, TokenScope};
+use openapi_client::models::{
+    ApiToken as ApiTokenApiModel, ApiTokenScope as ApiTokenApiScope, CreatedApiToken as CreatedApiTokenApiModel,
+    NewApiToken as NewApiTokenApiModel,
+};
+
+#[derive(Debug, Clone, Copy, Default, o2o)]
+#[map_owned(TokenScope)]
+#[map_owned(ApiTokenPersistenceScope)]
+#[map_owned(ApiTokenApiScope)]
+pub enum TokenScopeMapper {
+    Read,
+    #[default]
+    ReadWrite,
+}
+
+impl From<ApiTokenPersistenceModel> for ApiToken {
+    fn from(model: ApiTokenPersistenceModel) -> Self {
+        ApiToken {
+            id: model.id,
+            user_id: model.user_id,
+            name: model.name,
+            token_hash: model.token_hash,
+            scope: TokenScopeMapper::from(model.scope).into(),
+            expires_at: model.expires_at,
+            last_used_at: model.last_used_at,
+            created_at: model.created_at,
+        }
+    }
+}
+
+impl From<ApiToken> for ApiTokenPersistenceModel {
+    fn from(api_token: ApiToken) -> Self {
+        ApiTokenPersistenceModel {
+            id: api_token.id,
+            user_id: api_token.user_id,
+            name: api_token.name,
+            token_hash: api_token.token_hash,
+            scope: TokenScopeMapper::from(api_token.scope).into(),
+            expires_at: api_token.expires_at,
+            last_used_at: api_token.last_used_at,
+            created_at: api_token.created_at,
+        }
+    }
+}
+
+pub fn to_new_api_token_dto(api_token: NewApiTokenApiModel) -> NewApiTokenDto {
+    NewApiTokenDto {
+        name: api_token.name,
+        scope: TokenScopeMapper::from(api_token.scope).into(),
+        expires_at: api_token.expires_at,
+    }
+}
+
+pub trait ToApiTokenApiModel {
+    fn to_api_model(self) -> ApiTokenApiModel;
+}
+
+/// Leaves out the token hash.
+impl ToApiTokenApiModel for ApiToken {
+    fn to_api_model(self) -> ApiTokenApiModel {
+        ApiTokenApiModel {
+            id: self.id,
+            name: self.name,
+            scope: TokenScopeMapper::from(self.scope).into(),
+            expires_at: self.expires_at,
+            last_used_at: self.last_used_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+pub trait ToCreatedApiTokenApiModel {
+    fn to_api_model(self) -> CreatedApiTokenApiModel;
+}
+
+impl ToCreatedApiTokenApiModel for CreatedApiTokenDto {
+    fn to_api_model(self) -> CreatedApiTokenApiModel {
+        CreatedApiTokenApiModel {
+            token: self.token,
+            api_token: self.api_token.to_api_model(),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use crate::api::problem::ProblemDetails;
+use crate::mapper::task_filter_and_sort_mapper::ToTaskFilterAndSortDto;
+use crate::mapper::task_mapper::ToApiModel;
+use crate::mapper::task_patch_mapper::{TaskPatch, ToTaskPatchDto};
+use application::task::bulk_task_dto::{
+    BulkItemDto, BulkItemResultDto, BulkOperationDto, BulkOutcomeDto, BulkRequestDto,
+};
+use openapi_client::models::{Task as TaskApiModel, TaskFilterAndSort};
+
+/// Body of `POST /tasks/bulk`. It embeds a `TaskPatch`, which has to be read by hand,
+/// so the whole request is.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct BulkTaskRequest {
+    items: Option<Vec<BulkTaskItem>>,
+    selector: Option<TaskFilterAndSort>,
+    operation: Option<BulkTaskOperation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct BulkTaskItem {
+    id: i64,
+    version: Option<i64>,
+    operation: BulkTaskOperation,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum BulkTaskOperation {
+    MarkDone {
+        #[serde(default)]
+        force: bool,
+    },
+    Reopen,
+    Delete,
+    Retitle {
+        title: String,
+    },
+    Set {
+        patch: TaskPatch,
+    },
+}
+
+impl From<BulkTaskOperation> for BulkOperationDto {
+    fn from(operation: BulkTaskOperation) -> Self {
+        match operation {
+            BulkTaskOperation::MarkDone { force } => BulkOperationDto::MarkDone { force },
+            BulkTaskOperation::Reopen => BulkOperationDto::Reopen,
+            BulkTaskOperation::Delete => BulkOperationDto::Delete,
+            BulkTaskOperation::Retitle { title } => BulkOperationDto::Retitle(title),
+            BulkTaskOperation::Set { patch } => BulkOperationDto::Set(patch.to_dto()),
+        }
+    }
+}
+
+pub trait ToBulkRequestDto {
+    /// Fails unless the request holds either `items`, or a `selector` together with an `operation`.
+    /// `user_id` is the signed-in user, whom `me` refers to in the selector.
+    fn to_dto(self, user_id: i64) -> Result<BulkRequestDto, &'static str>;
+}
+
+impl ToBulkRequestDto for BulkTaskRequest {
+    fn to_dto(self, user_id: i64) -> Result<BulkRequestDto, &'static str> {
+        match (self.items, self.selector, self.operation) {
+            (Some(items), None, None) => Ok(BulkRequestDto::Items(
+                items
+                    .into_iter()
+                    .map(|item| BulkItemDto {
+                        id: item.id,
+                        operation: item.operation.into(),
+                        expected_version: item.version,
+                    })
+                    .collect(),
+            )),
+            (None, Some(selector), Some(operation)) => Ok(BulkRequestDto::Selector {
+                filter_and_sort: Box::new(ToTaskFilterAndSortDto::to_dto(selector, user_id)?),
+                operation: operation.into(),
+            }),
+            _ => Err("send either items, or a selector together with an operation"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkTaskResponse {
+    /// Whether the operations were kept; all of them are rolled back if any fails.
+    applied: bool,
+    results: Vec<BulkTaskResult>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BulkTaskResult {
+    id: i64,
+    status: BulkTaskResultStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task: Option<TaskApiModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    problem: Option<ProblemDetails>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum BulkTaskResultStatus {
+    Applied,
+    Failed,
+    RolledBack,
+}
+
+pub trait ToBulkTaskResponse {
+    fn to_api_model(self) -> BulkTaskResponse;
+}
+
+impl ToBulkTaskResponse for Vec<BulkItemResultDto> {
+    fn to_api_model(self) -> BulkTaskResponse {
+        let applied = self
+            .iter()
+            .all(|result| matches!(result.outcome, BulkOutcomeDto::Applied(_)));
+        let results = self
+            .into_iter()
+            .map(|result| {
+                let (status, task, problem) = match result.outcome {
+                    BulkOutcomeDto::Applied(task) => {
+                        (BulkTaskResultStatus::Applied, Some(ToApiModel::to_api_model(*task)), None)
+                    }
+                    BulkOutcomeDto::Failed(error) => {
+                        (BulkTaskResultStatus::Failed, None, Some(ProblemDetails::from_task_error(&error)))
+                    }
+                    BulkOutcomeDto::RolledBack => (BulkTaskResultStatus::RolledBack, None, None),
+                };
+                BulkTaskResult { id: result.id, status, task, problem }
+            })
+            .collect();
+        BulkTaskResponse { applied, results }
+    }
+}
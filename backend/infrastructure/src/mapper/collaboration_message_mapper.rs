@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::mapper::task_mapper::ToApiModel;
+use application::collaboration::collaboration_message_dto::{
+    ClientMessageDto, LockDto, PresenceDto, ServerMessageDto, SubscriptionFilterDto,
+};
+use openapi_client::models::Task as TaskApiModel;
+
+/// Messages sent by clients over `GET /collaboration/ws`, told apart by `type`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ClientMessage {
+    Subscribe {
+        #[serde(default)]
+        task_ids: Vec<i64>,
+        filter: Option<SubscriptionFilter>,
+    },
+    Focus {
+        task_id: Option<i64>,
+    },
+    Lock {
+        task_id: i64,
+    },
+    Unlock {
+        task_id: i64,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SubscriptionFilter {
+    project_id: Option<i64>,
+    tag_id: Option<i64>,
+}
+
+impl From<ClientMessage> for ClientMessageDto {
+    fn from(message: ClientMessage) -> Self {
+        match message {
+            ClientMessage::Subscribe { task_ids, filter } => ClientMessageDto::Subscribe {
+                task_ids,
+                filter: filter.map(|filter| SubscriptionFilterDto { project_id: filter.project_id, tag_id: filter.tag_id }),
+            },
+            ClientMessage::Focus { task_id } => ClientMessageDto::Focus(task_id),
+            ClientMessage::Lock { task_id } => ClientMessageDto::Lock(task_id),
+            ClientMessage::Unlock { task_id } => ClientMessageDto::Unlock(task_id),
+        }
+    }
+}
+
+/// Messages sent to clients, told apart by `type`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ServerMessage {
+    Welcome { client_id: u64, lock_timeout_seconds: u64 },
+    Snapshot { presence: Vec<Presence>, locks: Vec<Lock> },
+    Presence(Presence),
+    Locked(Lock),
+    LockDenied(Lock),
+    Unlocked { task_id: i64 },
+    TaskChanged { task: Box<TaskApiModel> },
+    TaskDeleted { task_id: i64 },
+    Error { message: String },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Presence {
+    client_id: u64,
+    user: String,
+    task_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lock {
+    task_id: i64,
+    client_id: u64,
+    user: String,
+}
+
+impl From<PresenceDto> for Presence {
+    fn from(presence: PresenceDto) -> Self {
+        Presence { client_id: presence.client_id, user: presence.user, task_id: presence.task_id }
+    }
+}
+
+impl From<LockDto> for Lock {
+    fn from(lock: LockDto) -> Self {
+        Lock { task_id: lock.task_id, client_id: lock.client_id, user: lock.user }
+    }
+}
+
+impl From<ServerMessageDto> for ServerMessage {
+    fn from(message: ServerMessageDto) -> Self {
+        match message {
+            ServerMessageDto::Welcome { client_id, lock_timeout } => {
+                ServerMessage::Welcome { client_id, lock_timeout_seconds: lock_timeout.as_secs() }
+            }
+            ServerMessageDto::Snapshot { presence, locks } => ServerMessage::Snapshot {
+                presence: presence.into_iter().map(Into::into).collect(),
+                locks: locks.into_iter().map(Into::into).collect(),
+            },
+            ServerMessageDto::Presence(presence) => ServerMessage::Presence(presence.into()),
+            ServerMessageDto::Locked(lock) => ServerMessage::Locked(lock.into()),
+            ServerMessageDto::LockDenied(lock) => ServerMessage::LockDenied(lock.into()),
+            ServerMessageDto::Unlocked { task_id } => ServerMessage::Unlocked { task_id },
+            ServerMessageDto::TaskChanged(task) => ServerMessage::TaskChanged { task: Box::new(ToApiModel::to_api_model(*task)) },
+            ServerMessageDto::TaskDeleted { id, .. } => ServerMessage::TaskDeleted { task_id: id },
+            ServerMessageDto::Error(message) => ServerMessage::Error { message },
+        }
+    }
+}
@@ -1,2 +1,14 @@
 pub mod task_mapper;
-pub mod task_filter_and_sort_mapper;
\ No newline at end of file
+pub mod task_filter_and_sort_mapper;
+pub mod new_task_mapper;
+pub mod priority_mapper;
+pub mod tag_mapper;
+pub mod project_mapper;
+pub mod task_page_mapper;
+pub mod task_patch_mapper;
+pub mod bulk_task_mapper;
+pub mod collaboration_message_mapper;
+pub mod webhook_mapper;
+pub mod user_mapper;
+pub mod api_token_mapper;
+pub mod workspace_mapper;
\ No newline at end of file
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use o2o::o2o;
+
+use crate::mapper::priority_mapper::PriorityMapper;
+use application::task::new_task_dto::NewTaskDto;
+use openapi_client::models::NewTask as ApiNewTask;
+
+#[derive(Debug, Clone, o2o)]
+#[from_owned(ApiNewTask)]
+#[owned_into(NewTaskDto)]
+pub struct NewTaskMapper {
+    pub title: String,
+    pub description: Option<String>,
+    pub due_at: Option<DateTime<Utc>>,
+    #[from(~.map(PriorityMapper::from).unwrap_or_default())]
+    #[into(~.into())]
+    pub priority: PriorityMapper,
+    pub parent_id: Option<i64>,
+    pub recurrence_rule: Option<String>,
+    pub recurrence_time_zone: Option<String>,
+    pub project_id: Option<i64>,
+    pub workspace_id: Option<i64>,
+    // the creator is the signed-in user, which the request does not get to choose
+    #[ghost(ApiNewTask| {None})]
+    pub created_by: Option<i64>,
+}
+
+pub trait ToNewTaskDto {
+    fn to_dto(self) -> NewTaskDto;
+}
+
+impl ToNewTaskDto for ApiNewTask {
+    fn to_dto(self) -> NewTaskDto {
+        let mapper: NewTaskMapper = self.into();
+        mapper.into()
+    }
+}
@@ -0,0 +1,18 @@
+use o2o::o2o;
+
+use crate::persistence::entity::task::TaskPriority;
+use domain::task::Priority;
+use openapi_client::models::Priority as ApiPriority;
+
+#[derive(Debug, Clone, Copy, Default, o2o)]
+#[map_owned(Priority)]
+#[map_owned(TaskPriority)]
+#[map_owned(ApiPriority)]
+pub enum PriorityMapper {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
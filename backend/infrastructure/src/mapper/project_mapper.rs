@@ -0,0 +1,48 @@
+use o2o::o2o;
+use chrono::{DateTime, Utc};
+use domain::project::Project;
+use crate::persistence::entity::project::Model as ProjectPersistenceModel;
+use openapi_client::models::Project as ProjectApiModel;
+
+#[derive(Debug, Clone, o2o)]
+#[map_owned(Project)]
+#[from_owned(ProjectPersistenceModel)]
+#[owned_into(ProjectPersistenceModel)]
+#[from_owned(ProjectApiModel)]
+#[owned_into(ProjectApiModel)]
+pub struct ProjectMapper {
+    pub id: i64,
+    pub workspace_id: i64,
+    pub name: String,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // counted by the repository
+    #[ghost(ProjectPersistenceModel| {0})]
+    pub open_task_count: i64,
+}
+
+impl From<Project> for ProjectPersistenceModel {
+    fn from(project: Project) -> ProjectPersistenceModel {
+        let project_mapper: ProjectMapper = project.into();
+        project_mapper.into()
+    }
+}
+
+impl From<ProjectPersistenceModel> for Project {
+    fn from(project: ProjectPersistenceModel) -> Project {
+        let project_mapper: ProjectMapper = project.into();
+        project_mapper.into()
+    }
+}
+
+pub trait ToProjectApiModel {
+    fn to_api_model(self) -> ProjectApiModel;
+}
+
+impl ToProjectApiModel for Project {
+    fn to_api_model(self) -> ProjectApiModel {
+        let project_mapper: ProjectMapper = self.into();
+        project_mapper.into()
+    }
+}
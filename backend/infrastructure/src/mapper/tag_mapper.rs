@@ -0,0 +1,42 @@
+use o2o::o2o;
+use domain::tag::Tag;
+use crate::persistence::entity::tag::Model as TagPersistenceModel;
+use openapi_client::models::Tag as TagApiModel;
+
+#[derive(Debug, Clone, o2o)]
+#[map_owned(Tag)]
+#[from_owned(TagPersistenceModel)]
+#[owned_into(TagPersistenceModel)]
+#[from_owned(TagApiModel)]
+#[owned_into(TagApiModel)]
+pub struct TagMapper {
+    pub id: i64,
+    pub workspace_id: i64,
+    pub name: String,
+    pub color: String,
+}
+
+impl From<Tag> for TagPersistenceModel {
+    fn from(tag: Tag) -> TagPersistenceModel {
+        let tag_mapper: TagMapper = tag.into();
+        tag_mapper.into()
+    }
+}
+
+impl From<TagPersistenceModel> for Tag {
+    fn from(tag: TagPersistenceModel) -> Tag {
+        let tag_mapper: TagMapper = tag.into();
+        tag_mapper.into()
+    }
+}
+
+pub trait ToTagApiModel {
+    fn to_api_model(self) -> TagApiModel;
+}
+
+impl ToTagApiModel for Tag {
+    fn to_api_model(self) -> TagApiModel {
+        let tag_mapper: TagMapper = self.into();
+        tag_mapper.into()
+    }
+}
@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
+use domain::task::Priority;
 use o2o::o2o;
 
+use crate::mapper::priority_mapper::PriorityMapper;
+
 use openapi_client::models::{
     TaskFilterAndSort as ApiTaskFilterAndSort,
     TaskFilterAndSortFilters as ApiTaskFilterAndSortFilters,
@@ -21,17 +24,37 @@ use application::task::task_filter_and_sort_dto::{
 pub struct TaskFilterAndSortMapper {
     pub filters: Option<TaskFilterAndSortFiltersMapper>,
     pub sort: Option<TaskFilterAndSortSortMapper>,
+    pub limit: Option<u64>,
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Clone, o2o)]
 #[owned_into(TaskFilterAndSortFiltersDto)]
+#[ghosts(include_deleted: {None}, viewable_workspace_ids: {None})]
 pub struct TaskFilterAndSortFiltersMapper {
     pub done: Option<bool>,
     pub title: Option<String>,
+    pub query: Option<String>,
     pub created_after: Option<DateTime<Utc>>,
     pub created_before: Option<DateTime<Utc>>,
     pub updated_after: Option<DateTime<Utc>>,
     pub updated_before: Option<DateTime<Utc>>,
+    pub deleted: Option<bool>,
+    pub due_after: Option<DateTime<Utc>>,
+    pub due_before: Option<DateTime<Utc>>,
+    pub overdue: Option<bool>,
+    pub priorities: Option<Vec<Priority>>,
+    pub tags_any: Option<Vec<i64>>,
+    pub tags_all: Option<Vec<i64>>,
+    pub tags_none: Option<Vec<i64>>,
+    pub blocked: Option<bool>,
+    pub series_id: Option<i64>,
+    pub project_id: Option<i64>,
+    pub top_level: Option<bool>,
+    pub assignee: Option<i64>,
+    pub unassigned: Option<bool>,
+    pub created_by: Option<i64>,
+    pub workspace_id: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +70,9 @@ pub enum TaskFilterAndSortSortFieldMapper {
     UpdatedAt,
     Title,
     Done,
+    DueAt,
+    Priority,
+    Relevance,
 }
 
 #[derive(Debug, Clone, Copy, o2o)]
@@ -56,25 +82,52 @@ pub enum TaskFilterAndSortSortDirectionMapper {
     Desc,
 }
 
-impl From<ApiTaskFilterAndSort> for TaskFilterAndSortMapper {
-    fn from(api: ApiTaskFilterAndSort) -> Self {
-        Self {
-            filters: api.filters.map(TaskFilterAndSortFiltersMapper::from),
-            sort: api.sort.map(TaskFilterAndSortSortMapper::from),
-        }
-    }
+/// Filter value that stands for the signed-in user.
+const ME: &str = "me";
+
+/// Resolves a user named in a filter by ID, or as `me`.
+fn resolve_user(value: Option<String>, user_id: i64, error: &'static str) -> Result<Option<i64>, &'static str> {
+    value
+        .map(|value| match value.trim() {
+            ME => Ok(user_id),
+            value => value.parse().map_err(|_| error),
+        })
+        .transpose()
 }
 
-impl From<ApiTaskFilterAndSortFilters> for TaskFilterAndSortFiltersMapper {
-    fn from(api: ApiTaskFilterAndSortFilters) -> Self {
-        Self {
+impl TaskFilterAndSortFiltersMapper {
+    /// `user_id` is the signed-in user, whom `me` refers to.
+    pub fn from_api(api: ApiTaskFilterAndSortFilters, user_id: i64) -> Result<Self, &'static str> {
+        Ok(Self {
             done: api.done,
             title: api.title,
+            query: api.query,
             created_after: api.created_after,
             created_before: api.created_before,
             updated_after: api.updated_after,
             updated_before: api.updated_before,
-        }
+            deleted: api.deleted,
+            due_after: api.due_after,
+            due_before: api.due_before,
+            overdue: api.overdue,
+            priorities: api.priorities.map(|priorities| {
+                priorities
+                    .into_iter()
+                    .map(|priority| PriorityMapper::from(priority).into())
+                    .collect()
+            }),
+            tags_any: api.tags_any,
+            tags_all: api.tags_all,
+            tags_none: api.tags_none,
+            blocked: api.blocked,
+            series_id: api.series_id,
+            project_id: api.project_id,
+            top_level: api.top_level,
+            assignee: resolve_user(api.assignee, user_id, "assignee must be a user ID or \"me\"")?,
+            unassigned: api.unassigned,
+            created_by: resolve_user(api.created_by, user_id, "createdBy must be a user ID or \"me\"")?,
+            workspace_id: api.workspace_id,
+        })
     }
 }
 
@@ -94,6 +147,9 @@ impl From<ApiTaskFilterAndSortSortField> for TaskFilterAndSortSortFieldMapper {
             ApiTaskFilterAndSortSortField::UpdatedAt => Self::UpdatedAt,
             ApiTaskFilterAndSortSortField::Title => Self::Title,
             ApiTaskFilterAndSortSortField::Done => Self::Done,
+            ApiTaskFilterAndSortSortField::DueAt => Self::DueAt,
+            ApiTaskFilterAndSortSortField::Priority => Self::Priority,
+            ApiTaskFilterAndSortSortField::Relevance => Self::Relevance,
         }
     }
 }
@@ -112,6 +168,8 @@ impl From<TaskFilterAndSortMapper> for TaskFilterAndSortDto {
         TaskFilterAndSortDto {
             filters: mapper.filters.map(Into::into),
             sort: mapper.sort.map(Into::into),
+            limit: mapper.limit,
+            cursor: mapper.cursor,
         }
     }
 }
@@ -126,8 +184,17 @@ impl From<TaskFilterAndSortSortMapper> for TaskFilterAndSortSortDto {
 }
 
 impl TaskFilterAndSortMapper {
-    pub fn from_api(api: ApiTaskFilterAndSort) -> Self {
-        api.into()
+    /// `user_id` is the signed-in user, whom `me` refers to in the filters.
+    pub fn from_api(api: ApiTaskFilterAndSort, user_id: i64) -> Result<Self, &'static str> {
+        Ok(Self {
+            filters: api
+                .filters
+                .map(|filters| TaskFilterAndSortFiltersMapper::from_api(filters, user_id))
+                .transpose()?,
+            sort: api.sort.map(TaskFilterAndSortSortMapper::from),
+            limit: api.limit.and_then(|limit| u64::try_from(limit).ok()),
+            cursor: api.cursor,
+        })
     }
 
     pub fn into_dto(self) -> TaskFilterAndSortDto {
@@ -136,12 +203,12 @@ impl TaskFilterAndSortMapper {
 }
 
 pub trait ToTaskFilterAndSortDto {
-    fn to_dto(self) -> TaskFilterAndSortDto;
+    /// Fails when a filter names a user by anything but an ID or `me`.
+    fn to_dto(self, user_id: i64) -> Result<TaskFilterAndSortDto, &'static str>;
 }
 
 impl ToTaskFilterAndSortDto for ApiTaskFilterAndSort {
-    fn to_dto(self) -> TaskFilterAndSortDto {
-        let mapper: TaskFilterAndSortMapper = self.into();
-        mapper.into()
+    fn to_dto(self, user_id: i64) -> Result<TaskFilterAndSortDto, &'static str> {
+        Ok(TaskFilterAndSortMapper::from_api(self, user_id)?.into())
     }
 }
\ No newline at end of file
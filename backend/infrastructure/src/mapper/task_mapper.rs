@@ -1,6 +1,11 @@
 use o2o::o2o;
 use chrono::{DateTime, Utc};
+use domain::tag::Tag;
 use domain::task::Task;
+use domain::user::User;
+use crate::mapper::priority_mapper::PriorityMapper;
+use crate::mapper::tag_mapper::TagMapper;
+use crate::mapper::user_mapper::ToTaskAssigneeApiModel;
 use crate::persistence::entity::task::Model as TaskPersistenceModel;
 use openapi_client::models::Task as TaskApiModel;
 
@@ -13,9 +18,45 @@ use openapi_client::models::Task as TaskApiModel;
 pub struct TaskMapper {
     pub id: i64,
     pub title: String,
+    pub description: Option<String>,
     pub done: bool,
+    #[map(~.into())]
+    pub priority: PriorityMapper,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub parent_id: Option<i64>,
+    // tags live in their own table and are attached by the repository
+    #[ghost(TaskPersistenceModel| {vec![]})]
+    #[map(TaskApiModel| ~.into_iter().map(|tag| TagMapper::from(tag).into()).collect())]
+    pub tags: Vec<Tag>,
+    // subtasks are only nested when a tree is requested
+    #[ghost(TaskPersistenceModel| {vec![]})]
+    #[from(TaskApiModel| ~.unwrap_or_default().into_iter().map(|child| TaskMapper::from(child).into()).collect())]
+    #[into(TaskApiModel| (!~.is_empty()).then(|| ~.into_iter().map(ToApiModel::to_api_model).collect()))]
+    pub children: Vec<Task>,
+    // dependencies live in their own table and are attached by the repository
+    #[ghost(TaskPersistenceModel| {vec![]})]
+    pub blocker_ids: Vec<i64>,
+    #[ghost(TaskPersistenceModel| {false})]
+    pub blocked: bool,
+    pub recurrence_rule: Option<String>,
+    pub recurrence_time_zone: Option<String>,
+    pub series_id: Option<i64>,
+    // only needed for scheduling the next occurrence
+    #[ghost(TaskApiModel| {None})]
+    pub series_start: Option<DateTime<Utc>>,
+    pub project_id: Option<i64>,
+    pub workspace_id: i64,
+    pub created_by: Option<i64>,
+    // assignments live in their own table and are attached by the repository
+    #[ghost(TaskPersistenceModel| {vec![]})]
+    #[from(TaskApiModel| ~.into_iter().map(|assignee| User { id: assignee.id, display_name: assignee.display_name, ..Default::default() }).collect())]
+    #[into(TaskApiModel| ~.into_iter().map(ToTaskAssigneeApiModel::to_task_assignee_api_model).collect())]
+    pub assignees: Vec<User>,
+    pub version: i64,
 }
 
 impl From<Task> for TaskPersistenceModel {
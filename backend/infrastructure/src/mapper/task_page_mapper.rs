@@ -0,0 +1,28 @@
+use application::task::task_page_dto::{TaskHighlightDto, TaskPageDto};
+use crate::mapper::task_mapper::ToApiModel;
+use openapi_client::models::{TaskHighlight as TaskHighlightApiModel, TaskPage as TaskPageApiModel};
+
+pub trait ToTaskPageApiModel {
+    fn to_api_model(self) -> TaskPageApiModel;
+}
+
+impl ToTaskPageApiModel for TaskPageDto {
+    fn to_api_model(self) -> TaskPageApiModel {
+        TaskPageApiModel {
+            items: self.items.into_iter().map(ToApiModel::to_api_model).collect(),
+            next_cursor: self.next_cursor,
+            total_estimate: self.total_estimate as i64,
+            // only searches highlight anything
+            highlights: (!self.highlights.is_empty())
+                .then(|| self.highlights.into_iter().map(to_highlight_api_model).collect()),
+        }
+    }
+}
+
+fn to_highlight_api_model(highlight: TaskHighlightDto) -> TaskHighlightApiModel {
+    TaskHighlightApiModel {
+        task_id: highlight.task_id,
+        title: highlight.title,
+        description: highlight.description,
+    }
+}
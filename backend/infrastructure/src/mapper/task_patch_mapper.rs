@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+
+use crate::mapper::priority_mapper::PriorityMapper;
+use application::task::task_patch_dto::TaskPatchDto;
+use openapi_client::models::Priority as ApiPriority;
+
+/// Body of `PATCH /tasks/{id}`. The generated models cannot tell an explicit `null`,
+/// which clears a field, from an absent one, so the patch is read by hand.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TaskPatch {
+    title: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    description: Option<Option<String>>,
+    done: Option<bool>,
+    priority: Option<ApiPriority>,
+    #[serde(default, deserialize_with = "present")]
+    due_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "present")]
+    parent_id: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present")]
+    project_id: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present")]
+    recurrence_rule: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    recurrence_time_zone: Option<Option<String>>,
+}
+
+/// Only called for fields that are present, so `null` turns into `Some(None)`.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+pub trait ToTaskPatchDto {
+    fn to_dto(self) -> TaskPatchDto;
+}
+
+impl ToTaskPatchDto for TaskPatch {
+    fn to_dto(self) -> TaskPatchDto {
+        TaskPatchDto {
+            title: self.title,
+            description: self.description,
+            done: self.done,
+            priority: self.priority.map(|priority| PriorityMapper::from(priority).into()),
+            due_at: self.due_at,
+            parent_id: self.parent_id,
+            project_id: self.project_id,
+            recurrence_rule: self.recurrence_rule,
+            recurrence_time_zone: self.recurrence_time_zone,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::persistence::entity::refresh_token::Model as RefreshTokenPersistenceModel;
+use crate::persistence::entity::user::Model as UserPersistenceModel;
+use crate::persistence::entity::user_identity::Model as UserIdentityPersistenceModel;
+use application::auth::auth_dto::AuthSessionDto;
+use domain::user::{RefreshToken, User, UserIdentity};
+use openapi_client::models::{AuthSession as AuthSessionApiModel, TaskAssignee as TaskAssigneeApiModel, User as UserApiModel};
+
+impl From<UserPersistenceModel> for User {
+    fn from(model: UserPersistenceModel) -> Self {
+        User {
+            id: model.id,
+            email: model.email,
+            display_name: model.display_name,
+            password_hash: model.password_hash,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+impl From<User> for UserPersistenceModel {
+    fn from(user: User) -> Self {
+        UserPersistenceModel {
+            id: user.id,
+            email: user.email,
+            display_name: user.display_name,
+            password_hash: user.password_hash,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+impl From<RefreshTokenPersistenceModel> for RefreshToken {
+    fn from(model: RefreshTokenPersistenceModel) -> Self {
+        RefreshToken {
+            id: model.id,
+            user_id: model.user_id,
+            token_hash: model.token_hash,
+            family: model.family,
+            expires_at: model.expires_at,
+            created_at: model.created_at,
+            revoked_at: model.revoked_at,
+        }
+    }
+}
+
+impl From<RefreshToken> for RefreshTokenPersistenceModel {
+    fn from(refresh_token: RefreshToken) -> Self {
+        RefreshTokenPersistenceModel {
+            id: refresh_token.id,
+            user_id: refresh_token.user_id,
+            token_hash: refresh_token.token_hash,
+            family: refresh_token.family,
+            expires_at: refresh_token.expires_at,
+            created_at: refresh_token.created_at,
+            revoked_at: refresh_token.revoked_at,
+        }
+    }
+}
+
+impl From<UserIdentityPersistenceModel> for UserIdentity {
+    fn from(model: UserIdentityPersistenceModel) -> Self {
+        UserIdentity {
+            id: model.id,
+            user_id: model.user_id,
+            issuer: model.issuer,
+            subject: model.subject,
+            created_at: model.created_at,
+        }
+    }
+}
+
+impl From<UserIdentity> for UserIdentityPersistenceModel {
+    fn from(identity: UserIdentity) -> Self {
+        UserIdentityPersistenceModel {
+            id: identity.id,
+            user_id: identity.user_id,
+            issuer: identity.issuer,
+            subject: identity.subject,
+            created_at: identity.created_at,
+        }
+    }
+}
+
+pub trait ToUserApiModel {
+    fn to_api_model(self) -> UserApiModel;
+}
+
+/// Leaves out the password hash.
+impl ToUserApiModel for User {
+    fn to_api_model(self) -> UserApiModel {
+        UserApiModel {
+            id: self.id,
+            email: self.email,
+            display_name: self.display_name,
+            created_at: self.created_at,
+        }
+    }
+}
+
+pub trait ToTaskAssigneeApiModel {
+    fn to_task_assignee_api_model(self) -> TaskAssigneeApiModel;
+}
+
+/// Everyone who can see a task sees its assignees, so they are shown by name only.
+impl ToTaskAssigneeApiModel for User {
+    fn to_task_assignee_api_model(self) -> TaskAssigneeApiModel {
+        TaskAssigneeApiModel {
+            id: self.id,
+            display_name: self.display_name,
+        }
+    }
+}
+
+pub trait ToAuthSessionApiModel {
+    fn to_api_model(self) -> AuthSessionApiModel;
+}
+
+impl ToAuthSessionApiModel for AuthSessionDto {
+    fn to_api_model(self) -> AuthSessionApiModel {
+        AuthSessionApiModel {
+            access_token: self.access_token,
+            access_token_expires_at: self.access_token_expires_at,
+            refresh_token: self.refresh_token,
+            refresh_token_expires_at: self.refresh_token_expires_at,
+            user: self.user.to_api_model(),
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::mapper::task_mapper::ToApiModel;
+use crate::persistence::entity::webhook::Model as WebhookPersistenceModel;
+use crate::persistence::entity::webhook_delivery::{
+    DeliveryStatus as DeliveryPersistenceStatus, Model as WebhookDeliveryPersistenceModel,
+};
+use application::task::task_event_dto::TaskEventDto;
+use application::webhook::webhook_dto::{NewWebhookDto, UpdateWebhookDto};
+use domain::webhook::{DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent};
+use openapi_client::models::Task as TaskApiModel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEventApiModel {
+    TaskCreated,
+    TaskUpdated,
+    TaskDeleted,
+}
+
+impl From<WebhookEventApiModel> for WebhookEvent {
+    fn from(event: WebhookEventApiModel) -> Self {
+        match event {
+            WebhookEventApiModel::TaskCreated => WebhookEvent::TaskCreated,
+            WebhookEventApiModel::TaskUpdated => WebhookEvent::TaskUpdated,
+            WebhookEventApiModel::TaskDeleted => WebhookEvent::TaskDeleted,
+        }
+    }
+}
+
+impl From<WebhookEvent> for WebhookEventApiModel {
+    fn from(event: WebhookEvent) -> Self {
+        match event {
+            WebhookEvent::TaskCreated => WebhookEventApiModel::TaskCreated,
+            WebhookEvent::TaskUpdated => WebhookEventApiModel::TaskUpdated,
+            WebhookEvent::TaskDeleted => WebhookEventApiModel::TaskDeleted,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewWebhook {
+    pub workspace_id: i64,
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<WebhookEventApiModel>,
+    pub secret: String,
+    #[serde(default = "active_by_default")]
+    pub active: bool,
+}
+
+fn active_by_default() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhook {
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<WebhookEventApiModel>,
+    /// Keeps the current secret when absent.
+    pub secret: Option<String>,
+    pub active: bool,
+}
+
+impl From<NewWebhook> for NewWebhookDto {
+    fn from(webhook: NewWebhook) -> Self {
+        NewWebhookDto {
+            workspace_id: webhook.workspace_id,
+            url: webhook.url,
+            events: webhook.events.into_iter().map(Into::into).collect(),
+            secret: webhook.secret,
+            active: webhook.active,
+        }
+    }
+}
+
+impl From<UpdateWebhook> for UpdateWebhookDto {
+    fn from(webhook: UpdateWebhook) -> Self {
+        UpdateWebhookDto {
+            url: webhook.url,
+            events: webhook.events.into_iter().map(Into::into).collect(),
+            secret: webhook.secret,
+            active: webhook.active,
+        }
+    }
+}
+
+/// A webhook as returned by the API, without its secret.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookApiModel {
+    pub id: i64,
+    pub workspace_id: i64,
+    pub url: String,
+    pub events: Vec<WebhookEventApiModel>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Webhook> for WebhookApiModel {
+    fn from(webhook: Webhook) -> Self {
+        WebhookApiModel {
+            id: webhook.id,
+            workspace_id: webhook.workspace_id,
+            url: webhook.url,
+            events: webhook.events.into_iter().map(Into::into).collect(),
+            active: webhook.active,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeliveryStatusApiModel {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryApiModel {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: WebhookEventApiModel,
+    /// The JSON body that was sent.
+    pub payload: serde_json::Value,
+    pub status: DeliveryStatusApiModel,
+    pub attempts: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redelivery_of: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryApiModel {
+    fn from(delivery: WebhookDelivery) -> Self {
+        WebhookDeliveryApiModel {
+            id: delivery.id,
+            webhook_id: delivery.webhook_id,
+            event: delivery.event.into(),
+            payload: serde_json::from_str(&delivery.payload).unwrap_or(serde_json::Value::String(delivery.payload)),
+            status: match delivery.status {
+                DeliveryStatus::Pending => DeliveryStatusApiModel::Pending,
+                DeliveryStatus::Succeeded => DeliveryStatusApiModel::Succeeded,
+                DeliveryStatus::Failed => DeliveryStatusApiModel::Failed,
+            },
+            attempts: delivery.attempts,
+            next_attempt_at: delivery.next_attempt_at,
+            response_status: delivery.response_status,
+            error: delivery.error,
+            redelivery_of: delivery.redelivery_of,
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
+
+/// Body POSTed to webhooks. `task` is sent for created and updated tasks, `taskId` for deleted ones.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub event: WebhookEventApiModel,
+    pub occurred_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<TaskApiModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<i64>,
+}
+
+impl WebhookPayload {
+    /// The webhook event for the task event, the ID of the task's workspace and the payload.
+    pub fn from_task_event(event: TaskEventDto, occurred_at: DateTime<Utc>) -> (WebhookEvent, i64, WebhookPayload) {
+        let (webhook_event, workspace_id, task, task_id) = match event {
+            TaskEventDto::Created(task) => {
+                (WebhookEvent::TaskCreated, task.workspace_id, Some(task.to_api_model()), None)
+            }
+            TaskEventDto::Updated(task) => {
+                (WebhookEvent::TaskUpdated, task.workspace_id, Some(task.to_api_model()), None)
+            }
+            TaskEventDto::Deleted { id, workspace_id } => (WebhookEvent::TaskDeleted, workspace_id, None, Some(id)),
+        };
+        let payload = WebhookPayload { event: webhook_event.into(), occurred_at, task, task_id };
+        (webhook_event, workspace_id, payload)
+    }
+}
+
+impl From<WebhookPersistenceModel> for Webhook {
+    fn from(model: WebhookPersistenceModel) -> Self {
+        Webhook {
+            id: model.id,
+            workspace_id: model.workspace_id,
+            url: model.url,
+            // names that are no longer known are dropped rather than failing the whole webhook
+            events: model.events.split(',').filter_map(WebhookEvent::from_name).collect(),
+            secret: model.secret,
+            active: model.active,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+impl From<Webhook> for WebhookPersistenceModel {
+    fn from(webhook: Webhook) -> Self {
+        WebhookPersistenceModel {
+            id: webhook.id,
+            workspace_id: webhook.workspace_id,
+            url: webhook.url,
+            events: webhook.events.iter().map(|event| event.name()).collect::<Vec<_>>().join(","),
+            secret: webhook.secret,
+            active: webhook.active,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+impl From<WebhookDeliveryPersistenceModel> for WebhookDelivery {
+    fn from(model: WebhookDeliveryPersistenceModel) -> Self {
+        WebhookDelivery {
+            id: model.id,
+            webhook_id: model.webhook_id,
+            event: WebhookEvent::from_name(&model.event).unwrap_or_default(),
+            payload: model.payload,
+            status: match model.status {
+                DeliveryPersistenceStatus::Pending => DeliveryStatus::Pending,
+                DeliveryPersistenceStatus::Succeeded => DeliveryStatus::Succeeded,
+                DeliveryPersistenceStatus::Failed => DeliveryStatus::Failed,
+            },
+            attempts: model.attempts,
+            next_attempt_at: model.next_attempt_at,
+            response_status: model.response_status,
+            error: model.error,
+            redelivery_of: model.redelivery_of,
+            created_at: model.created_at,
+            delivered_at: model.delivered_at,
+        }
+    }
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryPersistenceModel {
+    fn from(delivery: WebhookDelivery) -> Self {
+        WebhookDeliveryPersistenceModel {
+            id: delivery.id,
+            webhook_id: delivery.webhook_id,
+            event: delivery.event.name().to_string(),
+            payload: delivery.payload,
+            status: match delivery.status {
+                DeliveryStatus::Pending => DeliveryPersistenceStatus::Pending,
+                DeliveryStatus::Succeeded => DeliveryPersistenceStatus::Succeeded,
+                DeliveryStatus::Failed => DeliveryPersistenceStatus::Failed,
+            },
+            attempts: delivery.attempts,
+            next_attempt_at: delivery.next_attempt_at,
+            response_status: delivery.response_status,
+            error: delivery.error,
+            redelivery_of: delivery.redelivery_of,
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
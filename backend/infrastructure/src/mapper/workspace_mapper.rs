@@ -0,0 +1,104 @@
+use o2o::o2o;
+
+use crate::persistence::entity::workspace::Model as WorkspacePersistenceModel;
+use crate::persistence::entity::workspace_member::{
+    MemberRole as WorkspacePersistenceRole, Model as WorkspaceMemberPersistenceModel,
+};
+use application::workspace::workspace_dto::WorkspaceMembershipDto;
+use domain::workspace::{Workspace, WorkspaceMember, WorkspaceRole};
+use openapi_client::models::{
+    Workspace as WorkspaceApiModel, WorkspaceMember as WorkspaceMemberApiModel,
+    WorkspaceMembership as WorkspaceMembershipApiModel, WorkspaceRole as WorkspaceApiRole,
+};
+
+#[derive(Debug, Clone, Copy, Default, o2o)]
+#[map_owned(WorkspaceRole)]
+#[map_owned(WorkspacePersistenceRole)]
+#[map_owned(WorkspaceApiRole)]
+pub enum WorkspaceRoleMapper {
+    Viewer,
+    #[default]
+    Member,
+    Admin,
+    Owner,
+}
+
+impl From<WorkspacePersistenceModel> for Workspace {
+    fn from(model: WorkspacePersistenceModel) -> Self {
+        Workspace {
+            id: model.id,
+            name: model.name,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+impl From<Workspace> for WorkspacePersistenceModel {
+    fn from(workspace: Workspace) -> Self {
+        WorkspacePersistenceModel {
+            id: workspace.id,
+            name: workspace.name,
+            created_at: workspace.created_at,
+            updated_at: workspace.updated_at,
+        }
+    }
+}
+
+impl From<WorkspaceMemberPersistenceModel> for WorkspaceMember {
+    fn from(model: WorkspaceMemberPersistenceModel) -> Self {
+        WorkspaceMember {
+            workspace_id: model.workspace_id,
+            user_id: model.user_id,
+            role: WorkspaceRoleMapper::from(model.role).into(),
+            created_at: model.created_at,
+        }
+    }
+}
+
+impl From<WorkspaceMember> for WorkspaceMemberPersistenceModel {
+    fn from(member: WorkspaceMember) -> Self {
+        WorkspaceMemberPersistenceModel {
+            workspace_id: member.workspace_id,
+            user_id: member.user_id,
+            role: WorkspaceRoleMapper::from(member.role).into(),
+            created_at: member.created_at,
+        }
+    }
+}
+
+pub fn to_workspace_role(role: WorkspaceApiRole) -> WorkspaceRole {
+    WorkspaceRoleMapper::from(role).into()
+}
+
+pub trait ToWorkspaceMembershipApiModel {
+    fn to_api_model(self) -> WorkspaceMembershipApiModel;
+}
+
+impl ToWorkspaceMembershipApiModel for WorkspaceMembershipDto {
+    fn to_api_model(self) -> WorkspaceMembershipApiModel {
+        WorkspaceMembershipApiModel {
+            workspace: WorkspaceApiModel {
+                id: self.workspace.id,
+                name: self.workspace.name,
+                created_at: self.workspace.created_at,
+                updated_at: self.workspace.updated_at,
+            },
+            role: WorkspaceRoleMapper::from(self.role).into(),
+        }
+    }
+}
+
+pub trait ToWorkspaceMemberApiModel {
+    fn to_api_model(self) -> WorkspaceMemberApiModel;
+}
+
+impl ToWorkspaceMemberApiModel for WorkspaceMember {
+    fn to_api_model(self) -> WorkspaceMemberApiModel {
+        WorkspaceMemberApiModel {
+            user_id: self.user_id,
+            role: WorkspaceRoleMapper::from(self.role).into(),
+            created_at: self.created_at,
+        }
+    }
+}
@@ -1 +1,14 @@
 pub mod task;
+pub mod tag;
+pub mod task_tag;
+pub mod task_dependency;
+pub mod project;
+pub mod webhook;
+pub mod webhook_delivery;
+pub mod user;
+pub mod refresh_token;
+pub mod api_token;
+pub mod user_identity;
+pub mod task_assignee;
+pub mod workspace;
+pub mod workspace_member;
\ No newline at end of file
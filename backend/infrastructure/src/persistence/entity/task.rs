@@ -7,11 +7,127 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i64,
     pub title: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
     pub done: bool,
+    pub priority: TaskPriority,
     pub created_at: chrono::DateTime<Utc>,
     pub updated_at: chrono::DateTime<Utc>,
+    pub deleted_at: Option<chrono::DateTime<Utc>>,
+    pub completed_at: Option<chrono::DateTime<Utc>>,
+    pub due_at: Option<chrono::DateTime<Utc>>,
+    pub parent_id: Option<i64>,
+    pub recurrence_rule: Option<String>,
+    pub recurrence_time_zone: Option<String>,
+    pub series_id: Option<i64>,
+    pub series_start: Option<chrono::DateTime<Utc>>,
+    pub project_id: Option<i64>,
+    pub workspace_id: i64,
+    pub created_by: Option<i64>,
+    pub version: i64,
 }
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "i16", db_type = "SmallInteger")]
+pub enum TaskPriority {
+    #[default]
+    #[sea_orm(num_value = 0)]
+    None,
+    #[sea_orm(num_value = 1)]
+    Low,
+    #[sea_orm(num_value = 2)]
+    Medium,
+    #[sea_orm(num_value = 3)]
+    High,
+    #[sea_orm(num_value = 4)]
+    Urgent,
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::task_tag::Entity")]
+    TaskTag,
+    #[sea_orm(has_many = "super::task_assignee::Entity")]
+    TaskAssignee,
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentId",
+        to = "Column::Id",
+        on_delete = "SetNull"
+    )]
+    Parent,
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::SeriesId",
+        to = "Column::Id",
+        on_delete = "SetNull"
+    )]
+    Series,
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Project,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::CreatedBy",
+        to = "super::user::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Creator,
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Workspace,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
+impl Related<super::task_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TaskTag.def()
+    }
+}
+
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::task_tag::Relation::Tag.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::task_tag::Relation::Task.def().rev())
+    }
+}
+
+impl Related<super::task_assignee::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TaskAssignee.def()
+    }
+}
+
+// users are related through their assignments, the creator is only referenced by id
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::task_assignee::Relation::User.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::task_assignee::Relation::Task.def().rev())
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
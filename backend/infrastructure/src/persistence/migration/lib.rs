@@ -1,11 +1,51 @@
 pub use sea_orm_migration::prelude::*;
 use crate::persistence::migration::m20251103_183001_create_table;
+use crate::persistence::migration::m20251110_190000_add_deleted_at;
+use crate::persistence::migration::m20251112_174500_add_completed_at;
+use crate::persistence::migration::m20251114_101500_add_description;
+use crate::persistence::migration::m20251117_083000_add_due_at;
+use crate::persistence::migration::m20251119_141000_add_priority;
+use crate::persistence::migration::m20251121_160000_create_tags;
+use crate::persistence::migration::m20251124_093000_add_parent_id;
+use crate::persistence::migration::m20251126_110000_create_task_dependencies;
+use crate::persistence::migration::m20251128_150000_add_recurrence;
+use crate::persistence::migration::m20251201_091500_create_projects;
+use crate::persistence::migration::m20251203_140000_add_task_version;
+use crate::persistence::migration::m20251208_100000_create_webhooks;
+use crate::persistence::migration::m20251210_090000_add_task_search;
+use crate::persistence::migration::m20251212_090000_create_users;
+use crate::persistence::migration::m20251214_090000_create_api_tokens;
+use crate::persistence::migration::m20251216_090000_create_user_identities;
+use crate::persistence::migration::m20251218_090000_add_task_ownership;
+use crate::persistence::migration::m20251220_090000_create_workspaces;
+use crate::persistence::migration::m20251222_090000_add_series_start;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20251103_183001_create_table::Migration)]
+        vec![
+            Box::new(m20251103_183001_create_table::Migration),
+            Box::new(m20251110_190000_add_deleted_at::Migration),
+            Box::new(m20251112_174500_add_completed_at::Migration),
+            Box::new(m20251114_101500_add_description::Migration),
+            Box::new(m20251117_083000_add_due_at::Migration),
+            Box::new(m20251119_141000_add_priority::Migration),
+            Box::new(m20251121_160000_create_tags::Migration),
+            Box::new(m20251124_093000_add_parent_id::Migration),
+            Box::new(m20251126_110000_create_task_dependencies::Migration),
+            Box::new(m20251128_150000_add_recurrence::Migration),
+            Box::new(m20251201_091500_create_projects::Migration),
+            Box::new(m20251203_140000_add_task_version::Migration),
+            Box::new(m20251208_100000_create_webhooks::Migration),
+            Box::new(m20251210_090000_add_task_search::Migration),
+            Box::new(m20251212_090000_create_users::Migration),
+            Box::new(m20251214_090000_create_api_tokens::Migration),
+            Box::new(m20251216_090000_create_user_identities::Migration),
+            Box::new(m20251218_090000_add_task_ownership::Migration),
+            Box::new(m20251220_090000_create_workspaces::Migration),
+            Box::new(m20251222_090000_add_series_start::Migration),
+        ]
     }
 }
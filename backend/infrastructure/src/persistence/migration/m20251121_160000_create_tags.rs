@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Tags::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Tags::Id)
+                            .primary_key()
+                            .big_integer()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Tags::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(Tags::Color).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskTags::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TaskTags::TaskId).big_integer().not_null())
+                    .col(ColumnDef::new(TaskTags::TagId).big_integer().not_null())
+                    .primary_key(Index::create().col(TaskTags::TaskId).col(TaskTags::TagId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_tags_task_id")
+                            .from(TaskTags::Table, TaskTags::TaskId)
+                            .to(Tasks::Table, Tasks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_tags_tag_id")
+                            .from(TaskTags::Table, TaskTags::TagId)
+                            .to(Tags::Table, Tags::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_tags_tag_id")
+                    .table(TaskTags::Table)
+                    .col(TaskTags::TagId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskTags::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Tags::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Tasks {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Tags {
+    Table,
+    Id,
+    Name,
+    Color,
+}
+
+#[derive(Iden)]
+enum TaskTags {
+    Table,
+    TaskId,
+    TagId,
+}
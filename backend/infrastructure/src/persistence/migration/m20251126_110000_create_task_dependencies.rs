@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // longer cycles are rejected by the application, the check only guards against self-blocking
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskDependencies::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TaskDependencies::TaskId).big_integer().not_null())
+                    .col(ColumnDef::new(TaskDependencies::BlockerId).big_integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(TaskDependencies::TaskId)
+                            .col(TaskDependencies::BlockerId),
+                    )
+                    .check(Expr::col(TaskDependencies::TaskId).ne(Expr::col(TaskDependencies::BlockerId)))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_dependencies_task_id")
+                            .from(TaskDependencies::Table, TaskDependencies::TaskId)
+                            .to(Tasks::Table, Tasks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_dependencies_blocker_id")
+                            .from(TaskDependencies::Table, TaskDependencies::BlockerId)
+                            .to(Tasks::Table, Tasks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_dependencies_blocker_id")
+                    .table(TaskDependencies::Table)
+                    .col(TaskDependencies::BlockerId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskDependencies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Tasks {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum TaskDependencies {
+    Table,
+    TaskId,
+    BlockerId,
+}
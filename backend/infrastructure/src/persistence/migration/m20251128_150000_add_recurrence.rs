@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(ColumnDef::new(Tasks::RecurrenceRule).string().null())
+                    .add_column(ColumnDef::new(Tasks::RecurrenceTimeZone).string().null())
+                    .add_column(ColumnDef::new(Tasks::SeriesId).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // purging the first task of a series leaves the remaining occurrences in place
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_tasks_series_id")
+                    .from(Tasks::Table, Tasks::SeriesId)
+                    .to(Tasks::Table, Tasks::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tasks_series_id")
+                    .table(Tasks::Table)
+                    .col(Tasks::SeriesId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_tasks_series_id")
+                    .table(Tasks::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::RecurrenceRule)
+                    .drop_column(Tasks::RecurrenceTimeZone)
+                    .drop_column(Tasks::SeriesId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Tasks {
+    Table,
+    Id,
+    RecurrenceRule,
+    RecurrenceTimeZone,
+    SeriesId,
+}
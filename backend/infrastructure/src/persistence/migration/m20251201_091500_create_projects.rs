@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Projects::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Projects::Id)
+                            .primary_key()
+                            .big_integer()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Projects::Name).string().not_null())
+                    .col(ColumnDef::new(Projects::ArchivedAt).timestamp_with_time_zone().null())
+                    .col(
+                        ColumnDef::new(Projects::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Projects::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(ColumnDef::new(Tasks::ProjectId).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // deleting a project keeps its tasks, they just no longer belong to a project
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_tasks_project_id")
+                    .from(Tasks::Table, Tasks::ProjectId)
+                    .to(Projects::Table, Projects::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tasks_project_id")
+                    .table(Tasks::Table)
+                    .col(Tasks::ProjectId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::ProjectId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Projects::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Tasks {
+    Table,
+    ProjectId,
+}
+
+#[derive(Iden)]
+enum Projects {
+    Table,
+    Id,
+    Name,
+    ArchivedAt,
+    CreatedAt,
+    UpdatedAt,
+}
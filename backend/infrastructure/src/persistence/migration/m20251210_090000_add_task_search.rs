@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // titles weigh more than descriptions when ranking
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(
+                        ColumnDef::new(Tasks::SearchVector)
+                            .custom(Alias::new("tsvector"))
+                            .not_null()
+                            .generated(
+                                Expr::cust(
+                                    "setweight(to_tsvector('english', \"title\"), 'A') \
+                                     || setweight(to_tsvector('english', coalesce(\"description\", '')), 'B')",
+                                ),
+                                true,
+                            ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tasks_search_vector")
+                    .table(Tasks::Table)
+                    .col(Tasks::SearchVector)
+                    .full_text()
+                    .to_owned(),
+            )
+            .await?;
+
+        // lets the case-insensitive title filter use an index despite its leading wildcard
+        let connection = manager.get_connection();
+        connection
+            .execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .await?;
+        connection
+            .execute_unprepared("CREATE INDEX IF NOT EXISTS \"idx_tasks_title_trgm\" ON \"tasks\" USING GIN (\"title\" gin_trgm_ops)")
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_tasks_title_trgm").table(Tasks::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::SearchVector)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Tasks {
+    Table,
+    SearchVector,
+}
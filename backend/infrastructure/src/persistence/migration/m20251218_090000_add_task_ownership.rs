@@ -0,0 +1,115 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(ColumnDef::new(Tasks::CreatedBy).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // tasks outlive the account that created them
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_tasks_created_by")
+                    .from(Tasks::Table, Tasks::CreatedBy)
+                    .to(Users::Table, Users::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tasks_created_by")
+                    .table(Tasks::Table)
+                    .col(Tasks::CreatedBy)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskAssignees::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TaskAssignees::TaskId).big_integer().not_null())
+                    .col(ColumnDef::new(TaskAssignees::UserId).big_integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(TaskAssignees::TaskId)
+                            .col(TaskAssignees::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_assignees_task_id")
+                            .from(TaskAssignees::Table, TaskAssignees::TaskId)
+                            .to(Tasks::Table, Tasks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_assignees_user_id")
+                            .from(TaskAssignees::Table, TaskAssignees::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // serves the "assigned to me" view, the primary key covers lookups by task
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_assignees_user_id")
+                    .table(TaskAssignees::Table)
+                    .col(TaskAssignees::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskAssignees::Table).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::CreatedBy)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Tasks {
+    Table,
+    Id,
+    CreatedBy,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum TaskAssignees {
+    Table,
+    TaskId,
+    UserId,
+}
@@ -0,0 +1,229 @@
+use sea_orm_migration::prelude::*;
+
+/// Tables whose rows belong to a workspace, through a `workspace_id` column.
+const SCOPED_TABLES: [&str; 4] = ["tasks", "projects", "tags", "webhooks"];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Workspaces::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Workspaces::Id)
+                            .primary_key()
+                            .big_integer()
+                            .not_null()
+                            .auto_increment(),
+                    )
+                    .col(ColumnDef::new(Workspaces::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(Workspaces::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Workspaces::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WorkspaceMembers::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(WorkspaceMembers::WorkspaceId).big_integer().not_null())
+                    .col(ColumnDef::new(WorkspaceMembers::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(WorkspaceMembers::Role).small_integer().not_null())
+                    .col(
+                        ColumnDef::new(WorkspaceMembers::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(WorkspaceMembers::WorkspaceId)
+                            .col(WorkspaceMembers::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_workspace_members_workspace_id")
+                            .from(WorkspaceMembers::Table, WorkspaceMembers::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_workspace_members_user_id")
+                            .from(WorkspaceMembers::Table, WorkspaceMembers::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // lists a user's workspaces, the primary key covers lookups by workspace
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_workspace_members_user_id")
+                    .table(WorkspaceMembers::Table)
+                    .col(WorkspaceMembers::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        for table in SCOPED_TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column(ColumnDef::new(WorkspaceScoped::WorkspaceId).big_integer().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // existing rows and users move into one shared workspace, owned by the first user, so that
+        // everybody keeps seeing what they saw before; roles are 1 for members, 3 for owners
+        let connection = manager.get_connection();
+        let existing_rows: String =
+            SCOPED_TABLES.iter().map(|table| format!(" OR EXISTS (SELECT 1 FROM {table})")).collect();
+        connection
+            .execute_unprepared(&format!(
+                "INSERT INTO workspaces (name) SELECT 'Default' WHERE EXISTS (SELECT 1 FROM users){existing_rows}"
+            ))
+            .await?;
+        connection
+            .execute_unprepared(
+                "INSERT INTO workspace_members (workspace_id, user_id, role) \
+                 SELECT (SELECT MIN(id) FROM workspaces), id, \
+                 CASE WHEN id = (SELECT MIN(id) FROM users) THEN 3 ELSE 1 END FROM users",
+            )
+            .await?;
+        for table in SCOPED_TABLES {
+            connection
+                .execute_unprepared(&format!("UPDATE {table} SET workspace_id = (SELECT MIN(id) FROM workspaces)"))
+                .await?;
+
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .modify_column(ColumnDef::new(WorkspaceScoped::WorkspaceId).big_integer().not_null())
+                        .to_owned(),
+                )
+                .await?;
+
+            manager
+                .create_foreign_key(
+                    ForeignKey::create()
+                        .name(format!("fk_{table}_workspace_id"))
+                        .from(Alias::new(table), WorkspaceScoped::WorkspaceId)
+                        .to(Workspaces::Table, Workspaces::Id)
+                        .on_delete(ForeignKeyAction::Cascade)
+                        .to_owned(),
+                )
+                .await?;
+
+            manager
+                .create_index(
+                    Index::create()
+                        .name(format!("idx_{table}_workspace_id"))
+                        .table(Alias::new(table))
+                        .col(WorkspaceScoped::WorkspaceId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // tag names were unique overall, now they are per workspace
+        connection
+            .execute_unprepared("ALTER TABLE tags DROP CONSTRAINT IF EXISTS tags_name_key")
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tags_workspace_id_name")
+                    .table(Tags::Table)
+                    .col(WorkspaceScoped::WorkspaceId)
+                    .col(Tags::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // dropping the workspace column takes the per-workspace index with it
+        manager
+            .get_connection()
+            .execute_unprepared("ALTER TABLE tags ADD CONSTRAINT tags_name_key UNIQUE (name)")
+            .await?;
+        for table in SCOPED_TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .drop_column(WorkspaceScoped::WorkspaceId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_table(Table::drop().table(WorkspaceMembers::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Workspaces::Table).to_owned())
+            .await
+    }
+}
+
+/// The column added to each of the `SCOPED_TABLES`.
+#[derive(Iden)]
+enum WorkspaceScoped {
+    WorkspaceId,
+}
+
+#[derive(Iden)]
+enum Tags {
+    Table,
+    Name,
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Workspaces {
+    Table,
+    Id,
+    Name,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum WorkspaceMembers {
+    Table,
+    WorkspaceId,
+    UserId,
+    Role,
+    CreatedAt,
+}
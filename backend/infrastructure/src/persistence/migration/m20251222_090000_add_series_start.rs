@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .add_column(ColumnDef::new(Tasks::SeriesStart).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE tasks SET series_start = COALESCE(first.due_at, first.created_at) \
+                 FROM tasks AS first WHERE tasks.series_id = first.id",
+            )
+            .await?;
+
+        // the series id names the series even after its first task has been purged, so that the
+        // remaining occurrences still belong together
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_tasks_series_id")
+                    .table(Tasks::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE tasks SET series_id = NULL WHERE series_id NOT IN (SELECT id FROM tasks)")
+            .await?;
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_tasks_series_id")
+                    .from(Tasks::Table, Tasks::SeriesId)
+                    .to(Tasks::Table, Tasks::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tasks::Table)
+                    .drop_column(Tasks::SeriesStart)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Tasks {
+    Table,
+    Id,
+    SeriesId,
+    SeriesStart,
+}
@@ -1,3 +1,22 @@
 pub mod lib;
 
-mod m20251103_183001_create_table;
\ No newline at end of file
+mod m20251103_183001_create_table;
+mod m20251110_190000_add_deleted_at;
+mod m20251112_174500_add_completed_at;
+mod m20251114_101500_add_description;
+mod m20251117_083000_add_due_at;
+mod m20251119_141000_add_priority;
+mod m20251121_160000_create_tags;
+mod m20251124_093000_add_parent_id;
+mod m20251126_110000_create_task_dependencies;
+mod m20251128_150000_add_recurrence;
+mod m20251201_091500_create_projects;
+mod m20251203_140000_add_task_version;
+mod m20251208_100000_create_webhooks;
+mod m20251210_090000_add_task_search;
+mod m20251212_090000_create_users;
+mod m20251214_090000_create_api_tokens;
+mod m20251216_090000_create_user_identities;
+mod m20251218_090000_add_task_ownership;
+mod m20251220_090000_create_workspaces;
+mod m20251222_090000_add_series_start;
\ No newline at end of file
@@ -0,0 +1,81 @@
+use crate::persistence::entity::api_token::{Column, Entity as ApiTokenEntity, Model as ApiTokenModel};
+use application::auth::api_token_repository_trait::ApiTokenRepositoryTrait;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use domain::api_token::ApiToken;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, IntoActiveModel, NotSet, QueryFilter,
+    QueryOrder,
+};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct ApiTokenRepository {
+    database_connection: Arc<DatabaseConnection>,
+}
+
+impl ApiTokenRepository {
+    pub fn new(database_connection: Arc<DatabaseConnection>) -> Self {
+        Self { database_connection }
+    }
+}
+
+#[async_trait]
+impl ApiTokenRepositoryTrait for ApiTokenRepository {
+    async fn save(&self, api_token: ApiToken) -> anyhow::Result<ApiToken> {
+        let model: ApiTokenModel = api_token.into();
+        let mut active_model = model.clone().into_active_model();
+        if model.id == 0 {
+            active_model.id = NotSet;
+            active_model.created_at = NotSet;
+            Ok(active_model.insert(&*self.database_connection).await?.into())
+        } else {
+            let active_model = active_model.reset_all();
+            Ok(active_model.update(&*self.database_connection).await?.into())
+        }
+    }
+
+    async fn find_by_user(&self, user_id: i64) -> anyhow::Result<Vec<ApiToken>> {
+        Ok(ApiTokenEntity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .all(&*self.database_connection)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> anyhow::Result<Option<ApiToken>> {
+        Ok(ApiTokenEntity::find()
+            .filter(Column::TokenHash.eq(token_hash))
+            .one(&*self.database_connection)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn delete(&self, user_id: i64, id: i64) -> anyhow::Result<bool> {
+        let result = ApiTokenEntity::delete_many()
+            .filter(Column::Id.eq(id))
+            .filter(Column::UserId.eq(user_id))
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn record_use(&self, id: i64, now: DateTime<Utc>) -> anyhow::Result<()> {
+        ApiTokenEntity::update_many()
+            .col_expr(Column::LastUsedAt, Expr::value(now))
+            .filter(Column::Id.eq(id))
+            .filter(
+                Condition::any()
+                    .add(Column::LastUsedAt.is_null())
+                    .add(Column::LastUsedAt.lt(now - TimeDelta::minutes(1))),
+            )
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(())
+    }
+}
@@ -1 +1,8 @@
-pub mod task_repository;
\ No newline at end of file
+pub mod task_repository;
+pub mod tag_repository;
+pub mod project_repository;
+mod task_cursor;
+pub mod webhook_repository;
+pub mod user_repository;
+pub mod api_token_repository;
+pub mod workspace_repository;
\ No newline at end of file
@@ -0,0 +1,96 @@
+use crate::persistence::entity::project::{Column, Entity as ProjectEntity, Model as ProjectModel};
+use crate::persistence::entity::task::{Column as TaskColumn, Entity as TaskEntity};
+use application::project::project_repository_trait::ProjectRepositoryTrait;
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::project::Project;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, NotSet, QueryFilter};
+use sea_orm::{QueryOrder, QuerySelect};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct ProjectRepository {
+    database_connection: Arc<DatabaseConnection>,
+}
+
+impl ProjectRepository {
+    pub fn new(database_connection: Arc<DatabaseConnection>) -> Self {
+        Self { database_connection }
+    }
+
+    async fn to_projects(&self, models: Vec<ProjectModel>) -> anyhow::Result<Vec<Project>> {
+        let project_ids: Vec<i64> = models.iter().map(|model| model.id).collect();
+        let open_task_counts: HashMap<i64, i64> = TaskEntity::find()
+            .select_only()
+            .column(TaskColumn::ProjectId)
+            .column_as(TaskColumn::Id.count(), "open_task_count")
+            .filter(TaskColumn::ProjectId.is_in(project_ids))
+            .filter(TaskColumn::Done.eq(false))
+            .filter(TaskColumn::DeletedAt.is_null())
+            .group_by(TaskColumn::ProjectId)
+            .into_tuple::<(i64, i64)>()
+            .all(&*self.database_connection)
+            .await?
+            .into_iter()
+            .collect();
+        Ok(models
+            .into_iter()
+            .map(|model| {
+                let mut project: Project = model.into();
+                project.open_task_count = open_task_counts.get(&project.id).copied().unwrap_or_default();
+                project
+            })
+            .collect())
+    }
+
+    async fn to_project(&self, model: ProjectModel) -> anyhow::Result<Project> {
+        let mut projects = self.to_projects(vec![model]).await?;
+        Ok(projects.remove(0))
+    }
+}
+
+#[async_trait]
+impl ProjectRepositoryTrait for ProjectRepository {
+    async fn save(&self, project: Project) -> anyhow::Result<Project> {
+        let model: ProjectModel = project.into();
+        let mut active_model = model.clone().into_active_model();
+        if model.id == 0 {
+            active_model.id = NotSet;
+            active_model.created_at = NotSet;
+            active_model.updated_at = NotSet;
+            let inserted = active_model.insert(&*self.database_connection).await?;
+            self.to_project(inserted).await
+        } else {
+            active_model.updated_at.set_if_not_equals(Utc::now());
+            let active_model = active_model.reset_all();
+            let updated = active_model.update(&*self.database_connection).await?;
+            self.to_project(updated).await
+        }
+    }
+
+    async fn find_by_workspace(&self, workspace_id: i64, include_archived: bool) -> anyhow::Result<Vec<Project>> {
+        let mut query = ProjectEntity::find()
+            .filter(Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(Column::Name);
+        if !include_archived {
+            query = query.filter(Column::ArchivedAt.is_null());
+        }
+        let projects = query.all(&*self.database_connection).await?;
+        self.to_projects(projects).await
+    }
+
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Project>> {
+        match ProjectEntity::find_by_id(id).one(&*self.database_connection).await? {
+            Some(model) => Ok(Some(self.to_project(model).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool> {
+        let result = ProjectEntity::delete_by_id(id)
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::persistence::entity::tag::{Column, Entity as TagEntity, Model as TagModel};
+use application::tag::tag_repository_trait::TagRepositoryTrait;
+use async_trait::async_trait;
+use domain::tag::Tag;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, NotSet, QueryFilter};
+use sea_orm::QueryOrder;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct TagRepository {
+    database_connection: Arc<DatabaseConnection>,
+}
+
+impl TagRepository {
+    pub fn new(database_connection: Arc<DatabaseConnection>) -> Self {
+        Self { database_connection }
+    }
+}
+
+#[async_trait]
+impl TagRepositoryTrait for TagRepository {
+    async fn save(&self, tag: Tag) -> anyhow::Result<Tag> {
+        let model: TagModel = tag.into();
+        let mut active_model = model.clone().into_active_model();
+        if model.id == 0 {
+            active_model.id = NotSet;
+            Ok(active_model.insert(&*self.database_connection).await?.into())
+        } else {
+            let active_model = active_model.reset_all();
+            Ok(active_model.update(&*self.database_connection).await?.into())
+        }
+    }
+
+    async fn find_by_workspace(&self, workspace_id: i64) -> anyhow::Result<Vec<Tag>> {
+        let tags = TagEntity::find()
+            .filter(Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(Column::Name)
+            .all(&*self.database_connection)
+            .await?;
+        Ok(tags.into_iter().map(Into::into).collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Tag>> {
+        Ok(TagEntity::find_by_id(id)
+            .one(&*self.database_connection)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool> {
+        let result = TagEntity::delete_by_id(id)
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,112 @@
+use crate::persistence::entity::task::{Column, Model as TaskModel};
+use crate::persistence::repository::task_repository::sort_expression;
+use application::task::task_filter_and_sort_dto::TaskFilterAndSortSortFieldDto;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::{ActiveEnum, ColumnTrait, Condition, Value};
+
+/// Position of the last task of a page: the value of the sort field and the id breaking ties.
+///
+/// Encoded as `field|value|id`, where an empty value stands for a missing due date.
+pub(crate) struct TaskCursor {
+    field: Option<TaskFilterAndSortSortFieldDto>,
+    value: Option<Value>,
+    id: i64,
+}
+
+impl TaskCursor {
+    /// `rank` is the relevance of the task, which is not part of the model.
+    pub(crate) fn from_model(model: &TaskModel, field: Option<TaskFilterAndSortSortFieldDto>, rank: Option<f32>) -> Self {
+        let value: Option<Value> = field.and_then(|field| match field {
+            TaskFilterAndSortSortFieldDto::CreatedAt => Some(model.created_at.into()),
+            TaskFilterAndSortSortFieldDto::UpdatedAt => Some(model.updated_at.into()),
+            TaskFilterAndSortSortFieldDto::Title => Some(model.title.clone().into()),
+            TaskFilterAndSortSortFieldDto::Done => Some(model.done.into()),
+            TaskFilterAndSortSortFieldDto::DueAt => model.due_at.map(Into::into),
+            TaskFilterAndSortSortFieldDto::Priority => Some(model.priority.to_value().into()),
+            TaskFilterAndSortSortFieldDto::Relevance => rank.map(Into::into),
+        });
+        Self { field, value, id: model.id }
+    }
+
+    pub(crate) fn encode(&self) -> String {
+        let value = match &self.value {
+            Some(Value::ChronoDateTimeUtc(Some(date_time))) => date_time.to_rfc3339(),
+            Some(Value::String(Some(text))) => text.to_string(),
+            Some(Value::Bool(Some(flag))) => flag.to_string(),
+            Some(Value::SmallInt(Some(rank))) => rank.to_string(),
+            Some(Value::Float(Some(relevance))) => relevance.to_string(),
+            _ => String::new(),
+        };
+        URL_SAFE_NO_PAD.encode(format!("{}|{value}|{}", field_name(self.field), self.id))
+    }
+
+    /// Reads a cursor, rejecting it unless it was issued for the given sort field.
+    pub(crate) fn decode(cursor: &str, field: Option<TaskFilterAndSortSortFieldDto>) -> Option<Self> {
+        let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+        // titles may contain the separator, so the id is split off from the end
+        let (rest, id) = decoded.rsplit_once('|')?;
+        let (name, value) = rest.split_once('|')?;
+        if name != field_name(field) {
+            return None;
+        }
+        let parse_date_time = |value: &str| value.parse::<DateTime<Utc>>().ok().map(Value::from);
+        let value = match field {
+            None => None,
+            Some(TaskFilterAndSortSortFieldDto::DueAt) if value.is_empty() => None,
+            Some(
+                TaskFilterAndSortSortFieldDto::CreatedAt
+                | TaskFilterAndSortSortFieldDto::UpdatedAt
+                | TaskFilterAndSortSortFieldDto::DueAt,
+            ) => Some(parse_date_time(value)?),
+            Some(TaskFilterAndSortSortFieldDto::Title) => Some(value.to_string().into()),
+            Some(TaskFilterAndSortSortFieldDto::Done) => Some(value.parse::<bool>().ok()?.into()),
+            Some(TaskFilterAndSortSortFieldDto::Priority) => Some(value.parse::<i16>().ok()?.into()),
+            Some(TaskFilterAndSortSortFieldDto::Relevance) => Some(value.parse::<f32>().ok()?.into()),
+        };
+        Some(Self { field, value, id: id.parse().ok()? })
+    }
+
+    /// Matches the tasks that come after this position in the given direction; `search` is needed
+    /// to rank tasks when sorting by relevance.
+    pub(crate) fn after(&self, ascending: bool, search: Option<&str>) -> Condition {
+        let beyond = |expression: SimpleExpr, value: Value| {
+            if ascending { Expr::expr(expression).gt(value) } else { Expr::expr(expression).lt(value) }
+        };
+        let beyond_id = beyond(Expr::col(Column::Id).into(), self.id.into());
+        match (self.field, &self.value) {
+            (None, _) => Condition::all().add(beyond_id),
+            // tasks without a due date come last in both directions
+            (Some(TaskFilterAndSortSortFieldDto::DueAt), None) => {
+                Condition::all().add(Column::DueAt.is_null()).add(beyond_id)
+            }
+            (Some(field), Some(value)) => {
+                let sort_value = sort_expression(field, search);
+                let mut condition = Condition::any()
+                    .add(beyond(sort_value.clone(), value.clone()))
+                    .add(Condition::all().add(Expr::expr(sort_value).eq(value.clone())).add(beyond_id));
+                if field == TaskFilterAndSortSortFieldDto::DueAt {
+                    condition = condition.add(Column::DueAt.is_null());
+                }
+                condition
+            }
+            // only a missing due date is stored without a value
+            (Some(_), None) => Condition::all().add(beyond_id),
+        }
+    }
+}
+
+fn field_name(field: Option<TaskFilterAndSortSortFieldDto>) -> &'static str {
+    match field {
+        None => "id",
+        Some(TaskFilterAndSortSortFieldDto::CreatedAt) => "createdAt",
+        Some(TaskFilterAndSortSortFieldDto::UpdatedAt) => "updatedAt",
+        Some(TaskFilterAndSortSortFieldDto::Title) => "title",
+        Some(TaskFilterAndSortSortFieldDto::Done) => "done",
+        Some(TaskFilterAndSortSortFieldDto::DueAt) => "dueAt",
+        Some(TaskFilterAndSortSortFieldDto::Priority) => "priority",
+        Some(TaskFilterAndSortSortFieldDto::Relevance) => "relevance",
+    }
+}
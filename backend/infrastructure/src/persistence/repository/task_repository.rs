@@ -1,26 +1,491 @@
-use crate::persistence::entity::task::{Column, Entity as TaskEntity, Model as TaskModel};
-use application::task::task_filter_and_sort_dto::{TaskFilterAndSortDto, TaskFilterAndSortSortDirectionDto, TaskFilterAndSortSortFieldDto};
-use application::task::task_repository_trait::TaskRepositoryTrait;
+use crate::mapper::priority_mapper::PriorityMapper;
+use crate::persistence::entity::project::{Column as ProjectColumn, Entity as ProjectEntity};
+use crate::persistence::entity::tag::Entity as TagEntity;
+use crate::persistence::entity::task::{Column, Entity as TaskEntity, Model as TaskModel, TaskPriority};
+use crate::persistence::entity::task_assignee::{
+    ActiveModel as TaskAssigneeActiveModel, Column as TaskAssigneeColumn, Entity as TaskAssigneeEntity,
+};
+use crate::persistence::entity::task_dependency::{
+    ActiveModel as TaskDependencyActiveModel, Column as TaskDependencyColumn, Entity as TaskDependencyEntity,
+};
+use crate::persistence::entity::task_tag::{
+    ActiveModel as TaskTagActiveModel, Column as TaskTagColumn, Entity as TaskTagEntity,
+};
+use crate::persistence::entity::user::{Entity as UserEntity, Model as UserModel};
+use crate::persistence::repository::task_cursor::TaskCursor;
+use application::task::task_error::TaskError;
+use application::task::task_filter_and_sort_dto::{
+    TaskFilterAndSortDto, TaskFilterAndSortFiltersDto, TaskFilterAndSortSortDirectionDto, TaskFilterAndSortSortDto,
+    TaskFilterAndSortSortFieldDto,
+};
+use application::task::task_page_dto::{TaskHighlightDto, TaskPageDto};
+use application::task::task_repository_trait::{TaskRepositoryTrait, TransactionWork};
 use async_trait::async_trait;
 use chrono::Utc;
 use domain::task::Task;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, NotSet};
-use sea_orm::{ColumnTrait, Condition, QueryFilter, QueryOrder};
+use domain::user::User;
+use sea_orm::sea_query::extension::postgres::PgExpr;
+use sea_orm::sea_query::{Alias, Expr, Func, NullOrdering, OnConflict, Query, SelectStatement, SimpleExpr};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, LoaderTrait, ModelTrait, NotSet, Set};
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use sea_orm::{ColumnTrait, Condition, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-#[derive(Clone)]
-pub struct TaskRepository {
-    database_connection: Arc<DatabaseConnection>,
+/// Works on the plain connection, or on an open transaction inside `transaction`.
+pub struct TaskRepository<C = DatabaseConnection> {
+    database_connection: Arc<C>,
 }
 
-impl TaskRepository {
-    pub fn new(database_connection: Arc<DatabaseConnection>) -> Self {
+impl<C> Clone for TaskRepository<C> {
+    fn clone(&self) -> Self {
+        Self { database_connection: self.database_connection.clone() }
+    }
+}
+
+impl<C: ConnectionTrait + TransactionTrait + Send + Sync + 'static> TaskRepository<C> {
+    pub fn new(database_connection: Arc<C>) -> Self {
         Self { database_connection }
     }
+
+    async fn to_task(&self, model: TaskModel) -> anyhow::Result<Task> {
+        let tags = model.find_related(TagEntity).all(&*self.database_connection).await?;
+        let assignees = model.find_related(UserEntity).all(&*self.database_connection).await?;
+        let mut task: Task = model.into();
+        task.tags = tags.into_iter().map(Into::into).collect();
+        task.assignees = to_assignees(assignees);
+        self.attach_blockers(std::slice::from_mut(&mut task)).await?;
+        Ok(task)
+    }
+
+    async fn to_tasks(&self, models: Vec<TaskModel>) -> anyhow::Result<Vec<Task>> {
+        let tags = models
+            .load_many_to_many(TagEntity, TaskTagEntity, &*self.database_connection)
+            .await?;
+        let assignees = models
+            .load_many_to_many(UserEntity, TaskAssigneeEntity, &*self.database_connection)
+            .await?;
+        let mut tasks: Vec<Task> = models
+            .into_iter()
+            .zip(tags)
+            .zip(assignees)
+            .map(|((model, tags), assignees)| {
+                let mut task: Task = model.into();
+                task.tags = tags.into_iter().map(Into::into).collect();
+                task.assignees = to_assignees(assignees);
+                task
+            })
+            .collect();
+        self.attach_blockers(&mut tasks).await?;
+        Ok(tasks)
+    }
+
+    /// Ranks the given tasks against a full-text search and highlights the matched words.
+    async fn search_matches(
+        &self,
+        task_ids: Vec<i64>,
+        search: &str,
+    ) -> anyhow::Result<HashMap<i64, (f32, TaskHighlightDto)>> {
+        let rows = TaskEntity::find()
+            .select_only()
+            .column(Column::Id)
+            .expr(search_rank(search))
+            .expr(headline(Column::Title, search, TITLE_HEADLINE_OPTIONS))
+            .expr(headline(Column::Description, search, DESCRIPTION_HEADLINE_OPTIONS))
+            .filter(Column::Id.is_in(task_ids))
+            .into_tuple::<(i64, f32, String, Option<String>)>()
+            .all(&*self.database_connection)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(task_id, rank, title, description)| {
+                let title = highlight_html(&title);
+                let description = description.as_deref().map(highlight_html);
+                (task_id, (rank, TaskHighlightDto { task_id, title, description }))
+            })
+            .collect())
+    }
+
+    async fn attach_blockers(&self, tasks: &mut [Task]) -> anyhow::Result<()> {
+        let task_ids: Vec<i64> = tasks.iter().map(|task| task.id).collect();
+        let dependencies = TaskDependencyEntity::find()
+            .filter(TaskDependencyColumn::TaskId.is_in(task_ids))
+            .order_by_asc(TaskDependencyColumn::BlockerId)
+            .all(&*self.database_connection)
+            .await?;
+        if dependencies.is_empty() {
+            return Ok(());
+        }
+        let open_blocker_ids: HashSet<i64> = TaskEntity::find()
+            .select_only()
+            .column(Column::Id)
+            .filter(Column::Id.is_in(dependencies.iter().map(|dependency| dependency.blocker_id)))
+            .filter(Column::Done.eq(false))
+            .filter(Column::DeletedAt.is_null())
+            .into_tuple::<i64>()
+            .all(&*self.database_connection)
+            .await?
+            .into_iter()
+            .collect();
+        for task in tasks {
+            task.blocker_ids = dependencies
+                .iter()
+                .filter(|dependency| dependency.task_id == task.id)
+                .map(|dependency| dependency.blocker_id)
+                .collect();
+            task.blocked = task.blocker_ids.iter().any(|blocker_id| open_blocker_ids.contains(blocker_id));
+        }
+        Ok(())
+    }
+}
+
+/// Assignees ordered by display name, so that they are listed the same way on every load.
+fn to_assignees(users: Vec<UserModel>) -> Vec<User> {
+    let mut assignees: Vec<User> = users.into_iter().map(Into::into).collect();
+    assignees.sort_by(|a, b| a.display_name.cmp(&b.display_name).then(a.id.cmp(&b.id)));
+    assignees
+}
+
+/// Titles are highlighted as a whole, descriptions cut down to the passages around the matches.
+/// Matches are delimited by private use characters, which become `<mark>` tags once the rest of the
+/// text is escaped.
+const TITLE_HEADLINE_OPTIONS: &str = "StartSel=\u{E000}, StopSel=\u{E001}, HighlightAll=true";
+const DESCRIPTION_HEADLINE_OPTIONS: &str =
+    "StartSel=\u{E000}, StopSel=\u{E001}, MaxFragments=2, MaxWords=20, MinWords=5, FragmentDelimiter=\" … \"";
+const MATCH_START: char = '\u{E000}';
+const MATCH_STOP: char = '\u{E001}';
+
+/// Escapes a headline for HTML and marks its matches, so that the task's own text cannot inject markup.
+fn highlight_html(headline: &str) -> String {
+    let mut html = String::with_capacity(headline.len());
+    for character in headline.chars() {
+        match character {
+            MATCH_START => html.push_str("<mark>"),
+            MATCH_STOP => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            character => html.push(character),
+        }
+    }
+    html
+}
+
+/// The text search configuration the `search_vector` column is built with.
+fn search_config() -> SimpleExpr {
+    Expr::cust("'english'::regconfig")
+}
+
+/// Title and description, weighted in that order; generated by the database.
+fn search_vector() -> SimpleExpr {
+    Expr::col((TaskEntity, Alias::new("search_vector"))).into()
+}
+
+/// Parses a search in web search syntax: `"exact phrase"`, `or` and `-excluded` words.
+fn search_query(search: &str) -> SimpleExpr {
+    Func::cust(Alias::new("websearch_to_tsquery"))
+        .args([search_config(), Expr::val(search).into()])
+        .into()
+}
+
+fn search_rank(search: &str) -> SimpleExpr {
+    Func::cust(Alias::new("ts_rank"))
+        .args([search_vector(), search_query(search)])
+        .into()
+}
+
+fn headline(column: Column, search: &str, options: &str) -> SimpleExpr {
+    Func::cust(Alias::new("ts_headline"))
+        .args([
+            search_config(),
+            Expr::col((TaskEntity, column)).into(),
+            search_query(search),
+            Expr::val(options).into(),
+        ])
+        .into()
+}
+
+/// Wraps the text in wildcards, matching any `%`, `_` and `\` in it literally.
+fn contains_pattern(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{escaped}%")
+}
+
+/// Selects the ids of tasks carrying any of the given tags.
+fn tagged_task_ids(tag_ids: Vec<i64>) -> SelectStatement {
+    Query::select()
+        .column(TaskTagColumn::TaskId)
+        .from(TaskTagEntity)
+        .and_where(TaskTagColumn::TagId.is_in(tag_ids))
+        .to_owned()
+}
+
+/// Selects the ids of tasks assigned to the given user, or to anyone.
+fn assigned_task_ids(user_id: Option<i64>) -> SelectStatement {
+    let mut query = Query::select()
+        .column(TaskAssigneeColumn::TaskId)
+        .from(TaskAssigneeEntity)
+        .to_owned();
+    if let Some(user_id) = user_id {
+        query.and_where(TaskAssigneeColumn::UserId.eq(user_id));
+    }
+    query
+}
+
+/// Selects the ids of tasks in the trash.
+fn trashed_task_ids() -> SelectStatement {
+    Query::select()
+        .column(Column::Id)
+        .from(TaskEntity)
+        .and_where(Column::DeletedAt.is_not_null())
+        .to_owned()
+}
+
+/// Selects the ids of the archived projects in the workspace of the task at hand.
+fn archived_project_ids() -> SelectStatement {
+    Query::select()
+        .column(ProjectColumn::Id)
+        .from(ProjectEntity)
+        .and_where(ProjectColumn::ArchivedAt.is_not_null())
+        .and_where(Expr::col((ProjectEntity, ProjectColumn::WorkspaceId)).equals((TaskEntity, Column::WorkspaceId)))
+        .to_owned()
+}
+
+/// Selects the ids of tasks waiting on at least one open blocker.
+/// Blockers in the trash no longer hold anything up.
+fn blocked_task_ids() -> SelectStatement {
+    Query::select()
+        .column((TaskDependencyEntity, TaskDependencyColumn::TaskId))
+        .from(TaskDependencyEntity)
+        .inner_join(
+            TaskEntity,
+            Expr::col((TaskEntity, Column::Id)).equals((TaskDependencyEntity, TaskDependencyColumn::BlockerId)),
+        )
+        .and_where(Expr::col((TaskEntity, Column::Done)).eq(false))
+        .and_where(Expr::col((TaskEntity, Column::DeletedAt)).is_null())
+        .to_owned()
+}
+
+/// Combines the filters into one condition; trashed tasks and tasks of archived projects
+/// are left out unless asked for.
+fn filter_condition(filters: Option<TaskFilterAndSortFiltersDto>) -> Condition {
+    let mut condition = Condition::all();
+    let mut deleted = Some(false);
+    let mut project_id = None;
+
+    if let Some(filters) = filters {
+        // `None` for trashed and other tasks alike
+        deleted = match filters.include_deleted {
+            Some(true) => None,
+            _ => Some(filters.deleted.unwrap_or(false)),
+        };
+        project_id = filters.project_id;
+
+        if let Some(done) = filters.done {
+            condition = condition.add(Column::Done.eq(done));
+        }
+
+        if let Some(title) = filters.title.as_deref() {
+            // case-insensitive substring match, served by the trigram index on the title
+            condition = condition.add(Expr::col((TaskEntity, Column::Title)).ilike(contains_pattern(title)));
+        }
+
+        if let Some(search) = filters.search_query() {
+            condition = condition.add(search_vector().matches(search_query(search)));
+        }
+
+        if let Some(created_after) = filters.created_after {
+            condition = condition.add(Column::CreatedAt.gt(created_after));
+        }
+
+        if let Some(created_before) = filters.created_before {
+            condition = condition.add(Column::CreatedAt.lt(created_before));
+        }
+
+        if let Some(updated_after) = filters.updated_after {
+            condition = condition.add(Column::UpdatedAt.gt(updated_after));
+        }
+
+        if let Some(updated_before) = filters.updated_before {
+            condition = condition.add(Column::UpdatedAt.lt(updated_before));
+        }
+
+        if let Some(due_after) = filters.due_after {
+            condition = condition.add(Column::DueAt.gt(due_after));
+        }
+
+        if let Some(due_before) = filters.due_before {
+            condition = condition.add(Column::DueAt.lt(due_before));
+        }
+
+        if let Some(priorities) = filters.priorities.filter(|priorities| !priorities.is_empty()) {
+            condition = condition.add(Column::Priority.is_in(
+                priorities
+                    .into_iter()
+                    .map(|priority| TaskPriority::from(PriorityMapper::from(priority))),
+            ));
+        }
+
+        if let Some(tag_ids) = filters.tags_any.filter(|tag_ids| !tag_ids.is_empty()) {
+            condition = condition.add(Column::Id.in_subquery(tagged_task_ids(tag_ids)));
+        }
+
+        if let Some(mut tag_ids) = filters.tags_all.filter(|tag_ids| !tag_ids.is_empty()) {
+            tag_ids.sort_unstable();
+            tag_ids.dedup();
+            let tag_count = tag_ids.len() as i64;
+            // a task matches when it carries every one of the distinct tags
+            condition = condition.add(Column::Id.in_subquery(
+                tagged_task_ids(tag_ids)
+                    .group_by_col(TaskTagColumn::TaskId)
+                    .and_having(Expr::expr(Func::count_distinct(Expr::col(TaskTagColumn::TagId))).eq(tag_count))
+                    .to_owned(),
+            ));
+        }
+
+        if let Some(tag_ids) = filters.tags_none.filter(|tag_ids| !tag_ids.is_empty()) {
+            condition = condition.add(Column::Id.not_in_subquery(tagged_task_ids(tag_ids)));
+        }
+
+        if let Some(assignee) = filters.assignee {
+            condition = condition.add(Column::Id.in_subquery(assigned_task_ids(Some(assignee))));
+        }
+
+        if let Some(unassigned) = filters.unassigned {
+            condition = condition.add(if unassigned {
+                Column::Id.not_in_subquery(assigned_task_ids(None))
+            } else {
+                Column::Id.in_subquery(assigned_task_ids(None))
+            });
+        }
+
+        if let Some(created_by) = filters.created_by {
+            condition = condition.add(Column::CreatedBy.eq(created_by));
+        }
+
+        if let Some(workspace_id) = filters.workspace_id {
+            condition = condition.add(Column::WorkspaceId.eq(workspace_id));
+        }
+
+        if let Some(workspace_ids) = filters.viewable_workspace_ids {
+            condition = condition.add(Column::WorkspaceId.is_in(workspace_ids));
+        }
+
+        if let Some(series_id) = filters.series_id {
+            // the first task of a series carries no series id itself
+            condition = condition.add(
+                Condition::any()
+                    .add(Column::Id.eq(series_id))
+                    .add(Column::SeriesId.eq(series_id)),
+            );
+        }
+
+        if let Some(blocked) = filters.blocked {
+            condition = condition.add(if blocked {
+                Column::Id.in_subquery(blocked_task_ids())
+            } else {
+                Column::Id.not_in_subquery(blocked_task_ids())
+            });
+        }
+
+        if let Some(top_level) = filters.top_level {
+            // subtasks of a trashed task stand on their own
+            let top_level_condition = Condition::any()
+                .add(Column::ParentId.is_null())
+                .add(Column::ParentId.in_subquery(trashed_task_ids()));
+            condition = condition.add(if top_level {
+                top_level_condition
+            } else {
+                top_level_condition.not()
+            });
+        }
+
+        if let Some(overdue) = filters.overdue {
+            // overdue means still open with a due date in the past
+            let now = Utc::now();
+            condition = condition.add(if overdue {
+                Condition::all()
+                    .add(Column::Done.eq(false))
+                    .add(Column::DueAt.lt(now))
+            } else {
+                Condition::any()
+                    .add(Column::Done.eq(true))
+                    .add(Column::DueAt.is_null())
+                    .add(Column::DueAt.gte(now))
+            });
+        }
+    }
+
+    // tasks of archived projects are hidden unless their project is requested
+    condition = condition.add(match project_id {
+        Some(project_id) => Condition::all().add(Column::ProjectId.eq(project_id)),
+        None => Condition::any()
+            .add(Column::ProjectId.is_null())
+            .add(Column::ProjectId.not_in_subquery(archived_project_ids())),
+    });
+
+    // trashed tasks are hidden unless explicitly requested
+    match deleted {
+        Some(true) => condition.add(Column::DeletedAt.is_not_null()),
+        Some(false) => condition.add(Column::DeletedAt.is_null()),
+        None => condition,
+    }
+}
+
+/// The full-text search among the filters, if any.
+fn search(filter_and_sort: &TaskFilterAndSortDto) -> Option<String> {
+    filter_and_sort.filters.as_ref()?.search_query().map(str::to_string)
+}
+
+/// The requested sort field and whether to sort ascending, falling back to ascending ids.
+fn sort_key(
+    sort: Option<&TaskFilterAndSortSortDto>,
+    search: Option<&str>,
+) -> (Option<TaskFilterAndSortSortFieldDto>, bool) {
+    match sort.and_then(|sort| sort.field.map(|field| (field, sort.direction))) {
+        Some((TaskFilterAndSortSortFieldDto::Relevance, _)) if search.is_none() => (None, true),
+        Some((field, direction)) => (Some(field), matches!(direction, Some(TaskFilterAndSortSortDirectionDto::Asc))),
+        None => (None, true),
+    }
+}
+
+/// Orders by the sort field and breaks ties by id, so that every task has a stable position.
+fn order_by_sort_key(
+    query: Select<TaskEntity>,
+    field: Option<TaskFilterAndSortSortFieldDto>,
+    ascending: bool,
+    search: Option<&str>,
+) -> Select<TaskEntity> {
+    let order = if ascending { Order::Asc } else { Order::Desc };
+    let query = match field {
+        Some(TaskFilterAndSortSortFieldDto::DueAt) => {
+            // tasks without a due date come last in both directions
+            query.order_by_with_nulls(Column::DueAt, order.clone(), NullOrdering::Last)
+        }
+        Some(field) => query.order_by(sort_expression(field, search), order.clone()),
+        None => query,
+    };
+    query.order_by(Column::Id, order)
+}
+
+/// The value tasks are ordered by; `sort_key` only sorts by relevance along with a search.
+pub(crate) fn sort_expression(field: TaskFilterAndSortSortFieldDto, search: Option<&str>) -> SimpleExpr {
+    let column = match field {
+        TaskFilterAndSortSortFieldDto::CreatedAt => Column::CreatedAt,
+        TaskFilterAndSortSortFieldDto::UpdatedAt => Column::UpdatedAt,
+        TaskFilterAndSortSortFieldDto::Title => Column::Title,
+        TaskFilterAndSortSortFieldDto::Done => Column::Done,
+        TaskFilterAndSortSortFieldDto::DueAt => Column::DueAt,
+        TaskFilterAndSortSortFieldDto::Priority => Column::Priority,
+        TaskFilterAndSortSortFieldDto::Relevance => return search_rank(search.unwrap_or_default()),
+    };
+    Expr::col((TaskEntity, column)).into()
 }
 
 #[async_trait]
-impl TaskRepositoryTrait for TaskRepository {
+impl<C: ConnectionTrait + TransactionTrait + Send + Sync + 'static> TaskRepositoryTrait for TaskRepository<C> {
     async fn save(&self, task: Task) -> anyhow::Result<Task> {
         let model: TaskModel = task.into();
         let mut active_model = model.clone().into_active_model();
@@ -28,93 +493,214 @@ impl TaskRepositoryTrait for TaskRepository {
             active_model.id = NotSet;
             active_model.created_at = NotSet;
             active_model.updated_at = NotSet;
-            Ok(active_model.insert(&*self.database_connection).await?.into())
+            active_model.version = NotSet;
+            let inserted = active_model.insert(&*self.database_connection).await?;
+            self.to_task(inserted).await
         } else {
-            active_model.updated_at.set_if_not_equals(Utc::now());
-            let active_model = active_model.reset_all();
-            Ok(active_model.update(&*self.database_connection).await?.into())
+            let mut active_model = active_model.reset_all();
+            active_model.id = NotSet;
+            active_model.created_at = NotSet;
+            active_model.version = NotSet;
+            active_model.updated_at = Set(Utc::now());
+            // nothing is written if someone else saved the task since it was loaded
+            let result = TaskEntity::update_many()
+                .set(active_model)
+                .col_expr(Column::Version, Expr::col(Column::Version).add(1))
+                .filter(Column::Id.eq(model.id))
+                .filter(Column::Version.eq(model.version))
+                .exec(&*self.database_connection)
+                .await?;
+            if result.rows_affected == 0 {
+                return Err(TaskError::VersionMismatch(model.id).into());
+            }
+            let updated = TaskEntity::find_by_id(model.id)
+                .one(&*self.database_connection)
+                .await?
+                .ok_or(TaskError::VersionMismatch(model.id))?;
+            self.to_task(updated).await
         }
     }
 
     async fn find_tasks(&self, filter_and_sort: TaskFilterAndSortDto) -> anyhow::Result<Vec<Task>> {
-        let mut query = TaskEntity::find();
+        let search = search(&filter_and_sort);
+        let (field, ascending) = sort_key(filter_and_sort.sort.as_ref(), search.as_deref());
+        let query = TaskEntity::find().filter(filter_condition(filter_and_sort.filters));
+        let tasks = order_by_sort_key(query, field, ascending, search.as_deref())
+            .all(&*self.database_connection)
+            .await?;
+        self.to_tasks(tasks).await
+    }
 
-        if let Some(filters) = filter_and_sort.filters {
-            let mut condition = Condition::all();
+    async fn find_task_page(&self, filter_and_sort: TaskFilterAndSortDto) -> anyhow::Result<TaskPageDto> {
+        let page_size = filter_and_sort.page_size();
+        let search = search(&filter_and_sort);
+        let (field, ascending) = sort_key(filter_and_sort.sort.as_ref(), search.as_deref());
+        let condition = filter_condition(filter_and_sort.filters);
+        let total_estimate = TaskEntity::find()
+            .filter(condition.clone())
+            .count(&*self.database_connection)
+            .await?;
 
-            if let Some(done) = filters.done {
-                condition = condition.add(Column::Done.eq(done));
-            }
+        let mut query = TaskEntity::find().filter(condition);
+        if let Some(cursor) = filter_and_sort.cursor {
+            let cursor = TaskCursor::decode(&cursor, field).ok_or(TaskError::InvalidCursor)?;
+            query = query.filter(cursor.after(ascending, search.as_deref()));
+        }
+        // one extra row tells whether another page follows
+        let mut tasks = order_by_sort_key(query, field, ascending, search.as_deref())
+            .limit(page_size + 1)
+            .all(&*self.database_connection)
+            .await?;
+        let has_next_page = tasks.len() as u64 > page_size;
+        tasks.truncate(page_size as usize);
 
-            if let Some(title) = filters.title {
-                // case-insensitive substring match
-                condition = condition.add(Column::Title.contains(title));
+        let mut matches = match search.as_deref() {
+            Some(search) => {
+                self.search_matches(tasks.iter().map(|task| task.id).collect(), search)
+                    .await?
             }
+            None => HashMap::new(),
+        };
+        let next_cursor = tasks.last().filter(|_| has_next_page).map(|last| {
+            let rank = matches.get(&last.id).map(|(rank, _)| *rank);
+            TaskCursor::from_model(last, field, rank).encode()
+        });
+        let highlights = tasks
+            .iter()
+            .filter_map(|task| matches.remove(&task.id).map(|(_, highlight)| highlight))
+            .collect();
 
-            if let Some(created_after) = filters.created_after {
-                condition = condition.add(Column::CreatedAt.gt(created_after));
-            }
+        Ok(TaskPageDto {
+            items: self.to_tasks(tasks).await?,
+            next_cursor,
+            total_estimate,
+            highlights,
+        })
+    }
 
-            if let Some(created_before) = filters.created_before {
-                condition = condition.add(Column::CreatedAt.lt(created_before));
-            }
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Task>> {
+        match TaskEntity::find_by_id(id).one(&*self.database_connection).await? {
+            Some(model) => Ok(Some(self.to_task(model).await?)),
+            None => Ok(None),
+        }
+    }
 
-            if let Some(updated_after) = filters.updated_after {
-                condition = condition.add(Column::UpdatedAt.gt(updated_after));
-            }
+    async fn find_children(&self, parent_id: i64) -> anyhow::Result<Vec<Task>> {
+        let children = TaskEntity::find()
+            .filter(Column::ParentId.eq(parent_id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::CreatedAt)
+            .all(&*self.database_connection)
+            .await?;
+        self.to_tasks(children).await
+    }
 
-            if let Some(updated_before) = filters.updated_before {
-                condition = condition.add(Column::UpdatedAt.lt(updated_before));
-            }
+    async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool> {
+        let result = TaskEntity::delete_by_id(id)
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
 
-            query = query.filter(condition);
-        }
-
-        if let Some(sort) = filter_and_sort.sort {
-            if let Some(field) = sort.field {
-                let ascending = matches!(sort.direction, Some(TaskFilterAndSortSortDirectionDto::Asc));
-
-                query = match field {
-                    TaskFilterAndSortSortFieldDto::CreatedAt => {
-                        if ascending {
-                            query.order_by_asc(Column::CreatedAt)
-                        } else {
-                            query.order_by_desc(Column::CreatedAt)
-                        }
-                    }
-                    TaskFilterAndSortSortFieldDto::UpdatedAt => {
-                        if ascending {
-                            query.order_by_asc(Column::UpdatedAt)
-                        } else {
-                            query.order_by_desc(Column::UpdatedAt)
-                        }
-                    }
-                    TaskFilterAndSortSortFieldDto::Title => {
-                        if ascending {
-                            query.order_by_asc(Column::Title)
-                        } else {
-                            query.order_by_desc(Column::Title)
-                        }
-                    }
-                    TaskFilterAndSortSortFieldDto::Done => {
-                        if ascending {
-                            query.order_by_asc(Column::Done)
-                        } else {
-                            query.order_by_desc(Column::Done)
-                        }
-                    }
-                };
-            }
-        }
+    async fn add_tag(&self, task_id: i64, tag_id: i64) -> anyhow::Result<()> {
+        let link = TaskTagActiveModel {
+            task_id: Set(task_id),
+            tag_id: Set(tag_id),
+        };
+        TaskTagEntity::insert(link)
+            .on_conflict(
+                OnConflict::columns([TaskTagColumn::TaskId, TaskTagColumn::TagId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&*self.database_connection)
+            .await?;
+        Ok(())
+    }
 
-        let tasks = query.all(&*self.database_connection).await?;
-        Ok(tasks.into_iter().map(Into::into).collect())
+    async fn remove_tag(&self, task_id: i64, tag_id: i64) -> anyhow::Result<()> {
+        TaskTagEntity::delete_by_id((task_id, tag_id))
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(())
     }
 
-    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Task>> {
-        Ok(TaskEntity::find_by_id(id)
-            .one(&*self.database_connection)
-            .await?
-            .map(Into::into))
+    async fn add_assignee(&self, task_id: i64, user_id: i64) -> anyhow::Result<()> {
+        let assignment = TaskAssigneeActiveModel {
+            task_id: Set(task_id),
+            user_id: Set(user_id),
+        };
+        TaskAssigneeEntity::insert(assignment)
+            .on_conflict(
+                OnConflict::columns([TaskAssigneeColumn::TaskId, TaskAssigneeColumn::UserId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&*self.database_connection)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_assignee(&self, task_id: i64, user_id: i64) -> anyhow::Result<()> {
+        TaskAssigneeEntity::delete_by_id((task_id, user_id))
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(())
+    }
+
+    async fn find_dependencies(&self) -> anyhow::Result<Vec<(i64, i64)>> {
+        let dependencies = TaskDependencyEntity::find()
+            .all(&*self.database_connection)
+            .await?;
+        Ok(dependencies
+            .into_iter()
+            .map(|dependency| (dependency.task_id, dependency.blocker_id))
+            .collect())
+    }
+
+    async fn lock_dependencies(&self) -> anyhow::Result<()> {
+        // the mode conflicts with itself, so lockers take turns while readers carry on
+        self.database_connection
+            .execute_unprepared("LOCK TABLE task_dependencies IN SHARE ROW EXCLUSIVE MODE")
+            .await?;
+        Ok(())
+    }
+
+    async fn add_blocker(&self, task_id: i64, blocker_id: i64) -> anyhow::Result<()> {
+        let dependency = TaskDependencyActiveModel {
+            task_id: Set(task_id),
+            blocker_id: Set(blocker_id),
+        };
+        TaskDependencyEntity::insert(dependency)
+            .on_conflict(
+                OnConflict::columns([TaskDependencyColumn::TaskId, TaskDependencyColumn::BlockerId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&*self.database_connection)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_blocker(&self, task_id: i64, blocker_id: i64) -> anyhow::Result<()> {
+        TaskDependencyEntity::delete_by_id((task_id, blocker_id))
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(())
+    }
+
+    async fn transaction(&self, work: TransactionWork) -> anyhow::Result<()> {
+        let transaction = Arc::new(self.database_connection.begin().await?);
+        let result = work(Arc::new(TaskRepository::new(transaction.clone()))).await;
+        // the work has dropped its repository by now, leaving the only reference here
+        let transaction = Arc::try_unwrap(transaction)
+            .map_err(|_| anyhow::anyhow!("transaction is still in use after its work finished"))?;
+        match result {
+            Ok(()) => Ok(transaction.commit().await?),
+            Err(error) => {
+                transaction.rollback().await?;
+                Err(error)
+            }
+        }
     }
 }
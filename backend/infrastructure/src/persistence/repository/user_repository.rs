@@ -0,0 +1,118 @@
+use crate::persistence::entity::refresh_token::{
+    Column as RefreshTokenColumn, Entity as RefreshTokenEntity, Model as RefreshTokenModel,
+};
+use crate::persistence::entity::user::{Column, Entity as UserEntity, Model as UserModel};
+use crate::persistence::entity::user_identity::{
+    Column as UserIdentityColumn, Entity as UserIdentityEntity, Model as UserIdentityModel,
+};
+use application::auth::user_repository_trait::UserRepositoryTrait;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::user::{RefreshToken, User, UserIdentity};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, NotSet, QueryFilter};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct UserRepository {
+    database_connection: Arc<DatabaseConnection>,
+}
+
+impl UserRepository {
+    pub fn new(database_connection: Arc<DatabaseConnection>) -> Self {
+        Self { database_connection }
+    }
+}
+
+#[async_trait]
+impl UserRepositoryTrait for UserRepository {
+    async fn save(&self, user: User) -> anyhow::Result<User> {
+        let model: UserModel = user.into();
+        let mut active_model = model.clone().into_active_model();
+        if model.id == 0 {
+            active_model.id = NotSet;
+            active_model.created_at = NotSet;
+            active_model.updated_at = NotSet;
+            Ok(active_model.insert(&*self.database_connection).await?.into())
+        } else {
+            active_model.updated_at.set_if_not_equals(Utc::now());
+            let active_model = active_model.reset_all();
+            Ok(active_model.update(&*self.database_connection).await?.into())
+        }
+    }
+
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<User>> {
+        Ok(UserEntity::find_by_id(id)
+            .one(&*self.database_connection)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn find_by_email(&self, email: &str) -> anyhow::Result<Option<User>> {
+        Ok(UserEntity::find()
+            .filter(Column::Email.eq(email))
+            .one(&*self.database_connection)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn find_by_identity(&self, issuer: &str, subject: &str) -> anyhow::Result<Option<User>> {
+        Ok(UserEntity::find()
+            .inner_join(UserIdentityEntity)
+            .filter(UserIdentityColumn::Issuer.eq(issuer))
+            .filter(UserIdentityColumn::Subject.eq(subject))
+            .one(&*self.database_connection)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn save_identity(&self, identity: UserIdentity) -> anyhow::Result<UserIdentity> {
+        let model: UserIdentityModel = identity.into();
+        let mut active_model = model.into_active_model();
+        active_model.id = NotSet;
+        active_model.created_at = NotSet;
+        Ok(active_model.insert(&*self.database_connection).await?.into())
+    }
+
+    async fn save_refresh_token(&self, refresh_token: RefreshToken) -> anyhow::Result<RefreshToken> {
+        let model: RefreshTokenModel = refresh_token.into();
+        let mut active_model = model.clone().into_active_model();
+        if model.id == 0 {
+            active_model.id = NotSet;
+            active_model.created_at = NotSet;
+            Ok(active_model.insert(&*self.database_connection).await?.into())
+        } else {
+            let active_model = active_model.reset_all();
+            Ok(active_model.update(&*self.database_connection).await?.into())
+        }
+    }
+
+    async fn find_refresh_token(&self, token_hash: &str) -> anyhow::Result<Option<RefreshToken>> {
+        Ok(RefreshTokenEntity::find()
+            .filter(RefreshTokenColumn::TokenHash.eq(token_hash))
+            .one(&*self.database_connection)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn revoke_refresh_token(&self, id: i64, now: DateTime<Utc>) -> anyhow::Result<bool> {
+        // only one of several concurrent refreshes with the same token gets to revoke it
+        let result = RefreshTokenEntity::update_many()
+            .col_expr(RefreshTokenColumn::RevokedAt, Expr::value(now))
+            .filter(RefreshTokenColumn::Id.eq(id))
+            .filter(RefreshTokenColumn::RevokedAt.is_null())
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn revoke_refresh_token_family(&self, family: &str, now: DateTime<Utc>) -> anyhow::Result<()> {
+        RefreshTokenEntity::update_many()
+            .col_expr(RefreshTokenColumn::RevokedAt, Expr::value(now))
+            .filter(RefreshTokenColumn::Family.eq(family))
+            .filter(RefreshTokenColumn::RevokedAt.is_null())
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use crate::persistence::entity::webhook::{Column, Entity as WebhookEntity, Model as WebhookModel};
+use crate::persistence::entity::webhook_delivery::{
+    Column as DeliveryColumn, DeliveryStatus, Entity as WebhookDeliveryEntity, Model as WebhookDeliveryModel,
+};
+use application::webhook::webhook_repository_trait::WebhookRepositoryTrait;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::webhook::{Webhook, WebhookDelivery};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, NotSet, QueryFilter};
+use sea_orm::{QueryOrder, QuerySelect};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct WebhookRepository {
+    database_connection: Arc<DatabaseConnection>,
+}
+
+impl WebhookRepository {
+    pub fn new(database_connection: Arc<DatabaseConnection>) -> Self {
+        Self { database_connection }
+    }
+}
+
+#[async_trait]
+impl WebhookRepositoryTrait for WebhookRepository {
+    async fn save(&self, webhook: Webhook) -> anyhow::Result<Webhook> {
+        let model: WebhookModel = webhook.into();
+        let mut active_model = model.clone().into_active_model();
+        if model.id == 0 {
+            active_model.id = NotSet;
+            active_model.created_at = NotSet;
+            active_model.updated_at = NotSet;
+            Ok(active_model.insert(&*self.database_connection).await?.into())
+        } else {
+            active_model.updated_at.set_if_not_equals(Utc::now());
+            let active_model = active_model.reset_all();
+            Ok(active_model.update(&*self.database_connection).await?.into())
+        }
+    }
+
+    async fn find_by_workspace(&self, workspace_id: i64) -> anyhow::Result<Vec<Webhook>> {
+        let webhooks = WebhookEntity::find()
+            .filter(Column::WorkspaceId.eq(workspace_id))
+            .order_by_asc(Column::Id)
+            .all(&*self.database_connection)
+            .await?;
+        Ok(webhooks.into_iter().map(Into::into).collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Webhook>> {
+        Ok(WebhookEntity::find_by_id(id)
+            .one(&*self.database_connection)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool> {
+        let result = WebhookEntity::delete_by_id(id)
+            .exec(&*self.database_connection)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn save_delivery(&self, delivery: WebhookDelivery) -> anyhow::Result<WebhookDelivery> {
+        let model: WebhookDeliveryModel = delivery.into();
+        let mut active_model = model.clone().into_active_model();
+        if model.id == 0 {
+            active_model.id = NotSet;
+            active_model.created_at = NotSet;
+            Ok(active_model.insert(&*self.database_connection).await?.into())
+        } else {
+            let active_model = active_model.reset_all();
+            Ok(active_model.update(&*self.database_connection).await?.into())
+        }
+    }
+
+    async fn find_delivery(&self, id: i64) -> anyhow::Result<Option<WebhookDelivery>> {
+        Ok(WebhookDeliveryEntity::find_by_id(id)
+            .one(&*self.database_connection)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn find_deliveries(&self, webhook_id: i64, limit: u64) -> anyhow::Result<Vec<WebhookDelivery>> {
+        let deliveries = WebhookDeliveryEntity::find()
+            .filter(DeliveryColumn::WebhookId.eq(webhook_id))
+            .order_by_desc(DeliveryColumn::Id)
+            .limit(limit)
+            .all(&*self.database_connection)
+            .await?;
+        Ok(deliveries.into_iter().map(Into::into).collect())
+    }
+
+    async fn find_due_deliveries(&self, now: DateTime<Utc>, limit: u64) -> anyhow::Result<Vec<WebhookDelivery>> {
+        let deliveries = WebhookDeliveryEntity::find()
+            .filter(DeliveryColumn::Status.eq(DeliveryStatus::Pending))
+            .filter(DeliveryColumn::NextAttemptAt.lte(now))
+            .order_by_asc(DeliveryColumn::NextAttemptAt)
+            .order_by_asc(DeliveryColumn::Id)
+            .limit(limit)
+            .all(&*self.database_connection)
+            .await?;
+        Ok(deliveries.into_iter().map(Into::into).collect())
+    }
+}
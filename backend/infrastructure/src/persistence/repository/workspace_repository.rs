@@ -0,0 +1,147 @@
+use crate::persistence::entity::workspace::{Entity as WorkspaceEntity, Model as WorkspaceModel};
+use crate::persistence::entity::workspace_member::{
+    ActiveModel as WorkspaceMemberActiveModel, Column as WorkspaceMemberColumn, Entity as WorkspaceMemberEntity,
+    MemberRole, Model as WorkspaceMemberModel,
+};
+use application::workspace::workspace_dto::WorkspaceMembershipDto;
+use application::workspace::workspace_error::WorkspaceError;
+use application::workspace::workspace_policy::removes_last_owner;
+use application::workspace::workspace_repository_trait::WorkspaceRepositoryTrait;
+use async_trait::async_trait;
+use domain::workspace::{Workspace, WorkspaceMember, WorkspaceRole};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, IntoActiveModel, NotSet,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct WorkspaceRepository {
+    database_connection: Arc<DatabaseConnection>,
+}
+
+impl WorkspaceRepository {
+    pub fn new(database_connection: Arc<DatabaseConnection>) -> Self {
+        Self { database_connection }
+    }
+
+    /// Locks the memberships of the workspace until the transaction ends and refuses to change the
+    /// role of `user_id` to `new_role`, `None` for removal, if that would leave no owner. Concurrent
+    /// changes wait for the lock, so two owners cannot demote each other at the same time.
+    async fn ensure_owner_remains(
+        transaction: &DatabaseTransaction,
+        workspace_id: i64,
+        user_id: i64,
+        new_role: Option<WorkspaceRole>,
+    ) -> anyhow::Result<Option<WorkspaceRole>> {
+        let members: Vec<WorkspaceMember> = WorkspaceMemberEntity::find()
+            .filter(WorkspaceMemberColumn::WorkspaceId.eq(workspace_id))
+            .lock_exclusive()
+            .all(transaction)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        let current_role = members.iter().find(|member| member.user_id == user_id).map(|member| member.role);
+        let owner_count = members.iter().filter(|member| member.role == WorkspaceRole::Owner).count();
+        if removes_last_owner(current_role, new_role, owner_count) {
+            return Err(WorkspaceError::LastOwner.into());
+        }
+        Ok(current_role)
+    }
+}
+
+#[async_trait]
+impl WorkspaceRepositoryTrait for WorkspaceRepository {
+    async fn create(&self, workspace: Workspace, owner_id: i64) -> anyhow::Result<Workspace> {
+        let model: WorkspaceModel = workspace.into();
+        let mut active_model = model.into_active_model();
+        active_model.id = NotSet;
+        active_model.created_at = NotSet;
+        active_model.updated_at = NotSet;
+        // a workspace without an owner could never be managed
+        let transaction = self.database_connection.begin().await?;
+        let workspace = active_model.insert(&transaction).await?;
+        let owner = WorkspaceMemberActiveModel {
+            workspace_id: Set(workspace.id),
+            user_id: Set(owner_id),
+            role: Set(MemberRole::Owner),
+            created_at: NotSet,
+        };
+        owner.insert(&transaction).await?;
+        transaction.commit().await?;
+        Ok(workspace.into())
+    }
+
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Workspace>> {
+        Ok(WorkspaceEntity::find_by_id(id)
+            .one(&*self.database_connection)
+            .await?
+            .map(Into::into))
+    }
+
+    async fn find_memberships(&self, user_id: i64) -> anyhow::Result<Vec<WorkspaceMembershipDto>> {
+        Ok(WorkspaceMemberEntity::find()
+            .filter(WorkspaceMemberColumn::UserId.eq(user_id))
+            .find_also_related(WorkspaceEntity)
+            .order_by_asc(WorkspaceMemberColumn::CreatedAt)
+            .order_by_asc(WorkspaceMemberColumn::WorkspaceId)
+            .all(&*self.database_connection)
+            .await?
+            .into_iter()
+            .filter_map(|(member, workspace)| {
+                let member: WorkspaceMember = member.into();
+                Some(WorkspaceMembershipDto { workspace: workspace?.into(), role: member.role })
+            })
+            .collect())
+    }
+
+    async fn find_role(&self, workspace_id: i64, user_id: i64) -> anyhow::Result<Option<WorkspaceRole>> {
+        Ok(WorkspaceMemberEntity::find_by_id((workspace_id, user_id))
+            .one(&*self.database_connection)
+            .await?
+            .map(|member| WorkspaceMember::from(member).role))
+    }
+
+    async fn find_members(&self, workspace_id: i64) -> anyhow::Result<Vec<WorkspaceMember>> {
+        Ok(WorkspaceMemberEntity::find()
+            .filter(WorkspaceMemberColumn::WorkspaceId.eq(workspace_id))
+            .order_by_asc(WorkspaceMemberColumn::CreatedAt)
+            .order_by_asc(WorkspaceMemberColumn::UserId)
+            .all(&*self.database_connection)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn save_member(&self, member: WorkspaceMember) -> anyhow::Result<WorkspaceMember> {
+        let transaction = self.database_connection.begin().await?;
+        Self::ensure_owner_remains(&transaction, member.workspace_id, member.user_id, Some(member.role)).await?;
+        let model: WorkspaceMemberModel = member.into();
+        let mut active_model = model.into_active_model();
+        active_model.created_at = NotSet;
+        // existing members keep the date they joined, only their role changes
+        let member = WorkspaceMemberEntity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([WorkspaceMemberColumn::WorkspaceId, WorkspaceMemberColumn::UserId])
+                    .update_column(WorkspaceMemberColumn::Role)
+                    .to_owned(),
+            )
+            .exec_with_returning(&transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(member.into())
+    }
+
+    async fn delete_member(&self, workspace_id: i64, user_id: i64) -> anyhow::Result<bool> {
+        let transaction = self.database_connection.begin().await?;
+        if Self::ensure_owner_remains(&transaction, workspace_id, user_id, None).await?.is_none() {
+            return Ok(false);
+        }
+        WorkspaceMemberEntity::delete_by_id((workspace_id, user_id)).exec(&transaction).await?;
+        transaction.commit().await?;
+        Ok(true)
+    }
+}
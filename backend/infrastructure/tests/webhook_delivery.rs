@@ -0,0 +1,614 @@
+//! Webhook deliveries against a local stand-in receiver, with the webhooks kept in memory.
+
+use actix_web::dev::Service;
+use actix_web::{test, web, App, HttpMessage, HttpRequest, HttpResponse, HttpServer};
+use application::auth::principal::Principal;
+use application::task::task_event_dto::TaskEventDto;
+use application::task::task_event_publisher_trait::TaskEventPublisherTrait;
+use application::webhook::webhook_dto::NewWebhookDto;
+use application::webhook::webhook_error::WebhookError;
+use application::webhook::webhook_repository_trait::WebhookRepositoryTrait;
+use application::webhook::webhook_sender_trait::WebhookSenderTrait;
+use application::webhook::webhook_service::{RetryPolicy, WebhookService};
+use application::webhook::webhook_service_trait::WebhookServiceTrait;
+use application::workspace::workspace_dto::WorkspaceMembershipDto;
+use application::workspace::workspace_policy::WorkspaceAction;
+use application::workspace::workspace_repository_trait::WorkspaceRepositoryTrait;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use domain::api_token::TokenScope;
+use domain::webhook::{DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent};
+use domain::workspace::{Workspace, WorkspaceMember, WorkspaceRole};
+use hmac::{Hmac, Mac};
+use infrastructure::api::webhook_controller::WebhookController;
+use infrastructure::event::http_webhook_sender::HttpWebhookSender;
+use infrastructure::event::webhook_delivery_worker::WebhookDeliveryWorker;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::net::{IpAddr, Ipv4Addr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const SECRET: &str = "s3cret";
+const WORKSPACE_ID: i64 = 1;
+const OTHER_WORKSPACE_ID: i64 = 2;
+const ADMIN_ID: i64 = 1;
+const VIEWER_ID: i64 = 2;
+const OUTSIDER_ID: i64 = 3;
+/// Signs test requests in as this user instead of the admin.
+const USER_HEADER: &str = "X-Test-User";
+
+/// Workspace, user and role of every membership.
+const MEMBERSHIPS: [(i64, i64, WorkspaceRole); 3] = [
+    (WORKSPACE_ID, ADMIN_ID, WorkspaceRole::Admin),
+    (WORKSPACE_ID, VIEWER_ID, WorkspaceRole::Viewer),
+    (OTHER_WORKSPACE_ID, OUTSIDER_ID, WorkspaceRole::Owner),
+];
+
+/// The `MEMBERSHIPS`, which are all webhooks look at.
+struct FixedWorkspaceRepository;
+
+#[async_trait]
+impl WorkspaceRepositoryTrait for FixedWorkspaceRepository {
+    async fn create(&self, _: Workspace, _: i64) -> anyhow::Result<Workspace> {
+        unimplemented!("workspaces are fixed")
+    }
+
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Workspace>> {
+        let exists = MEMBERSHIPS.iter().any(|(workspace_id, _, _)| *workspace_id == id);
+        Ok(exists.then(|| Workspace { id, ..Default::default() }))
+    }
+
+    async fn find_memberships(&self, user_id: i64) -> anyhow::Result<Vec<WorkspaceMembershipDto>> {
+        Ok(MEMBERSHIPS
+            .iter()
+            .filter(|(_, member_id, _)| *member_id == user_id)
+            .map(|(workspace_id, _, role)| WorkspaceMembershipDto {
+                workspace: Workspace { id: *workspace_id, ..Default::default() },
+                role: *role,
+            })
+            .collect())
+    }
+
+    async fn find_role(&self, workspace_id: i64, user_id: i64) -> anyhow::Result<Option<WorkspaceRole>> {
+        Ok(MEMBERSHIPS
+            .iter()
+            .find(|(id, member_id, _)| *id == workspace_id && *member_id == user_id)
+            .map(|(_, _, role)| *role))
+    }
+
+    async fn find_members(&self, _: i64) -> anyhow::Result<Vec<WorkspaceMember>> {
+        unimplemented!("workspaces are fixed")
+    }
+
+    async fn save_member(&self, _: WorkspaceMember) -> anyhow::Result<WorkspaceMember> {
+        unimplemented!("workspaces are fixed")
+    }
+
+    async fn delete_member(&self, _: i64, _: i64) -> anyhow::Result<bool> {
+        unimplemented!("workspaces are fixed")
+    }
+}
+
+#[derive(Default)]
+struct InMemoryWebhookRepository {
+    state: Mutex<(Vec<Webhook>, Vec<WebhookDelivery>, i64)>,
+}
+
+#[async_trait]
+impl WebhookRepositoryTrait for InMemoryWebhookRepository {
+    async fn save(&self, mut webhook: Webhook) -> anyhow::Result<Webhook> {
+        let (webhooks, _, next_id) = &mut *self.state.lock().unwrap();
+        if webhook.id == 0 {
+            *next_id += 1;
+            webhook.id = *next_id;
+            webhook.created_at = Utc::now();
+        }
+        webhook.updated_at = Utc::now();
+        webhooks.retain(|existing| existing.id != webhook.id);
+        webhooks.push(webhook.clone());
+        Ok(webhook)
+    }
+
+    async fn find_by_workspace(&self, workspace_id: i64) -> anyhow::Result<Vec<Webhook>> {
+        let webhooks = &self.state.lock().unwrap().0;
+        Ok(webhooks.iter().filter(|webhook| webhook.workspace_id == workspace_id).cloned().collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> anyhow::Result<Option<Webhook>> {
+        Ok(self.state.lock().unwrap().0.iter().find(|webhook| webhook.id == id).cloned())
+    }
+
+    async fn delete_by_id(&self, id: i64) -> anyhow::Result<bool> {
+        let (webhooks, deliveries, _) = &mut *self.state.lock().unwrap();
+        let count = webhooks.len();
+        webhooks.retain(|webhook| webhook.id != id);
+        deliveries.retain(|delivery| delivery.webhook_id != id);
+        Ok(webhooks.len() < count)
+    }
+
+    async fn save_delivery(&self, mut delivery: WebhookDelivery) -> anyhow::Result<WebhookDelivery> {
+        let (_, deliveries, next_id) = &mut *self.state.lock().unwrap();
+        if delivery.id == 0 {
+            *next_id += 1;
+            delivery.id = *next_id;
+            delivery.created_at = Utc::now();
+        }
+        deliveries.retain(|existing| existing.id != delivery.id);
+        deliveries.push(delivery.clone());
+        Ok(delivery)
+    }
+
+    async fn find_delivery(&self, id: i64) -> anyhow::Result<Option<WebhookDelivery>> {
+        Ok(self.state.lock().unwrap().1.iter().find(|delivery| delivery.id == id).cloned())
+    }
+
+    async fn find_deliveries(&self, webhook_id: i64, limit: u64) -> anyhow::Result<Vec<WebhookDelivery>> {
+        let mut deliveries: Vec<WebhookDelivery> = self
+            .state
+            .lock()
+            .unwrap()
+            .1
+            .iter()
+            .filter(|delivery| delivery.webhook_id == webhook_id)
+            .cloned()
+            .collect();
+        deliveries.sort_by_key(|delivery| std::cmp::Reverse(delivery.id));
+        deliveries.truncate(limit as usize);
+        Ok(deliveries)
+    }
+
+    async fn find_due_deliveries(&self, now: DateTime<Utc>, limit: u64) -> anyhow::Result<Vec<WebhookDelivery>> {
+        let mut deliveries: Vec<WebhookDelivery> = self
+            .state
+            .lock()
+            .unwrap()
+            .1
+            .iter()
+            .filter(|delivery| {
+                delivery.status == DeliveryStatus::Pending && delivery.next_attempt_at.is_some_and(|due| due <= now)
+            })
+            .cloned()
+            .collect();
+        deliveries.sort_by_key(|delivery| (delivery.next_attempt_at, delivery.id));
+        deliveries.truncate(limit as usize);
+        Ok(deliveries)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ReceivedRequest {
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl ReceivedRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Default)]
+struct StandInState {
+    received: Mutex<Vec<ReceivedRequest>>,
+    /// Statuses to answer with, in order; 200 once they are used up.
+    responses: Mutex<VecDeque<u16>>,
+}
+
+/// A webhook receiver listening on a random local port.
+struct StandInReceiver {
+    url: String,
+    state: web::Data<StandInState>,
+}
+
+impl StandInReceiver {
+    async fn start(responses: &[u16]) -> StandInReceiver {
+        let state = web::Data::new(StandInState {
+            received: Mutex::default(),
+            responses: Mutex::new(responses.iter().copied().collect()),
+        });
+        let app_state = state.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(app_state.clone())
+                .route("/hook", web::post().to(receive))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let address = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+        StandInReceiver { url: format!("http://{address}/hook"), state }
+    }
+
+    fn received(&self) -> Vec<ReceivedRequest> {
+        self.state.received.lock().unwrap().clone()
+    }
+
+    async fn wait_for_requests(&self, count: usize) -> Vec<ReceivedRequest> {
+        for _ in 0..100 {
+            let received = self.received();
+            if received.len() >= count {
+                return received;
+            }
+            actix_web::rt::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("the receiver got {} of {count} requests", self.received().len());
+    }
+}
+
+async fn receive(request: HttpRequest, body: web::Bytes, state: web::Data<StandInState>) -> HttpResponse {
+    let headers = request
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or_default().to_string()))
+        .collect();
+    let body = String::from_utf8_lossy(&body).into_owned();
+    state.received.lock().unwrap().push(ReceivedRequest { headers, body });
+    let status = state.responses.lock().unwrap().pop_front().unwrap_or(200);
+    HttpResponse::build(status.try_into().unwrap()).finish()
+}
+
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_secs(60),
+        max_backoff: Duration::from_secs(60 * 60),
+    }
+}
+
+/// Passes the stand-in receivers on loopback addresses off as public ones, which webhooks may only
+/// be delivered to.
+struct LoopbackAsPublicSender(HttpWebhookSender);
+
+#[async_trait]
+impl WebhookSenderTrait for LoopbackAsPublicSender {
+    async fn send(&self, webhook: &Webhook, delivery: &WebhookDelivery) -> anyhow::Result<u16> {
+        self.0.send(webhook, delivery).await
+    }
+
+    async fn resolve(&self, url: &str) -> anyhow::Result<Vec<IpAddr>> {
+        let addresses = self.0.resolve(url).await?;
+        let public = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 10));
+        Ok(addresses.into_iter().map(|address| if address.is_loopback() { public } else { address }).collect())
+    }
+}
+
+fn webhook_service(repository: Arc<InMemoryWebhookRepository>) -> Arc<dyn WebhookServiceTrait> {
+    let sender = Arc::new(LoopbackAsPublicSender(HttpWebhookSender::new(Duration::from_secs(5)).unwrap()));
+    Arc::new(WebhookService::new(repository, Arc::new(FixedWorkspaceRepository), sender, retry_policy()))
+}
+
+fn new_webhook(url: &str, events: Vec<WebhookEvent>) -> NewWebhookDto {
+    NewWebhookDto { workspace_id: WORKSPACE_ID, url: url.to_string(), events, secret: SECRET.to_string(), active: true }
+}
+
+async fn create_webhook(service: &Arc<dyn WebhookServiceTrait>, url: &str, events: Vec<WebhookEvent>) -> Webhook {
+    service.create_webhook(ADMIN_ID, new_webhook(url, events)).await.unwrap()
+}
+
+/// Stands in for the authentication middleware, signing requests in as the admin or as the user
+/// in the `USER_HEADER`.
+fn principal(request: &actix_web::dev::ServiceRequest) -> Principal {
+    let user_id = request
+        .headers()
+        .get(USER_HEADER)
+        .and_then(|value| value.to_str().ok()?.parse().ok())
+        .unwrap_or(ADMIN_ID);
+    Principal { user_id, email: String::new(), display_name: String::new(), scope: TokenScope::ReadWrite }
+}
+
+fn expected_signature(body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[actix_web::test]
+async fn delivers_signed_payloads_to_subscribed_webhooks() {
+    let receiver = StandInReceiver::start(&[]).await;
+    let service = webhook_service(Arc::default());
+    let webhook = create_webhook(&service, &receiver.url, vec![WebhookEvent::TaskDeleted]).await;
+    let now = Utc::now();
+
+    let payload = r#"{"event":"taskCreated"}"#.to_string();
+    let queued = service.enqueue(WebhookEvent::TaskCreated, WORKSPACE_ID, payload, now).await.unwrap();
+    assert!(queued.is_empty(), "the webhook is not subscribed to created tasks");
+    let payload = r#"{"event":"taskDeleted"}"#.to_string();
+    let queued = service.enqueue(WebhookEvent::TaskDeleted, WORKSPACE_ID, payload, now).await.unwrap();
+    let delivered = service.deliver_due(now).await.unwrap();
+
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].id, queued[0].id);
+    assert_eq!(delivered[0].status, DeliveryStatus::Succeeded);
+    assert_eq!(delivered[0].response_status, Some(200));
+    let received = receiver.received();
+    assert_eq!(received.len(), 1);
+    let request = &received[0];
+    assert_eq!(request.body, r#"{"event":"taskDeleted"}"#);
+    assert_eq!(request.header("Content-Type"), Some("application/json"));
+    assert_eq!(request.header("X-Webhook-Event"), Some("taskDeleted"));
+    assert_eq!(request.header("X-Webhook-Delivery"), Some(queued[0].id.to_string().as_str()));
+    assert_eq!(request.header("X-Webhook-Signature"), Some(expected_signature(&request.body).as_str()));
+    assert_eq!(service.find_deliveries(ADMIN_ID, webhook.id).await.unwrap().unwrap().len(), 1);
+}
+
+#[actix_web::test]
+async fn retries_with_exponential_backoff_until_the_receiver_accepts() {
+    let receiver = StandInReceiver::start(&[500, 503]).await;
+    let service = webhook_service(Arc::default());
+    create_webhook(&service, &receiver.url, vec![]).await;
+    let start = Utc::now();
+    service.enqueue(WebhookEvent::TaskUpdated, WORKSPACE_ID, "{}".into(), start).await.unwrap();
+
+    let first = service.deliver_due(start).await.unwrap().remove(0);
+    assert_eq!(first.status, DeliveryStatus::Pending);
+    assert_eq!(first.response_status, Some(500));
+    assert_eq!(first.next_attempt_at, Some(start + TimeDelta::seconds(60)));
+
+    assert!(service.deliver_due(start + TimeDelta::seconds(59)).await.unwrap().is_empty(), "not due yet");
+
+    let second_attempt_at = start + TimeDelta::seconds(60);
+    let second = service.deliver_due(second_attempt_at).await.unwrap().remove(0);
+    assert_eq!(second.attempts, 2);
+    assert_eq!(second.response_status, Some(503));
+    assert_eq!(second.next_attempt_at, Some(second_attempt_at + TimeDelta::seconds(120)));
+
+    let third = service.deliver_due(second_attempt_at + TimeDelta::seconds(120)).await.unwrap().remove(0);
+    assert_eq!(third.attempts, 3);
+    assert_eq!(third.status, DeliveryStatus::Succeeded);
+    assert_eq!(third.error, None);
+    assert_eq!(receiver.received().len(), 3);
+}
+
+#[actix_web::test]
+async fn gives_up_after_the_last_attempt() {
+    let receiver = StandInReceiver::start(&[500, 500, 500]).await;
+    let service = webhook_service(Arc::default());
+    create_webhook(&service, &receiver.url, vec![]).await;
+    let mut now = Utc::now();
+    service.enqueue(WebhookEvent::TaskUpdated, WORKSPACE_ID, "{}".into(), now).await.unwrap();
+
+    let mut delivery = None;
+    for _ in 0..3 {
+        let attempted = service.deliver_due(now).await.unwrap().remove(0);
+        now = attempted.next_attempt_at.unwrap_or(now);
+        delivery = Some(attempted);
+    }
+
+    let delivery = delivery.unwrap();
+    assert_eq!(delivery.status, DeliveryStatus::Failed);
+    assert_eq!(delivery.next_attempt_at, None);
+    assert_eq!(delivery.error.as_deref(), Some("the receiver responded with status 500"));
+    assert!(service.deliver_due(now + TimeDelta::days(1)).await.unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn records_unreachable_receivers_and_retries_them() {
+    // nothing listens on a port that was just released
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let service = webhook_service(Arc::default());
+    create_webhook(&service, &format!("http://127.0.0.1:{port}/hook"), vec![]).await;
+    let now = Utc::now();
+    service.enqueue(WebhookEvent::TaskCreated, WORKSPACE_ID, "{}".into(), now).await.unwrap();
+
+    let delivery = service.deliver_due(now).await.unwrap().remove(0);
+
+    assert_eq!(delivery.status, DeliveryStatus::Pending);
+    assert_eq!(delivery.response_status, None);
+    assert!(delivery.error.is_some());
+    assert_eq!(delivery.next_attempt_at, Some(now + TimeDelta::seconds(60)));
+}
+
+#[actix_web::test]
+async fn refuses_internal_addresses_at_registration_and_delivery() {
+    let receiver = StandInReceiver::start(&[]).await;
+    let repository = Arc::new(InMemoryWebhookRepository::default());
+    let sender = Arc::new(HttpWebhookSender::new(Duration::from_secs(5)).unwrap());
+    let service: Arc<dyn WebhookServiceTrait> =
+        Arc::new(WebhookService::new(repository.clone(), Arc::new(FixedWorkspaceRepository), sender, retry_policy()));
+    for url in [receiver.url.as_str(), "http://localhost/hook", "http://169.254.169.254/latest/meta-data", "http://[::1]/hook"] {
+        let error = service.create_webhook(ADMIN_ID, new_webhook(url, vec![])).await.unwrap_err();
+        assert_eq!(error.downcast_ref::<WebhookError>(), Some(&WebhookError::NonPublicAddress(url.to_string())));
+    }
+
+    // registered while the host was public
+    create_webhook(&webhook_service(repository), &receiver.url, vec![]).await;
+    let now = Utc::now();
+    service.enqueue(WebhookEvent::TaskCreated, WORKSPACE_ID, "{}".into(), now).await.unwrap();
+    let delivery = service.deliver_due(now).await.unwrap().remove(0);
+
+    assert_eq!(delivery.status, DeliveryStatus::Pending);
+    assert!(delivery.error.is_some_and(|error| error.contains("loopback, private or link-local")));
+    assert!(receiver.received().is_empty());
+}
+
+#[actix_web::test]
+async fn redelivers_the_same_payload_as_a_new_delivery() {
+    let receiver = StandInReceiver::start(&[]).await;
+    let service = webhook_service(Arc::default());
+    let webhook = create_webhook(&service, &receiver.url, vec![]).await;
+    let now = Utc::now();
+    service.enqueue(WebhookEvent::TaskCreated, WORKSPACE_ID, r#"{"n":1}"#.into(), now).await.unwrap();
+    let original = service.deliver_due(now).await.unwrap().remove(0);
+
+    let redelivery = service.redeliver(ADMIN_ID, webhook.id, original.id).await.unwrap().unwrap();
+
+    assert_ne!(redelivery.id, original.id);
+    assert_eq!(redelivery.redelivery_of, Some(original.id));
+    assert_eq!(redelivery.status, DeliveryStatus::Succeeded);
+    let received = receiver.received();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].body, received[1].body);
+    assert_eq!(received[1].header("X-Webhook-Delivery"), Some(redelivery.id.to_string().as_str()));
+    assert!(service.redeliver(ADMIN_ID, webhook.id + 1, original.id).await.unwrap().is_none());
+}
+
+#[actix_web::test]
+async fn the_worker_delivers_published_task_events() {
+    let receiver = StandInReceiver::start(&[]).await;
+    let service = webhook_service(Arc::default());
+    create_webhook(&service, &receiver.url, vec![WebhookEvent::TaskDeleted]).await;
+    let (worker, publisher) = WebhookDeliveryWorker::new(service, Duration::from_secs(60));
+    actix_web::rt::spawn(worker.run());
+
+    publisher.publish(TaskEventDto::Deleted { id: 42, workspace_id: 1 });
+
+    let received = receiver.wait_for_requests(1).await;
+    let payload: Value = serde_json::from_str(&received[0].body).unwrap();
+    assert_eq!(payload["event"], "taskDeleted");
+    assert_eq!(payload["taskId"], 42);
+    assert!(payload["occurredAt"].is_string());
+    assert_eq!(received[0].header("X-Webhook-Signature"), Some(expected_signature(&received[0].body).as_str()));
+}
+
+#[actix_web::test]
+async fn events_published_to_a_full_queue_are_queued_once_there_is_room() {
+    let receiver = StandInReceiver::start(&[]).await;
+    let repository = Arc::new(InMemoryWebhookRepository::default());
+    let service = webhook_service(repository.clone());
+    create_webhook(&service, &receiver.url, vec![WebhookEvent::TaskDeleted]).await;
+    let (worker, publisher) = WebhookDeliveryWorker::new(service, Duration::from_secs(60));
+
+    // more events than the queue holds, published before the worker takes any of them
+    let published = 1100;
+    for id in 0..published {
+        publisher.publish(TaskEventDto::Deleted { id, workspace_id: WORKSPACE_ID });
+    }
+    actix_web::rt::spawn(worker.run());
+
+    let queued = || repository.state.lock().unwrap().1.len();
+    for _ in 0..200 {
+        if queued() == published as usize {
+            break;
+        }
+        actix_web::rt::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(queued(), published as usize);
+}
+
+#[actix_web::test]
+async fn the_api_manages_webhooks_and_redelivers() {
+    let receiver = StandInReceiver::start(&[500]).await;
+    let service = webhook_service(Arc::default());
+    let app = test::init_service(App::new().service(WebhookController::new(service.clone()).configure()).wrap_fn(
+        |request, app| {
+            request.extensions_mut().insert(principal(&request));
+            app.call(request)
+        },
+    ))
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/webhooks")
+        .set_json(json!({ "workspaceId": WORKSPACE_ID, "url": "ftp://example.com", "secret": SECRET }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), 422);
+    let problem: Value = test::read_body_json(response).await;
+    assert_eq!(problem["code"], "invalid-webhook-url");
+
+    let request = test::TestRequest::post()
+        .uri("/webhooks")
+        .set_json(json!({
+            "workspaceId": WORKSPACE_ID,
+            "url": "http://169.254.169.254/latest/meta-data",
+            "secret": SECRET,
+        }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), 422);
+    let problem: Value = test::read_body_json(response).await;
+    assert_eq!(problem["code"], "non-public-webhook-address");
+
+    let request = test::TestRequest::post()
+        .uri("/webhooks")
+        .set_json(json!({
+            "workspaceId": WORKSPACE_ID,
+            "url": receiver.url,
+            "events": ["taskCreated"],
+            "secret": SECRET,
+        }))
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(created["workspaceId"], WORKSPACE_ID);
+    assert_eq!(created["events"], json!(["taskCreated"]));
+    assert_eq!(created["active"], true);
+    assert!(created.get("secret").is_none(), "the secret is never returned");
+    let id = created["id"].as_i64().unwrap();
+
+    let now = Utc::now();
+    service.enqueue(WebhookEvent::TaskCreated, WORKSPACE_ID, "{}".into(), now).await.unwrap();
+    service.deliver_due(now).await.unwrap();
+    let request = test::TestRequest::get()
+        .uri(&format!("/webhooks/{id}/deliveries"))
+        .insert_header((USER_HEADER, VIEWER_ID.to_string()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), 403);
+    let problem: Value = test::read_body_json(response).await;
+    assert_eq!(problem["code"], "forbidden");
+    let request = test::TestRequest::get()
+        .uri(&format!("/webhooks/{id}"))
+        .insert_header((USER_HEADER, OUTSIDER_ID.to_string()))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), 404);
+
+    let request = test::TestRequest::get().uri(&format!("/webhooks/{id}/deliveries")).to_request();
+    let deliveries: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(deliveries[0]["status"], "pending");
+    assert_eq!(deliveries[0]["responseStatus"], 500);
+    let delivery_id = deliveries[0]["id"].as_i64().unwrap();
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/webhooks/{id}/deliveries/{delivery_id}/redeliver"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), 201);
+    let redelivery: Value = test::read_body_json(response).await;
+    assert_eq!(redelivery["status"], "succeeded");
+    assert_eq!(redelivery["redeliveryOf"], delivery_id);
+
+    let request = test::TestRequest::delete().uri(&format!("/webhooks/{id}")).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), 204);
+    let request = test::TestRequest::get().uri(&format!("/webhooks/{id}/deliveries")).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), 404);
+}
+
+#[actix_web::test]
+async fn only_admins_and_owners_manage_the_webhooks_of_their_workspace() {
+    let service = webhook_service(Arc::default());
+    let url = "http://203.0.113.10/hook";
+
+    let error = service.create_webhook(VIEWER_ID, new_webhook(url, vec![])).await.unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<WebhookError>(),
+        Some(&WebhookError::Forbidden(WorkspaceAction::ManageWebhooks))
+    );
+    let error = service.create_webhook(OUTSIDER_ID, new_webhook(url, vec![])).await.unwrap_err();
+    assert_eq!(error.downcast_ref::<WebhookError>(), Some(&WebhookError::WorkspaceNotFound(WORKSPACE_ID)));
+
+    let webhook = create_webhook(&service, url, vec![]).await;
+
+    let error = service.get_webhook(VIEWER_ID, webhook.id).await.unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<WebhookError>(),
+        Some(&WebhookError::Forbidden(WorkspaceAction::ManageWebhooks))
+    );
+    assert!(service.get_webhook(OUTSIDER_ID, webhook.id).await.unwrap().is_none());
+    assert!(!service.delete_webhook(OUTSIDER_ID, webhook.id).await.unwrap());
+    assert!(service.find_webhooks(VIEWER_ID).await.unwrap().is_empty());
+    assert!(service.find_webhooks(OUTSIDER_ID).await.unwrap().is_empty());
+    let webhooks = service.find_webhooks(ADMIN_ID).await.unwrap();
+    assert_eq!(webhooks.iter().map(|webhook| webhook.id).collect::<Vec<_>>(), [webhook.id]);
+
+    let now = Utc::now();
+    let queued = service.enqueue(WebhookEvent::TaskCreated, OTHER_WORKSPACE_ID, "{}".into(), now).await.unwrap();
+    assert!(queued.is_empty(), "events of other workspaces are not sent");
+    let queued = service.enqueue(WebhookEvent::TaskCreated, WORKSPACE_ID, "{}".into(), now).await.unwrap();
+    assert_eq!(queued.len(), 1);
+}
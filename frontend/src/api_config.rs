@@ -1,13 +1,76 @@
 use api_client::apis::configuration::Configuration;
+use api_client::models::AuthSession;
+use gloo_storage::{LocalStorage, Storage};
+
+const BASE_PATH: &str = "http://127.0.0.1:8080";
+
+/// Local storage key of the signed-in session, so that it survives reloads.
+const SESSION_KEY: &str = "task-tracker.session";
 
 pub fn config() -> Configuration {
     Configuration {
-        base_path: "http://127.0.0.1:8080".to_string(),
+        base_path: BASE_PATH.to_string(),
         user_agent: None,
         client: reqwest::Client::new(),
         basic_auth: None,
         oauth_access_token: None,
-        bearer_access_token: None,
+        bearer_access_token: session().map(|session| session.access_token),
         api_key: None,
     }
 }
+
+/// Credentials of the signed-in user, if any.
+pub fn session() -> Option<AuthSession> {
+    LocalStorage::get(SESSION_KEY).ok()
+}
+
+pub fn store_session(session: &AuthSession) {
+    let _ = LocalStorage::set(SESSION_KEY, session);
+}
+
+pub fn clear_session() {
+    LocalStorage::delete(SESSION_KEY);
+}
+
+/// Adds the access token to the URL of an event stream or socket, which cannot send headers.
+pub fn with_access_token(url: &str) -> String {
+    match session() {
+        Some(session) => {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!("{url}{separator}access_token={}", js_sys::encode_uri_component(&session.access_token))
+        }
+        None => url.to_string(),
+    }
+}
+
+/// Starts single sign-on, which comes back with the outcome in the URL fragment.
+pub fn sso_login_url() -> String {
+    format!("{BASE_PATH}/auth/oidc/login")
+}
+
+/// What single sign-on redirected back with, if the page was loaded that way.
+pub enum SsoOutcome {
+    /// One-time code to exchange for the session.
+    Code(String),
+    /// Problem code, e.g. `unverified-email`.
+    Error(String),
+}
+
+/// Reads the outcome of single sign-on from the URL fragment and removes it, so that the code does
+/// not linger in the address bar or history.
+pub fn take_sso_outcome() -> Option<SsoOutcome> {
+    let window = web_sys::window()?;
+    let location = window.location();
+    let hash = location.hash().ok()?;
+    let (key, value) = hash.strip_prefix('#')?.split_once('=')?;
+    let outcome = match key {
+        "code" => SsoOutcome::Code(value.to_string()),
+        "error" => SsoOutcome::Error(value.to_string()),
+        _ => return None,
+    };
+    let url = format!("{}{}", location.pathname().ok()?, location.search().ok()?);
+    let _ = window
+        .history()
+        .and_then(|history| history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url)));
+    Some(outcome)
+}
@@ -0,0 +1,33 @@
+use api_client::models::TaskAssignee;
+use yew::prelude::*;
+
+/// Background colors for avatars, picked by user ID so that everyone keeps their color.
+const AVATAR_COLORS: [&str; 6] = ["#2563eb", "#16a34a", "#d97706", "#dc2626", "#7c3aed", "#0891b2"];
+
+#[derive(Properties, PartialEq)]
+pub struct AssigneeAvatarProps {
+    pub assignee: TaskAssignee,
+}
+
+#[function_component(AssigneeAvatar)]
+pub fn assignee_avatar(props: &AssigneeAvatarProps) -> Html {
+    let AssigneeAvatarProps { assignee } = props;
+    let color = AVATAR_COLORS[assignee.id.unsigned_abs() as usize % AVATAR_COLORS.len()];
+
+    html! {
+        <span
+            class="inline-flex items-center justify-center w-6 h-6 rounded-full text-[10px] font-semibold text-white shrink-0"
+            style={format!("background-color: {color}")}
+            title={format!("Assigned to {}", assignee.display_name)}>
+            { initials(&assignee.display_name) }
+        </span>
+    }
+}
+
+/// The first letters of the first and last word of the name, e.g. `AL` for Ada Lovelace.
+fn initials(display_name: &str) -> String {
+    let mut words = display_name.split_whitespace();
+    let first = words.next().and_then(|word| word.chars().next());
+    let last = words.last().and_then(|word| word.chars().next());
+    first.into_iter().chain(last).flat_map(char::to_uppercase).collect()
+}
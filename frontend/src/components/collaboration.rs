@@ -0,0 +1,233 @@
+use crate::api_config::{config, with_access_token};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{MessageEvent, WebSocket};
+use yew::prelude::*;
+
+/// Messages for `GET /collaboration/ws`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ClientMessage {
+    Subscribe {
+        task_ids: Vec<i64>,
+        filter: Option<SubscriptionFilter>,
+    },
+    Focus {
+        task_id: Option<i64>,
+    },
+    Lock {
+        task_id: i64,
+    },
+    Unlock {
+        task_id: i64,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionFilter {
+    pub project_id: Option<i64>,
+    pub tag_id: Option<i64>,
+}
+
+/// Messages from the server; task changes are followed through the event stream instead.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ServerMessage {
+    Welcome { client_id: u64, lock_timeout_seconds: u64 },
+    Snapshot { presence: Vec<Presence>, locks: Vec<Lock> },
+    Presence(Presence),
+    Locked(Lock),
+    LockDenied(Lock),
+    Unlocked { task_id: i64 },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Presence {
+    pub client_id: u64,
+    pub user: String,
+    pub task_id: Option<i64>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lock {
+    pub task_id: i64,
+    pub client_id: u64,
+    pub user: String,
+}
+
+#[derive(Default, PartialEq)]
+pub struct CollaborationState {
+    client_id: Option<u64>,
+    lock_timeout_seconds: u64,
+    locks: HashMap<i64, Lock>,
+    /// What the other clients are looking at, by client.
+    presence: HashMap<u64, Presence>,
+}
+
+impl Reducible for CollaborationState {
+    type Action = ServerMessage;
+
+    fn reduce(self: Rc<Self>, message: ServerMessage) -> Rc<Self> {
+        let mut state = CollaborationState {
+            client_id: self.client_id,
+            lock_timeout_seconds: self.lock_timeout_seconds,
+            locks: self.locks.clone(),
+            presence: self.presence.clone(),
+        };
+        match message {
+            ServerMessage::Welcome { client_id, lock_timeout_seconds } => {
+                state = CollaborationState { client_id: Some(client_id), lock_timeout_seconds, ..Default::default() };
+            }
+            ServerMessage::Snapshot { presence, locks } => {
+                state.presence = presence.into_iter().map(|presence| (presence.client_id, presence)).collect();
+                state.locks = locks.into_iter().map(|lock| (lock.task_id, lock)).collect();
+            }
+            ServerMessage::Presence(presence) => {
+                if presence.task_id.is_some() {
+                    state.presence.insert(presence.client_id, presence);
+                } else {
+                    state.presence.remove(&presence.client_id);
+                }
+            }
+            ServerMessage::Locked(lock) | ServerMessage::LockDenied(lock) => {
+                state.locks.insert(lock.task_id, lock);
+            }
+            ServerMessage::Unlocked { task_id } => {
+                state.locks.remove(&task_id);
+            }
+            ServerMessage::Other => return self,
+        }
+        Rc::new(state)
+    }
+}
+
+/// The socket, with messages sent before it opened waiting in `pending`.
+#[derive(Default)]
+struct Connection {
+    socket: RefCell<Option<WebSocket>>,
+    pending: RefCell<Vec<String>>,
+}
+
+impl Connection {
+    fn send(&self, text: String) {
+        match self.socket.borrow().as_ref() {
+            Some(socket) if socket.ready_state() == WebSocket::OPEN => {
+                let _ = socket.send_with_str(&text);
+            }
+            _ => self.pending.borrow_mut().push(text),
+        }
+    }
+
+    fn flush(&self) {
+        for text in self.pending.take() {
+            self.send(text);
+        }
+    }
+}
+
+/// Presence and soft locks shared with the other clients, provided by `CollaborationProvider`.
+#[derive(Clone)]
+pub struct Collaboration {
+    state: UseReducerHandle<CollaborationState>,
+    connection: Rc<Connection>,
+}
+
+impl PartialEq for Collaboration {
+    fn eq(&self, other: &Self) -> bool {
+        self.state == other.state && Rc::ptr_eq(&self.connection, &other.connection)
+    }
+}
+
+impl Collaboration {
+    pub fn send(&self, message: ClientMessage) {
+        if let Ok(text) = serde_json::to_string(&message) {
+            self.connection.send(text);
+        }
+    }
+
+    /// Someone else's lock on the task.
+    pub fn lock_holder(&self, task_id: i64) -> Option<&Lock> {
+        self.state.locks.get(&task_id).filter(|lock| Some(lock.client_id) != self.state.client_id)
+    }
+
+    /// Names of the other users looking at the task.
+    pub fn viewers(&self, task_id: i64) -> Vec<String> {
+        let mut viewers: Vec<String> = self
+            .state
+            .presence
+            .values()
+            .filter(|presence| presence.task_id == Some(task_id) && Some(presence.client_id) != self.state.client_id)
+            .map(|presence| presence.user.clone())
+            .collect();
+        viewers.sort();
+        viewers.dedup();
+        viewers
+    }
+
+    /// How often a held lock is renewed, well within the server's timeout.
+    pub fn lock_renewal_millis(&self) -> u32 {
+        (self.state.lock_timeout_seconds.max(3) * 1000 / 3) as u32
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CollaborationProviderProps {
+    /// The signed-in user; the connection is reopened when they change.
+    pub user: String,
+    pub children: Html,
+}
+
+#[function_component(CollaborationProvider)]
+pub fn collaboration_provider(props: &CollaborationProviderProps) -> Html {
+    let state = use_reducer(CollaborationState::default);
+    let connection = use_memo((), |_| Connection::default());
+
+    {
+        let state = state.clone();
+        let connection = connection.clone();
+        // the server names the connection after the signed-in user
+        use_effect_with(props.user.clone(), move |_| {
+            let url = format!("{}/collaboration/ws", config().base_path.replacen("http", "ws", 1));
+            let socket = WebSocket::new(&with_access_token(&url)).ok();
+            let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                let message = event.data().as_string().and_then(|text| serde_json::from_str(&text).ok());
+                if let Some(message) = message {
+                    state.dispatch(message);
+                }
+            });
+            let on_open = {
+                let connection = connection.clone();
+                Closure::<dyn FnMut()>::new(move || connection.flush())
+            };
+            if let Some(socket) = &socket {
+                socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+                socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+            }
+            *connection.socket.borrow_mut() = socket.clone();
+            move || {
+                connection.socket.take();
+                if let Some(socket) = socket {
+                    let _ = socket.close();
+                }
+                drop(on_message);
+                drop(on_open);
+            }
+        });
+    }
+
+    let collaboration = Collaboration { state, connection };
+    html! {
+        <ContextProvider<Collaboration> context={collaboration}>
+            { props.children.clone() }
+        </ContextProvider<Collaboration>>
+    }
+}
@@ -0,0 +1,128 @@
+use crate::api_config::{config, sso_login_url, store_session};
+use crate::components::utils::{bind_input, field_error, problem_detail};
+use api_client::apis::auth_api;
+use api_client::models::{AuthSession, LoginRequest, RegisterRequest};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct LoginPageProps {
+    /// Receives the session once the user has signed in or registered.
+    pub on_sign_in: Callback<AuthSession>,
+    /// Why the last single sign-on failed, if it did.
+    #[prop_or_default]
+    pub sso_error: Option<String>,
+}
+
+#[function_component(LoginPage)]
+pub fn login_page(props: &LoginPageProps) -> Html {
+    let registering = use_state(|| false);
+    let email = use_state(String::new);
+    let password = use_state(String::new);
+    let display_name = use_state(String::new);
+    let error = use_state(|| None::<String>);
+    let busy = use_state(|| false);
+
+    let onsubmit = {
+        let registering = *registering;
+        let email = email.clone();
+        let password = password.clone();
+        let display_name = display_name.clone();
+        let error = error.clone();
+        let busy = busy.clone();
+        let on_sign_in = props.on_sign_in.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+            let email = (*email).clone();
+            let password = (*password).clone();
+            let display_name = (*display_name).clone();
+            let error = error.clone();
+            let busy = busy.clone();
+            let on_sign_in = on_sign_in.clone();
+            busy.set(true);
+            spawn_local(async move {
+                let config = config();
+                let result = if registering {
+                    auth_api::register(&config, RegisterRequest { email, password, display_name })
+                        .await
+                        .map_err(|error| field_error(&error, "password").unwrap_or_else(|| problem_detail(&error)))
+                } else {
+                    auth_api::login(&config, LoginRequest { email, password })
+                        .await
+                        .map_err(|error| problem_detail(&error))
+                };
+                busy.set(false);
+                match result {
+                    Ok(session) => {
+                        store_session(&session);
+                        on_sign_in.emit(session);
+                    }
+                    Err(message) => error.set(Some(message)),
+                }
+            });
+        })
+    };
+
+    let onclick_switch = {
+        let registering = registering.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            registering.set(!*registering);
+            error.set(None);
+        })
+    };
+
+    let input_class = "border rounded px-2 py-1 w-full";
+
+    html! {
+        <div class="min-h-screen flex items-center justify-center bg-gray-50">
+            <form class="w-80 bg-white shadow rounded-lg p-6 space-y-3" {onsubmit}>
+                <h1 class="text-xl font-semibold text-gray-800">
+                    { if *registering { "Create an account" } else { "Sign in" } }
+                </h1>
+                if *registering {
+                    <input
+                        class={input_class}
+                        placeholder="Display name"
+                        autocomplete="name"
+                        value={(*display_name).clone()}
+                        oninput={bind_input(display_name.clone())}
+                    />
+                }
+                <input
+                    class={input_class}
+                    type="email"
+                    placeholder="Email"
+                    autocomplete="email"
+                    value={(*email).clone()}
+                    oninput={bind_input(email.clone())}
+                />
+                <input
+                    class={input_class}
+                    type="password"
+                    placeholder="Password"
+                    autocomplete={if *registering { "new-password" } else { "current-password" }}
+                    value={(*password).clone()}
+                    oninput={bind_input(password.clone())}
+                />
+                if let Some(message) = error.as_ref().or(props.sso_error.as_ref()) {
+                    <p class="text-sm text-red-600">{ message }</p>
+                }
+                <button
+                    type="submit"
+                    class="w-full px-3 py-1 rounded-md bg-blue-500 hover:bg-blue-600 text-white duration-200 disabled:opacity-50"
+                    disabled={*busy}>
+                    { if *registering { "Register" } else { "Sign in" } }
+                </button>
+                <a
+                    href={sso_login_url()}
+                    class="block w-full text-center px-3 py-1 rounded-md border border-gray-300 hover:bg-gray-100 text-gray-700 duration-200">
+                    { "Sign in with single sign-on" }
+                </a>
+                <button type="button" class="w-full text-sm text-blue-600 hover:underline" onclick={onclick_switch}>
+                    { if *registering { "I already have an account" } else { "Create an account" } }
+                </button>
+            </form>
+        </div>
+    }
+}
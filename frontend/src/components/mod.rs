@@ -1,4 +1,11 @@
+pub mod collaboration;
+pub mod login_page;
 pub mod task_list;
 
+mod assignee_avatar;
+mod project_sidebar;
+mod tag_chip;
+mod task_details;
 mod task_item;
+mod undo_toast;
 mod utils;
\ No newline at end of file
@@ -0,0 +1,136 @@
+use crate::api_config::config;
+use crate::components::utils::bind_input;
+use api_client::apis::projects_api;
+use api_client::models::{NewProject, Project};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ProjectSidebarProps {
+    pub selected: Option<i64>,
+    pub on_select: Callback<Option<i64>>,
+    /// Changes whenever tasks change so that the open task counts are refetched.
+    pub refresh: u32,
+}
+
+#[function_component(ProjectSidebar)]
+pub fn project_sidebar(props: &ProjectSidebarProps) -> Html {
+    let ProjectSidebarProps { selected, on_select, refresh } = props;
+    let projects = use_state(Vec::<Project>::new);
+    let new_project_name = use_state(String::new);
+    let changes = use_state(|| 0u32);
+
+    {
+        let projects = projects.clone();
+        use_effect_with((*refresh, *changes), move |_| {
+            spawn_local(async move {
+                let config = config();
+                if let Ok(fetched) = projects_api::list_projects(&config, None).await {
+                    projects.set(fetched);
+                }
+            });
+            || ()
+        });
+    }
+
+    let onclick_add = {
+        let new_project_name = new_project_name.clone();
+        let changes = changes.clone();
+        Callback::from(move |_| {
+            let name = new_project_name.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let new_project_name = new_project_name.clone();
+            let changes = changes.clone();
+            spawn_local(async move {
+                let config = config();
+                if projects_api::create_project(&config, NewProject { name, workspace_id: None }).await.is_ok() {
+                    new_project_name.set(String::new());
+                    changes.set(changes.wrapping_add(1));
+                }
+            });
+        })
+    };
+
+    // Archiving hides the project and its tasks, so the list falls back to all tasks
+    let on_archive = {
+        let selected = *selected;
+        let on_select = on_select.clone();
+        let changes = changes.clone();
+        Callback::from(move |id: i64| {
+            let on_select = on_select.clone();
+            let changes = changes.clone();
+            spawn_local(async move {
+                let config = config();
+                if projects_api::archive_project(&config, id).await.is_ok() {
+                    changes.set(changes.wrapping_add(1));
+                    if selected == Some(id) {
+                        on_select.emit(None);
+                    }
+                }
+            });
+        })
+    };
+
+    let oninput_new_project_name = bind_input(new_project_name.clone());
+
+    let entry_class = |active: bool| {
+        classes!(
+            "flex-1", "text-left", "px-2", "py-1", "rounded",
+            if active { "bg-blue-100 text-blue-800" } else { "hover:bg-gray-100" }
+        )
+    };
+
+    html! {
+        <nav class="w-48 shrink-0 text-sm">
+            <h2 class="font-semibold text-gray-700 mb-2">{ "Projects" }</h2>
+            <ul class="space-y-1 mb-3">
+                <li class="flex">
+                    <button
+                        type="button"
+                        class={entry_class(selected.is_none())}
+                        onclick={on_select.reform(|_| None)}>
+                        { "All tasks" }
+                    </button>
+                </li>
+                { for projects.iter().map(|project| {
+                    let id = project.id;
+                    html! {
+                        <li key={id} class="flex items-center gap-1">
+                            <button
+                                type="button"
+                                class={entry_class(*selected == Some(id))}
+                                onclick={on_select.reform(move |_| Some(id))}>
+                                { &project.name }
+                                <span class="ml-1 text-gray-400">{ project.open_task_count }</span>
+                            </button>
+                            <button
+                                type="button"
+                                class="text-gray-400 hover:text-gray-700"
+                                title="Archive project"
+                                onclick={on_archive.reform(move |_| id)}>
+                                {"🗄"}
+                            </button>
+                        </li>
+                    }
+                })}
+            </ul>
+            <div class="flex gap-1">
+                <input
+                    class="border rounded px-1 w-full"
+                    placeholder="New project"
+                    value={(*new_project_name).clone()}
+                    oninput={oninput_new_project_name}
+                />
+                <button
+                    type="button"
+                    class="px-2 rounded-md bg-blue-500 hover:bg-blue-600 text-white duration-200"
+                    title="Create project"
+                    onclick={onclick_add}>
+                    {"+"}
+                </button>
+            </div>
+        </nav>
+    }
+}
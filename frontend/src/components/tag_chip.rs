@@ -0,0 +1,52 @@
+use api_client::models::Tag;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct TagChipProps {
+    pub tag: Tag,
+    #[prop_or_default]
+    pub on_click: Option<Callback<Tag>>,
+    #[prop_or_default]
+    pub on_remove: Option<Callback<Tag>>,
+}
+
+#[function_component(TagChip)]
+pub fn tag_chip(props: &TagChipProps) -> Html {
+    let TagChipProps { tag, on_click, on_remove } = props;
+
+    let onclick_chip = on_click.clone().map(|on_click| {
+        let tag = tag.clone();
+        Callback::from(move |_| on_click.emit(tag.clone()))
+    });
+
+    let onclick_remove = on_remove.clone().map(|on_remove| {
+        let tag = tag.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            on_remove.emit(tag.clone())
+        })
+    });
+
+    html! {
+        <span
+            class={classes!(
+                on_click.is_some().then_some("cursor-pointer hover:opacity-80"),
+                "inline-flex", "items-center", "gap-1", "px-2", "rounded-full", "text-xs", "text-white", "whitespace-nowrap"
+            )}
+            style={format!("background-color: {}", tag.color)}
+            title={if on_click.is_some() {"Show tasks with this tag"} else {""}}
+            onclick={onclick_chip}>
+            { &tag.name }
+            {
+                match onclick_remove {
+                    Some(onclick_remove) => html! {
+                        <button type="button" class="font-bold" title="Remove tag" onclick={onclick_remove}>
+                            {"×"}
+                        </button>
+                    },
+                    None => html! {},
+                }
+            }
+        </span>
+    }
+}
@@ -0,0 +1,440 @@
+use crate::api_config::config;
+use crate::components::tag_chip::TagChip;
+use crate::components::utils::{
+    bind_input, bind_textarea, browser_time_zone, emit_change, from_date_input_value, if_match, priority_label,
+    render_markdown, repeat_label, to_date_input_value, PRIORITIES, REPEAT_OPTIONS,
+};
+use api_client::apis::{projects_api, tags_api, tasks_api};
+use api_client::models::{
+    ChangeTaskDescription, ChangeTaskDueDate, ChangeTaskPriority, ChangeTaskProject, ChangeTaskRecurrence, NewTag,
+    Project, Tag, Task,
+};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct TaskDetailsProps {
+    pub task: Task,
+    pub on_update: Callback<Task>,
+    /// Reports that a change was rejected because the task had been changed elsewhere.
+    pub on_stale: Callback<()>,
+    /// Reports why a change was rejected.
+    pub on_error: Callback<String>,
+}
+
+#[function_component(TaskDetails)]
+pub fn task_details(props: &TaskDetailsProps) -> Html {
+    let TaskDetailsProps { task, on_update, on_stale, on_error } = props;
+    let version = if_match(task);
+    let description_input = use_state(String::new);
+    let editing = use_state(|| false);
+    let available_tags = use_state(Vec::<Tag>::new);
+    let new_tag_name = use_state(String::new);
+    let new_tag_color = use_state(|| "#3b82f6".to_string());
+    let projects = use_state(Vec::<Project>::new);
+
+    // Tag suggestions and project choices, only those of the task's workspace fit
+    {
+        let available_tags = available_tags.clone();
+        let projects = projects.clone();
+        use_effect_with(task.workspace_id, move |workspace_id| {
+            let workspace_id = *workspace_id;
+            spawn_local(async move {
+                let config = config();
+                if let Ok(mut tags) = tags_api::list_tags(&config).await {
+                    tags.retain(|tag| tag.workspace_id == workspace_id);
+                    available_tags.set(tags);
+                }
+                if let Ok(mut fetched) = projects_api::list_projects(&config, None).await {
+                    fetched.retain(|project| project.workspace_id == workspace_id);
+                    projects.set(fetched);
+                }
+            });
+            || ()
+        });
+    }
+
+    let onclick_edit = {
+        let editing_for_closure = editing.clone();
+        let description_input_for_closure = description_input.clone();
+        let description_for_closure = task.description.clone().unwrap_or_default();
+        Callback::from(move |_| {
+            description_input_for_closure.set(description_for_closure.clone());
+            editing_for_closure.set(true);
+        })
+    };
+
+    let onclick_cancel = {
+        let editing_for_closure = editing.clone();
+        Callback::from(move |_| editing_for_closure.set(false))
+    };
+
+    let onclick_save = {
+        let task_id = task.id;
+        let version = version.clone();
+        let description_input_for_closure = description_input.clone();
+        let on_update_for_closure = on_update.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
+        let editing_for_closure = editing.clone();
+        Callback::from(move |_| {
+            let version = version.clone();
+            let description_input = description_input_for_closure.clone();
+            let on_update = on_update_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
+            let editing = editing_for_closure.clone();
+
+            spawn_local(async move {
+                let config = config();
+                let body = ChangeTaskDescription {
+                    description: Some((*description_input).clone()),
+                };
+                let result = tasks_api::change_task_description(&config, task_id, Some(&version), body).await;
+                if emit_change(result, &on_update, &on_stale, &on_error) {
+                    editing.set(false);
+                }
+            });
+        })
+    };
+
+    let change_due_date = {
+        let task_id = task.id;
+        let version = version.clone();
+        let on_update_for_closure = on_update.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
+        Callback::from(move |due_at: Option<String>| {
+            let version = version.clone();
+            let on_update = on_update_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
+            spawn_local(async move {
+                let config = config();
+                let body = ChangeTaskDueDate { due_at };
+                let result = tasks_api::change_task_due_date(&config, task_id, Some(&version), body).await;
+                emit_change(result, &on_update, &on_stale, &on_error);
+            });
+        })
+    };
+
+    let onchange_due_date = {
+        let change_due_date = change_due_date.clone();
+        Callback::from(move |e: Event| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                change_due_date.emit(from_date_input_value(&input.value()));
+            }
+        })
+    };
+
+    let onclick_clear_due_date = Callback::from(move |_| change_due_date.emit(None));
+
+    let onchange_priority = {
+        let task_id = task.id;
+        let version = version.clone();
+        let on_update_for_closure = on_update.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
+        Callback::from(move |e: Event| {
+            let Some(select) = e.target_dyn_into::<HtmlSelectElement>() else {
+                return;
+            };
+            let Some(priority) = usize::try_from(select.selected_index())
+                .ok()
+                .and_then(|index| PRIORITIES.get(index).copied())
+            else {
+                return;
+            };
+            let version = version.clone();
+            let on_update = on_update_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
+            spawn_local(async move {
+                let config = config();
+                let body = ChangeTaskPriority { priority };
+                let result = tasks_api::change_task_priority(&config, task_id, Some(&version), body).await;
+                emit_change(result, &on_update, &on_stale, &on_error);
+            });
+        })
+    };
+
+    let onchange_repeat = {
+        let task_id = task.id;
+        let version = version.clone();
+        let on_update_for_closure = on_update.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
+        Callback::from(move |e: Event| {
+            let Some(select) = e.target_dyn_into::<HtmlSelectElement>() else {
+                return;
+            };
+            // the extra option of a custom rule keeps it unchanged
+            let Some((_, rule)) = usize::try_from(select.selected_index())
+                .ok()
+                .and_then(|index| REPEAT_OPTIONS.get(index))
+            else {
+                return;
+            };
+            let body = ChangeTaskRecurrence {
+                rule: rule.map(String::from),
+                time_zone: rule.and_then(|_| browser_time_zone()),
+            };
+            let version = version.clone();
+            let on_update = on_update_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
+            spawn_local(async move {
+                let config = config();
+                let result = tasks_api::change_task_recurrence(&config, task_id, Some(&version), body).await;
+                emit_change(result, &on_update, &on_stale, &on_error);
+            });
+        })
+    };
+
+    // The first option moves the task out of its project
+    let onchange_project = {
+        let task_id = task.id;
+        let projects = projects.clone();
+        let version = version.clone();
+        let on_update_for_closure = on_update.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
+        Callback::from(move |e: Event| {
+            let Some(select) = e.target_dyn_into::<HtmlSelectElement>() else {
+                return;
+            };
+            let project_id = usize::try_from(select.selected_index())
+                .ok()
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|index| projects.get(index))
+                .map(|project| project.id);
+            let version = version.clone();
+            let on_update = on_update_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
+            spawn_local(async move {
+                let config = config();
+                let body = ChangeTaskProject { project_id };
+                let result = tasks_api::change_task_project(&config, task_id, Some(&version), body).await;
+                emit_change(result, &on_update, &on_stale, &on_error);
+            });
+        })
+    };
+
+    let custom_rule = task
+        .recurrence_rule
+        .as_deref()
+        .filter(|rule| repeat_label(Some(rule)) == "Custom");
+
+    // Attach an existing tag by name, creating it first if needed
+    let onclick_add_tag = {
+        let task_id = task.id;
+        let workspace_id = task.workspace_id;
+        let available_tags = available_tags.clone();
+        let new_tag_name = new_tag_name.clone();
+        let new_tag_color = new_tag_color.clone();
+        let version = version.clone();
+        let on_update_for_closure = on_update.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
+        Callback::from(move |_| {
+            let name = new_tag_name.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let existing = available_tags
+                .iter()
+                .find(|tag| tag.name.eq_ignore_ascii_case(&name))
+                .cloned();
+            let new_tag = NewTag { name, color: (*new_tag_color).clone(), workspace_id: Some(workspace_id) };
+            let available_tags = available_tags.clone();
+            let new_tag_name = new_tag_name.clone();
+            let version = version.clone();
+            let on_update = on_update_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
+
+            spawn_local(async move {
+                let config = config();
+                let tag = match existing {
+                    Some(tag) => tag,
+                    None => match tags_api::create_tag(&config, new_tag).await {
+                        Ok(tag) => {
+                            let mut tags = (*available_tags).clone();
+                            tags.push(tag.clone());
+                            available_tags.set(tags);
+                            tag
+                        }
+                        Err(_) => return,
+                    },
+                };
+                let result = tasks_api::add_task_tag(&config, task_id, tag.id, Some(&version)).await;
+                if emit_change(result, &on_update, &on_stale, &on_error) {
+                    new_tag_name.set(String::new());
+                }
+            });
+        })
+    };
+
+    let on_remove_tag = {
+        let task_id = task.id;
+        let version = version.clone();
+        let on_update_for_closure = on_update.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
+        Callback::from(move |tag: Tag| {
+            let version = version.clone();
+            let on_update = on_update_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
+            spawn_local(async move {
+                let config = config();
+                let result = tasks_api::remove_task_tag(&config, task_id, tag.id, Some(&version)).await;
+                emit_change(result, &on_update, &on_stale, &on_error);
+            });
+        })
+    };
+
+    let oninput_new_tag_name = bind_input(new_tag_name.clone());
+    let oninput_new_tag_color = bind_input(new_tag_color.clone());
+    let oninput_description = bind_textarea(description_input.clone());
+
+    html! {
+        <div class="ml-6 mb-2 px-2 py-2 border-l-2 border-gray-200">
+            <div class="flex items-center gap-2 mb-2 text-sm text-gray-600">
+                <label for={format!("due-{}", task.id)}>{ "Due" }</label>
+                <input
+                    id={format!("due-{}", task.id)}
+                    type="date"
+                    class="border rounded px-1"
+                    value={task.due_at.as_deref().map(to_date_input_value).unwrap_or_default()}
+                    onchange={onchange_due_date}
+                />
+                <button
+                    type="button"
+                    class={classes!(task.due_at.is_none().then_some("hidden"), "text-gray-400", "hover:text-gray-700")}
+                    title="Clear due date"
+                    onclick={onclick_clear_due_date}>
+                    {"✕"}
+                </button>
+                <label for={format!("priority-{}", task.id)} class="ml-4">{ "Priority" }</label>
+                <select
+                    id={format!("priority-{}", task.id)}
+                    class="border rounded px-1"
+                    onchange={onchange_priority}>
+                    { for PRIORITIES.iter().map(|priority| html! {
+                        <option selected={*priority == task.priority}>{ priority_label(*priority) }</option>
+                    })}
+                </select>
+                <label for={format!("repeat-{}", task.id)} class="ml-4">{ "Repeat" }</label>
+                <select
+                    id={format!("repeat-{}", task.id)}
+                    class="border rounded px-1"
+                    onchange={onchange_repeat}>
+                    { for REPEAT_OPTIONS.iter().map(|(label, rule)| html! {
+                        <option selected={*rule == task.recurrence_rule.as_deref()}>{ *label }</option>
+                    })}
+                    {
+                        match custom_rule {
+                            Some(rule) => html! { <option selected=true>{ format!("Custom ({rule})") }</option> },
+                            None => html! {},
+                        }
+                    }
+                </select>
+                <label for={format!("project-{}", task.id)} class="ml-4">{ "Project" }</label>
+                <select
+                    id={format!("project-{}", task.id)}
+                    class="border rounded px-1"
+                    onchange={onchange_project}>
+                    <option selected={task.project_id.is_none()}>{ "None" }</option>
+                    { for projects.iter().map(|project| html! {
+                        <option selected={task.project_id == Some(project.id)}>{ &project.name }</option>
+                    })}
+                </select>
+            </div>
+            <div class="flex flex-wrap items-center gap-2 mb-2 text-sm text-gray-600">
+                { for task.tags.iter().map(|tag| html! {
+                    <TagChip tag={tag.clone()} on_remove={on_remove_tag.clone()} />
+                })}
+                <input
+                    class="border rounded px-1 w-28"
+                    placeholder="Add tag"
+                    list={format!("tag-suggestions-{}", task.id)}
+                    value={(*new_tag_name).clone()}
+                    oninput={oninput_new_tag_name}
+                />
+                <datalist id={format!("tag-suggestions-{}", task.id)}>
+                    { for available_tags.iter().map(|tag| html! { <option value={tag.name.clone()} /> }) }
+                </datalist>
+                <input
+                    type="color"
+                    class="h-6 w-6"
+                    title="Color for new tags"
+                    value={(*new_tag_color).clone()}
+                    oninput={oninput_new_tag_color}
+                />
+                <button
+                    type="button"
+                    class="px-2 rounded-md bg-blue-500 hover:bg-blue-600 text-white duration-200"
+                    title="Attach tag"
+                    onclick={onclick_add_tag}>
+                    {"+"}
+                </button>
+            </div>
+            {
+                if *editing {
+                    html! {
+                        <div class="flex flex-col gap-2">
+                            <textarea
+                                class="border rounded px-2 py-1 h-32 font-mono text-sm"
+                                placeholder="Describe the task using Markdown"
+                                value={(*description_input).clone()}
+                                oninput={oninput_description}
+                            />
+                            <div class="flex gap-2 justify-end">
+                                <button
+                                    type="button"
+                                    class="px-3 py-1 rounded-md bg-gray-200 hover:bg-gray-300 duration-200"
+                                    onclick={onclick_cancel}>
+                                    { "Cancel" }
+                                </button>
+                                <button
+                                    type="button"
+                                    class="px-3 py-1 rounded-md bg-green-500 hover:bg-green-600 duration-200"
+                                    title="Save description"
+                                    onclick={onclick_save}>
+                                    {"💾"}
+                                </button>
+                            </div>
+                        </div>
+                    }
+                } else {
+                    html! {
+                        <div class="flex justify-between items-start gap-2">
+                            {
+                                match task.description.as_deref() {
+                                    Some(description) => html! {
+                                        <div class="prose prose-sm flex-1">
+                                            { render_markdown(description) }
+                                        </div>
+                                    },
+                                    None => html! {
+                                        <span class="flex-1 text-sm italic text-gray-400">{ "No description" }</span>
+                                    },
+                                }
+                            }
+                            <button
+                                type="button"
+                                class="px-3 py-1 rounded-md bg-blue-500 hover:bg-blue-600 duration-200"
+                                title="Edit description"
+                                onclick={onclick_edit}>
+                                {"✏️"}
+                            </button>
+                        </div>
+                    }
+                }
+            }
+        </div>
+    }
+}
@@ -1,7 +1,17 @@
 use crate::api_config::config;
-use crate::components::utils::bind_input;
-use api_client::apis::tasks_api;
-use api_client::models::{RenameTask, Task};
+use crate::components::assignee_avatar::AssigneeAvatar;
+use crate::components::collaboration::{ClientMessage, Collaboration};
+use crate::components::tag_chip::TagChip;
+use crate::components::task_details::TaskDetails;
+use crate::components::utils::{
+    bind_input, emit_change, if_match, is_overdue, priority_badge_class, priority_label, problem_detail,
+    repeat_label, to_date_input_value,
+};
+use api_client::apis::{tasks_api, Error};
+use api_client::models::{MoveTask, RenameTask, Tag, Task};
+use gloo_timers::callback::Interval;
+use std::collections::HashSet;
+use std::rc::Rc;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
@@ -9,6 +19,19 @@ use yew::prelude::*;
 pub struct TaskItemProps {
     pub task: Task,
     pub on_update: Callback<Task>,
+    pub on_delete: Callback<Task>,
+    pub on_tag_click: Callback<Tag>,
+    /// Asks the list to refetch after changes to the tree structure.
+    pub on_reload: Callback<()>,
+    /// Task to indent this task under, if any.
+    #[prop_or_default]
+    pub previous_sibling_id: Option<i64>,
+    /// Parent of this task's parent, which outdenting moves the task to.
+    #[prop_or_default]
+    pub grandparent_id: Option<i64>,
+    /// Tasks picked for a bulk action, shared with the subtasks.
+    pub selected_ids: Rc<HashSet<i64>>,
+    pub on_toggle_select: Callback<i64>,
 }
 
 #[function_component(TaskItem)]
@@ -16,20 +39,136 @@ pub fn task_item(props: &TaskItemProps) -> Html {
     let TaskItemProps {
         task: task_prop,
         on_update,
+        on_delete,
+        on_tag_click,
+        on_reload,
+        previous_sibling_id,
+        grandparent_id,
+        selected_ids,
+        on_toggle_select,
     } = props;
     let task = use_state(|| task_prop.clone());
     let on_update = on_update.clone();
+    let on_delete = on_delete.clone();
     let title_input = use_state(|| task.title.clone());
     let editing = use_state(|| false);
+    let expanded = use_state(|| false);
+    let children_collapsed = use_state(|| false);
+    let error = use_state(|| None::<String>);
+    // whether the error can be overridden by completing the task anyway
+    let force_offered = use_state(|| false);
+    // set when a change was rejected because the task had been changed elsewhere
+    let stale = use_state(|| false);
 
     {
         let task_for_closure = task.clone();
+        let stale_for_closure = stale.clone();
+        let error_for_closure = error.clone();
         use_effect_with(props.task.clone(), move |new_task| {
             task_for_closure.clone().set(new_task.clone());
+            stale_for_closure.set(false);
+            error_for_closure.set(None);
             || ()
         });
     }
 
+    let collaboration = use_context::<Collaboration>();
+    let lock_holder = collaboration
+        .as_ref()
+        .and_then(|collaboration| collaboration.lock_holder(task.id))
+        .map(|lock| lock.user.clone());
+    let viewers = collaboration
+        .as_ref()
+        .map(|collaboration| collaboration.viewers(task.id))
+        .unwrap_or_default();
+
+    // Soft-lock the task while its title is edited, renewing the lock until editing ends
+    {
+        let collaboration = collaboration.clone();
+        let task_id = task.id;
+        use_effect_with(*editing, move |editing| {
+            let renewal = collaboration.filter(|_| *editing).map(|collaboration| {
+                collaboration.send(ClientMessage::Lock { task_id });
+                let renewing = collaboration.clone();
+                let interval = Interval::new(collaboration.lock_renewal_millis(), move || {
+                    renewing.send(ClientMessage::Lock { task_id })
+                });
+                (collaboration, interval)
+            });
+            move || {
+                if let Some((collaboration, interval)) = renewal {
+                    interval.cancel();
+                    collaboration.send(ClientMessage::Unlock { task_id });
+                }
+            }
+        });
+    }
+
+    // Someone else got the lock first
+    {
+        let editing_for_closure = editing.clone();
+        use_effect_with(lock_holder.clone(), move |lock_holder| {
+            if lock_holder.is_some() {
+                editing_for_closure.set(false);
+            }
+            || ()
+        });
+    }
+
+    // Let the others see which task's details are open
+    {
+        let collaboration = collaboration.clone();
+        let task_id = task.id;
+        use_effect_with(*expanded, move |expanded| {
+            let focused = collaboration.filter(|_| *expanded);
+            if let Some(collaboration) = &focused {
+                collaboration.send(ClientMessage::Focus { task_id: Some(task_id) });
+            }
+            move || {
+                if let Some(collaboration) = focused {
+                    collaboration.send(ClientMessage::Focus { task_id: None });
+                }
+            }
+        });
+    }
+
+    let on_stale = {
+        let stale_for_closure = stale.clone();
+        Callback::from(move |_| stale_for_closure.set(true))
+    };
+
+    let on_error = {
+        let error_for_closure = error.clone();
+        let force_offered_for_closure = force_offered.clone();
+        Callback::from(move |message: String| {
+            force_offered_for_closure.set(false);
+            error_for_closure.set(Some(message));
+        })
+    };
+
+    let onclick_reload = {
+        let stale_for_closure = stale.clone();
+        let on_reload_for_closure = on_reload.clone();
+        Callback::from(move |_| {
+            stale_for_closure.set(false);
+            on_reload_for_closure.emit(());
+        })
+    };
+
+    let onclick_toggle_details = {
+        let expanded_for_closure = expanded.clone();
+        Callback::from(move |_| expanded_for_closure.set(!*expanded_for_closure))
+    };
+
+    let on_details_update = {
+        let task_for_closure = task.clone();
+        let on_update_for_closure = on_update.clone();
+        Callback::from(move |updated_task: Task| {
+            task_for_closure.set(updated_task.clone());
+            on_update_for_closure.emit(updated_task);
+        })
+    };
+
     let onclick_edit = {
         let editing_for_closure = editing.clone();
         let title_input_for_closure = title_input.clone();
@@ -44,11 +183,15 @@ pub fn task_item(props: &TaskItemProps) -> Html {
         let task_for_closure = task.clone();
         let title_input_for_closure = title_input.clone();
         let on_update_for_closure = on_update.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
         let editing_for_closure = editing.clone();
         Callback::from(move |_| {
             let task = task_for_closure.clone();
             let title_input = title_input_for_closure.clone();
             let on_update = on_update_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
             let editing = editing_for_closure.clone();
 
             spawn_local(async move {
@@ -56,42 +199,189 @@ pub fn task_item(props: &TaskItemProps) -> Html {
                 let body = RenameTask {
                     title: (*title_input).clone(),
                 };
-                if let Ok(updated_task) = tasks_api::rename_task(&config, task.id, body).await {
-                    task.set(updated_task.clone());
-                    on_update.emit(updated_task);
+                let on_renamed = {
+                    let task = task.clone();
+                    on_update.reform(move |updated_task: Task| {
+                        task.set(updated_task.clone());
+                        updated_task
+                    })
+                };
+                let result = tasks_api::rename_task(&config, task.id, Some(&if_match(&task)), body).await;
+                if emit_change(result, &on_renamed, &on_stale, &on_error) {
                     editing.set(false);
                 }
             });
         })
     };
 
-    let onclick_mark_done = {
+    let mark_done = {
         let task_for_closure = task.clone();
         let on_update_for_closure = on_update.clone();
+        let on_reload_for_closure = on_reload.clone();
         let editing_for_closure = editing.clone();
-        Callback::from(move |_| {
+        let error_for_closure = error.clone();
+        let force_offered_for_closure = force_offered.clone();
+        let stale_for_closure = stale.clone();
+        Callback::from(move |force: bool| {
             let task = task_for_closure.clone();
             let on_update = on_update_for_closure.clone();
+            let on_reload = on_reload_for_closure.clone();
             let editing = editing_for_closure.clone();
+            let error = error_for_closure.clone();
+            let force_offered = force_offered_for_closure.clone();
+            let stale = stale_for_closure.clone();
             spawn_local(async move {
                 let config = config();
                 let current_task = (*task).clone();
                 task.set(current_task.clone());
                 editing.set(false);
 
-                if let Ok(done_task) = tasks_api::mark_task_done(&config, current_task.id).await {
-                    task.set(done_task.clone());
-                    on_update.emit(done_task);
+                let version = if_match(&current_task);
+                match tasks_api::mark_task_done(&config, current_task.id, force.then_some(true), Some(&version)).await {
+                    Ok(done_task) => {
+                        error.set(None);
+                        // subtasks may have been completed along with their parent,
+                        // and repeating tasks schedule their next occurrence
+                        if current_task.children.is_some() || current_task.recurrence_rule.is_some() {
+                            on_reload.emit(());
+                        } else {
+                            task.set(done_task.clone());
+                            on_update.emit(done_task);
+                        }
+                    }
+                    Err(Error::ResponseError(response)) if response.status.as_u16() == 409 => {
+                        force_offered.set(current_task.blocked);
+                        error.set(Some(if current_task.blocked {
+                            "Blocked by open tasks".to_string()
+                        } else {
+                            "Finish all subtasks first".to_string()
+                        }));
+                    }
+                    Err(Error::ResponseError(response)) if response.status.as_u16() == 412 => stale.set(true),
+                    Err(other) => {
+                        force_offered.set(false);
+                        error.set(Some(problem_detail(&other)));
+                    }
                 }
             });
         })
     };
 
+    let onclick_mark_done = {
+        let mark_done = mark_done.clone();
+        Callback::from(move |_| mark_done.emit(false))
+    };
+
+    let onclick_force_done = Callback::from(move |_| mark_done.emit(true));
+
+    let onclick_reopen = {
+        let task_for_closure = task.clone();
+        let on_update_for_closure = on_update.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
+        Callback::from(move |_| {
+            let task = task_for_closure.clone();
+            let on_update = on_update_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
+            spawn_local(async move {
+                let config = config();
+                let on_reopened = {
+                    let task = task.clone();
+                    on_update.reform(move |reopened_task: Task| {
+                        task.set(reopened_task.clone());
+                        reopened_task
+                    })
+                };
+                let result = tasks_api::mark_task_undone(&config, task.id, Some(&if_match(&task))).await;
+                emit_change(result, &on_reopened, &on_stale, &on_error);
+            });
+        })
+    };
+
+    let onclick_delete = {
+        let task_for_closure = task.clone();
+        let on_delete_for_closure = on_delete.clone();
+        let on_stale_for_closure = on_stale.clone();
+        let on_error_for_closure = on_error.clone();
+        Callback::from(move |_| {
+            let task = task_for_closure.clone();
+            let on_delete = on_delete_for_closure.clone();
+            let on_stale = on_stale_for_closure.clone();
+            let on_error = on_error_for_closure.clone();
+            spawn_local(async move {
+                let config = config();
+                let result = tasks_api::delete_task(&config, task.id, Some(&if_match(&task))).await;
+                emit_change(result, &on_delete, &on_stale, &on_error);
+            });
+        })
+    };
+
+    let move_task = {
+        let task_id = task.id;
+        let version = if_match(&task);
+        let on_reload = on_reload.clone();
+        let on_stale = on_stale.clone();
+        let on_error = on_error.clone();
+        Callback::from(move |parent_id: Option<i64>| {
+            let version = version.clone();
+            let on_reload = on_reload.clone();
+            let on_stale = on_stale.clone();
+            let on_error = on_error.clone();
+            spawn_local(async move {
+                let config = config();
+                let result = tasks_api::move_task(&config, task_id, Some(&version), MoveTask { parent_id }).await;
+                emit_change(result, &on_reload.reform(|_| ()), &on_stale, &on_error);
+            });
+        })
+    };
+
+    let onclick_indent = previous_sibling_id.map(|sibling_id| {
+        let move_task = move_task.clone();
+        Callback::from(move |_| move_task.emit(Some(sibling_id)))
+    });
+
+    let onclick_outdent = task.parent_id.map(|_| {
+        let grandparent_id = *grandparent_id;
+        Callback::from(move |_| move_task.emit(grandparent_id))
+    });
+
+    let onclick_toggle_children = {
+        let children_collapsed_for_closure = children_collapsed.clone();
+        Callback::from(move |_| children_collapsed_for_closure.set(!*children_collapsed_for_closure))
+    };
+
+    let onchange_select = {
+        let id = task.id;
+        on_toggle_select.reform(move |_: Event| id)
+    };
+
+    let children = task.children.clone().unwrap_or_default();
     let oninput_title = bind_input(title_input.clone());
+    let overdue = is_overdue(&task);
 
     html! {
-        <li class="flex justify-between items-center gap-2 px-2 py-1 hover:bg-gray-50 rounded transition-colors h-10">
-            <div class="flex justify-between items-center w-full gap-2">
+        <li class="flex flex-col px-2 py-1 hover:bg-gray-50 rounded transition-colors">
+            <div class="flex justify-between items-center w-full gap-2 h-8">
+                <input
+                    type="checkbox"
+                    class="accent-blue-600"
+                    title="Select for bulk actions"
+                    checked={selected_ids.contains(&task.id)}
+                    onchange={onchange_select}
+                />
+                <button
+                    type="button"
+                    class="w-5 text-gray-500 hover:text-gray-800 duration-200"
+                    title={if *expanded {"Hide details"} else {"Show details"}}
+                    onclick={onclick_toggle_details}>
+                    {if *expanded {"▾"} else {"▸"}}
+                </button>
+                <span
+                    class={classes!(priority_badge_class(task.priority), "px-2", "rounded-full", "text-xs", "font-semibold")}
+                    title="Priority">
+                    { priority_label(task.priority) }
+                </span>
                 {
                     if *editing {
                         html! {
@@ -105,18 +395,90 @@ pub fn task_item(props: &TaskItemProps) -> Html {
                         html! {
                             <span class={classes!(
                                 task.done.then_some("line-through text-gray-500"),
+                                overdue.then_some("text-red-600 font-semibold"),
                                 "transition-opacity", "duration-200", "opacity-100",
                                 "flex-1", "px-1", "py-1", "border", "border-transparent", "rounded"
                             )}>
+                                {
+                                    if task.blocked && !task.done {
+                                        html! { <span class="mr-1" title="Blocked by open tasks">{"🔒"}</span> }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
                                 { &task.title }
+                                {
+                                    match task.recurrence_rule.as_deref() {
+                                        Some(rule) => html! {
+                                            <span class="ml-2 text-xs text-gray-400" title={rule.to_string()}>
+                                                { format!("🔁 {}", repeat_label(Some(rule))) }
+                                            </span>
+                                        },
+                                        None => html! {},
+                                    }
+                                }
+                                {
+                                    match task.due_at.as_deref() {
+                                        Some(due_at) => html! {
+                                            <span
+                                                class={classes!("ml-2", "text-xs", if overdue {"text-red-500"} else {"text-gray-400"})}
+                                                title={if overdue {"Overdue"} else {"Due date"}}>
+                                                { format!("📅 {}", to_date_input_value(due_at)) }
+                                            </span>
+                                        },
+                                        None => html! {},
+                                    }
+                                }
                             </span>
                         }
                     }
                 }
+                {
+                    match &lock_holder {
+                        Some(user) => html! {
+                            <span class="text-xs text-amber-600 whitespace-nowrap" title="Locked while being edited">
+                                { format!("✎ {user} is editing") }
+                            </span>
+                        },
+                        None => html! {},
+                    }
+                }
+                {
+                    if viewers.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <span class="text-xs text-gray-400 whitespace-nowrap" title="Also looking at this task">
+                                { format!("👀 {}", viewers.join(", ")) }
+                            </span>
+                        }
+                    }
+                }
+                { for task.assignees.iter().map(|assignee| html! {
+                    <AssigneeAvatar assignee={assignee.clone()} />
+                })}
+                { for task.tags.iter().map(|tag| html! {
+                    <TagChip tag={tag.clone()} on_click={on_tag_click.clone()} />
+                })}
+                <button
+                    type="button"
+                    class={classes!(onclick_outdent.is_none().then_some("invisible"), "text-gray-500", "hover:text-gray-800")}
+                    title="Outdent task"
+                    onclick={onclick_outdent}>
+                    {"⇤"}
+                </button>
+                <button
+                    type="button"
+                    class={classes!(onclick_indent.is_none().then_some("invisible"), "text-gray-500", "hover:text-gray-800")}
+                    title="Indent under previous task"
+                    onclick={onclick_indent}>
+                    {"⇥"}
+                </button>
                 <button
                     type="button"
                     title={if *editing {"Save changes"} else {"Edit title"}}
                     onclick={if *editing {onclick_save_name} else {onclick_edit}}
+                    disabled={lock_holder.is_some()}
                     class={classes!(
                         task.done.then_some("hidden"),
                         if *editing {"bg-green-500"} else {"bg-blue-500"},
@@ -139,7 +501,131 @@ pub fn task_item(props: &TaskItemProps) -> Html {
                     disabled={*editing}>
                     {"✔"}
                 </button>
+                <button
+                    type="button"
+                    class={classes!(
+                        (!task.done).then_some("hidden"),
+                        "bg-yellow-500", "hover:bg-yellow-600",
+                        "px-3", "py-1", "rounded-md", "duration-200"
+                    )}
+                    onclick={onclick_reopen}
+                    title="Reopen task">
+                    {"↩"}
+                </button>
+                <button
+                    type="button"
+                    class={classes!(
+                        "bg-red-500", "hover:bg-red-600", "disabled:bg-gray-400", "disabled:hover:bg-gray-500", "disabled:cursor-not-allowed",
+                        "px-3", "py-1", "rounded-md", "duration-200"
+                    )}
+                    onclick={onclick_delete}
+                    title="Move task to trash"
+                    disabled={*editing}>
+                    {"🗑"}
+                </button>
             </div>
+            {
+                match (*error).as_ref() {
+                    Some(message) => html! {
+                        <div class="ml-6 flex items-center gap-2 text-sm text-red-600">
+                            { message }
+                            {
+                                if *force_offered {
+                                    html! {
+                                        <button
+                                            type="button"
+                                            class="underline hover:text-red-800"
+                                            onclick={onclick_force_done}>
+                                            { "Complete anyway" }
+                                        </button>
+                                    }
+                                } else {
+                                    html! {}
+                                }
+                            }
+                        </div>
+                    },
+                    None => html! {},
+                }
+            }
+            {
+                if *stale {
+                    html! {
+                        <div class="ml-6 flex items-center gap-2 text-sm text-amber-700">
+                            { "Task changed, reload?" }
+                            <button
+                                type="button"
+                                class="underline hover:text-amber-900"
+                                onclick={onclick_reload}>
+                                { "Reload" }
+                            </button>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                if *expanded {
+                    html! {
+                        <TaskDetails
+                            task={(*task).clone()}
+                            on_update={on_details_update}
+                            on_stale={on_stale}
+                            on_error={on_error}
+                        />
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                if children.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <div class="ml-6">
+                            <button
+                                type="button"
+                                class="text-xs text-gray-500 hover:text-gray-800"
+                                onclick={onclick_toggle_children}>
+                                {
+                                    format!(
+                                        "{} {} subtask{}",
+                                        if *children_collapsed {"▸"} else {"▾"},
+                                        children.len(),
+                                        if children.len() == 1 {""} else {"s"},
+                                    )
+                                }
+                            </button>
+                            {
+                                if *children_collapsed {
+                                    html! {}
+                                } else {
+                                    html! {
+                                        <ul class="space-y-1 border-l-2 border-gray-100">
+                                            { for children.iter().enumerate().map(|(index, child)| html! {
+                                                <TaskItem
+                                                    key={child.id}
+                                                    task={child.clone()}
+                                                    on_update={on_update.clone()}
+                                                    on_delete={on_delete.clone()}
+                                                    on_tag_click={on_tag_click.clone()}
+                                                    on_reload={on_reload.clone()}
+                                                    previous_sibling_id={index.checked_sub(1).map(|previous| children[previous].id)}
+                                                    grandparent_id={task.parent_id}
+                                                    selected_ids={selected_ids.clone()}
+                                                    on_toggle_select={on_toggle_select.clone()}
+                                                />
+                                            })}
+                                        </ul>
+                                    }
+                                }
+                            }
+                        </div>
+                    }
+                }
+            }
         </li>
     }
 }
@@ -1,50 +1,259 @@
-use crate::api_config::config;
+use crate::api_config::{config, with_access_token};
+use crate::components::collaboration::{ClientMessage, Collaboration, SubscriptionFilter};
+use crate::components::project_sidebar::ProjectSidebar;
+use crate::components::tag_chip::TagChip;
 use crate::components::task_item::TaskItem;
-use crate::components::utils::bind_input;
+use crate::components::undo_toast::UndoToast;
+use crate::components::utils::{
+    bind_input, browser_time_zone, field_error, from_date_input_value, if_match, problem_detail, REPEAT_OPTIONS,
+};
 use api_client::apis::tasks_api;
 use api_client::models;
-use api_client::models::{NewTask, Task};
+use api_client::models::{
+    BulkTaskItem, BulkTaskOperation, BulkTaskOperationKind, BulkTaskRequest, BulkTaskResultStatus, NewTask, Tag, Task,
+    TaskDeletedEvent,
+};
+use std::collections::HashSet;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
+use web_sys::{Element, EventSource, HtmlSelectElement, MessageEvent};
 use yew::prelude::*;
 
+/// Distance in pixels from the end of the list at which the next page is requested.
+const LOAD_MORE_THRESHOLD: i32 = 200;
+/// Events sent by `GET /tasks/events`.
+const TASK_EVENTS: [&str; 4] = ["taskCreated", "taskUpdated", "taskDeleted", "reset"];
+
+/// The filters of the list: tag, project and assignee.
+type FilterIds = (Option<i64>, Option<i64>, Option<i64>);
+
+#[derive(Properties, PartialEq)]
+pub struct TaskListProps {
+    /// The signed-in user, whose tasks "My tasks" shows.
+    pub user_id: i64,
+}
+
 #[function_component(TaskList)]
-pub fn task_list() -> Html {
+pub fn task_list(props: &TaskListProps) -> Html {
     let tasks = use_state(Vec::<Task>::new);
     let title = use_state(String::new);
+    let title_error = use_state(|| None::<String>);
+    let due_date = use_state(String::new);
+    let repeat = use_state(|| None::<&'static str>);
+    let trashed_task = use_state(|| None::<Task>);
+    let tag_filter = use_state(|| None::<Tag>);
+    let project_filter = use_state(|| None::<i64>);
+    let my_tasks = use_state(|| false);
+    let reload = use_state(|| 0u32);
+    let selected_ids = use_state(|| Rc::new(HashSet::<i64>::new()));
+    let bulk_error = use_state(|| None::<String>);
+    let task_changes = use_state(|| 0u32);
+    let next_cursor = use_state(|| None::<String>);
+    // the cursor of the page in flight, so that scrolling does not request it twice
+    let requested_cursor = use_mut_ref(|| None::<String>);
+    // bumped whenever the first page is refetched, so that stale pages are dropped
+    let generation = use_mut_ref(|| 0u32);
+    let filter_ids: FilterIds = (
+        tag_filter.as_ref().map(|tag| tag.id),
+        *project_filter,
+        my_tasks.then_some(props.user_id),
+    );
+    // the list as last rendered or merged, for event listeners that outlive a render
+    let latest_tasks = use_mut_ref(Vec::<Task>::new);
+    *latest_tasks.borrow_mut() = (*tasks).clone();
 
-    // Fetch on mount
+    // Fetch the first page on mount, whenever a filter changes and after changes to the tree structure
     {
         let tasks = tasks.clone();
-        use_effect_with((), move |_| {
-            let tasks = tasks.clone();
+        let next_cursor = next_cursor.clone();
+        let requested_cursor = requested_cursor.clone();
+        let generation = generation.clone();
+        use_effect_with((filter_ids, *reload), move |(filter_ids, _)| {
+            let filter_and_sort = task_filter(*filter_ids, None);
+            *requested_cursor.borrow_mut() = None;
+            *generation.borrow_mut() += 1;
+            let current_generation = *generation.borrow();
             spawn_local(async move {
                 let config = config();
-                let task_filter_and_sort = models::TaskFilterAndSort::default();
-                if let Ok(fetched) = tasks_api::filter_tasks(&config, task_filter_and_sort).await {
-                    tasks.set(fetched);
+                if let Ok(page) = tasks_api::filter_tasks(&config, filter_and_sort).await {
+                    if *generation.borrow() == current_generation {
+                        tasks.set(page.items);
+                        next_cursor.set(page.next_cursor);
+                    }
                 }
             });
             || ()
         });
     }
 
+    // Merge changes made elsewhere; after missed events the list is fetched again
+    {
+        let tasks = tasks.clone();
+        let latest_tasks = latest_tasks.clone();
+        let reload = reload.clone();
+        use_effect_with((filter_ids, *reload), move |(filter_ids, reload_count)| {
+            let filter_ids = *filter_ids;
+            let reload_count = *reload_count;
+            let on_event = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                let data = event.data().as_string().unwrap_or_default();
+                let current = latest_tasks.borrow().clone();
+                let merged = match event.type_().as_str() {
+                    "taskCreated" | "taskUpdated" => match serde_json::from_str::<Task>(&data) {
+                        Ok(changed) => merge_task(&current, changed, filter_ids),
+                        Err(_) => return,
+                    },
+                    "taskDeleted" => match serde_json::from_str::<TaskDeletedEvent>(&data) {
+                        Ok(deleted) => remove_task(&current, deleted.id),
+                        Err(_) => return,
+                    },
+                    _ => {
+                        reload.set(reload_count.wrapping_add(1));
+                        return;
+                    }
+                };
+                *latest_tasks.borrow_mut() = merged.clone();
+                tasks.set(merged);
+            });
+            let source = EventSource::new(&with_access_token(&format!("{}/tasks/events", config().base_path))).ok();
+            if let Some(source) = &source {
+                for name in TASK_EVENTS {
+                    let _ = source.add_event_listener_with_callback(name, on_event.as_ref().unchecked_ref());
+                }
+            }
+            move || {
+                if let Some(source) = source {
+                    source.close();
+                }
+                drop(on_event);
+            }
+        });
+    }
+
+    // Hear about presence and locks on the loaded tasks
+    {
+        let collaboration = use_context::<Collaboration>();
+        let task_ids = task_ids(&tasks);
+        use_effect_with((task_ids, filter_ids), move |(task_ids, (tag_id, project_id, _))| {
+            if let Some(collaboration) = collaboration {
+                collaboration.send(ClientMessage::Subscribe {
+                    task_ids: task_ids.clone(),
+                    filter: Some(SubscriptionFilter { project_id: *project_id, tag_id: *tag_id }),
+                });
+            }
+            || ()
+        });
+    }
+
+    // Append the next page, skipping tasks that were added locally in the meantime
+    let load_more = {
+        let tasks = tasks.clone();
+        let next_cursor = next_cursor.clone();
+        Callback::from(move |_: ()| {
+            let Some(cursor) = (*next_cursor).clone() else {
+                return;
+            };
+            if requested_cursor.borrow().as_ref() == Some(&cursor) {
+                return;
+            }
+            *requested_cursor.borrow_mut() = Some(cursor.clone());
+            let filter_and_sort = task_filter(filter_ids, Some(cursor));
+            let current_generation = *generation.borrow();
+            let generation = generation.clone();
+            let tasks = tasks.clone();
+            let next_cursor = next_cursor.clone();
+            spawn_local(async move {
+                let config = config();
+                if let Ok(page) = tasks_api::filter_tasks(&config, filter_and_sort).await {
+                    if *generation.borrow() == current_generation {
+                        let mut loaded = (*tasks).clone();
+                        let page_items: Vec<Task> = page
+                            .items
+                            .into_iter()
+                            .filter(|task| !loaded.iter().any(|existing| existing.id == task.id))
+                            .collect();
+                        loaded.extend(page_items);
+                        tasks.set(loaded);
+                        next_cursor.set(page.next_cursor);
+                    }
+                }
+            });
+        })
+    };
+
+    let onscroll = {
+        let load_more = load_more.clone();
+        Callback::from(move |e: Event| {
+            if let Some(list) = e.target_dyn_into::<Element>() {
+                if list.scroll_top() + list.client_height() >= list.scroll_height() - LOAD_MORE_THRESHOLD {
+                    load_more.emit(());
+                }
+            }
+        })
+    };
+
     let oninput_title = bind_input(title.clone());
+    let oninput_due_date = bind_input(due_date.clone());
+
+    let onchange_repeat = {
+        let repeat = repeat.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                let option = usize::try_from(select.selected_index())
+                    .ok()
+                    .and_then(|index| REPEAT_OPTIONS.get(index));
+                repeat.set(option.and_then(|(_, rule)| *rule));
+            }
+        })
+    };
 
     // Add new task
     let onclick_add = {
         let title = title.clone();
+        let title_error = title_error.clone();
+        let due_date = due_date.clone();
+        let repeat = repeat.clone();
+        let project_id = *project_filter;
         let tasks = tasks.clone();
+        let task_changes = task_changes.clone();
         Callback::from(move |_| {
             let title_val = (*title).clone();
+            let due_at = from_date_input_value(&due_date);
+            let recurrence_rule = repeat.map(String::from);
             let tasks = tasks.clone();
+            let task_changes = task_changes.clone();
+            let title_error = title_error.clone();
             spawn_local(async move {
                 if !title_val.is_empty() {
                     let config = config();
-                    let new_task_req = NewTask { title: title_val };
-                    if let Ok(new_task) = tasks_api::create_task(&config, new_task_req).await {
-                        let mut new_list = (*tasks).clone();
-                        new_list.push(new_task);
-                        tasks.set(new_list);
+                    let new_task_req = NewTask {
+                        title: title_val,
+                        description: None,
+                        due_at,
+                        priority: None,
+                        parent_id: None,
+                        recurrence_time_zone: recurrence_rule.as_ref().and_then(|_| browser_time_zone()),
+                        recurrence_rule,
+                        project_id,
+                        // the server picks the user's workspace
+                        workspace_id: None,
+                    };
+                    match tasks_api::create_task(&config, new_task_req).await {
+                        Ok(new_task) => {
+                            title_error.set(None);
+                            let mut new_list = (*tasks).clone();
+                            // the task may have arrived as an event already
+                            if find_task(&new_list, new_task.id).is_none() {
+                                new_list.push(new_task);
+                            }
+                            tasks.set(new_list);
+                            task_changes.set(task_changes.wrapping_add(1));
+                        }
+                        // the message is shown below the title input
+                        Err(error) => title_error.set(Some(
+                            field_error(&error, "title").unwrap_or_else(|| problem_detail(&error)),
+                        )),
                     }
                 }
             });
@@ -54,45 +263,444 @@ pub fn task_list() -> Html {
     // When a task updates (e.g., marked done)
     let on_task_update = {
         let tasks = tasks.clone();
+        let task_changes = task_changes.clone();
         Callback::from(move |updated: Task| {
-            let new_tasks = (*tasks)
-                .iter()
-                .map(|t| {
-                    if t.id == updated.id {
-                        updated.clone()
-                    } else {
-                        t.clone()
+            tasks.set(replace_task(&tasks, &updated));
+            task_changes.set(task_changes.wrapping_add(1));
+        })
+    };
+
+    let on_reload = {
+        let reload = reload.clone();
+        Callback::from(move |_| reload.set(reload.wrapping_add(1)))
+    };
+
+    // When a task is moved to the trash; its subtasks move up a level
+    let on_task_delete = {
+        let on_reload = on_reload.clone();
+        let trashed_task = trashed_task.clone();
+        Callback::from(move |deleted: Task| {
+            trashed_task.set(Some(deleted));
+            on_reload.emit(());
+        })
+    };
+
+    // Restore the most recently trashed task
+    let on_undo_delete = {
+        let on_reload = on_reload.clone();
+        let trashed_task = trashed_task.clone();
+        Callback::from(move |_| {
+            let on_reload = on_reload.clone();
+            let trashed_task = trashed_task.clone();
+            if let Some(deleted) = (*trashed_task).clone() {
+                spawn_local(async move {
+                    let config = config();
+                    if tasks_api::restore_task(&config, deleted.id, Some(&if_match(&deleted))).await.is_ok() {
+                        trashed_task.set(None);
+                        on_reload.emit(());
                     }
+                });
+            }
+        })
+    };
+
+    let on_toggle_select = {
+        let selected_ids = selected_ids.clone();
+        Callback::from(move |id: i64| {
+            let mut ids = (**selected_ids).clone();
+            if !ids.remove(&id) {
+                ids.insert(id);
+            }
+            selected_ids.set(Rc::new(ids));
+        })
+    };
+
+    let onclick_clear_selection = {
+        let selected_ids = selected_ids.clone();
+        let bulk_error = bulk_error.clone();
+        Callback::from(move |_| {
+            bulk_error.set(None);
+            selected_ids.set(Rc::default());
+        })
+    };
+
+    // Apply one operation to all selected tasks; nothing changes if any of them fails
+    let run_bulk = {
+        let selected_ids = selected_ids.clone();
+        let bulk_error = bulk_error.clone();
+        let on_reload = on_reload.clone();
+        Callback::from(move |kind: BulkTaskOperationKind| {
+            let items = selected_ids
+                .iter()
+                .map(|id| BulkTaskItem {
+                    id: *id,
+                    version: None,
+                    operation: Box::new(BulkTaskOperation {
+                        kind,
+                        ..Default::default()
+                    }),
                 })
                 .collect();
-            tasks.set(new_tasks);
+            let selected_ids = selected_ids.clone();
+            let bulk_error = bulk_error.clone();
+            let on_reload = on_reload.clone();
+            spawn_local(async move {
+                let config = config();
+                let request = BulkTaskRequest {
+                    items: Some(items),
+                    ..Default::default()
+                };
+                match tasks_api::bulk_update_tasks(&config, request).await {
+                    Ok(response) if response.applied => {
+                        bulk_error.set(None);
+                        selected_ids.set(Rc::default());
+                        on_reload.emit(());
+                    }
+                    Ok(response) => {
+                        let reason = response
+                            .results
+                            .into_iter()
+                            .filter(|result| result.status == BulkTaskResultStatus::Failed)
+                            .find_map(|result| result.problem)
+                            .map(|problem| problem.detail)
+                            .unwrap_or_default();
+                        bulk_error.set(Some(format!("Nothing was changed: {reason}")));
+                    }
+                    Err(error) => bulk_error.set(Some(problem_detail(&error))),
+                }
+            });
         })
     };
 
+    let on_tag_click = {
+        let tag_filter = tag_filter.clone();
+        Callback::from(move |tag: Tag| tag_filter.set(Some(tag)))
+    };
+
+    let onclick_clear_tag_filter = {
+        let tag_filter = tag_filter.clone();
+        Callback::from(move |_| tag_filter.set(None))
+    };
+
+    let on_project_select = {
+        let project_filter = project_filter.clone();
+        Callback::from(move |project_id: Option<i64>| project_filter.set(project_id))
+    };
+
+    let onclick_my_tasks = {
+        let my_tasks = my_tasks.clone();
+        Callback::from(move |_| my_tasks.set(!*my_tasks))
+    };
+
+    let on_toast_dismiss = {
+        let trashed_task = trashed_task.clone();
+        Callback::from(move |_| trashed_task.set(None))
+    };
+
     html! {
-        <div class="p-6 max-w-md mx-auto">
-            <h1 class="text-2xl font-bold mb-4 text-gray-800 text-center">{ "Task Tracker" }</h1>
-
-            <div class="flex mb-6">
-                <input
-                    class="border border-gray-300 rounded px-3 py-2 flex-grow focus:outline-none focus:ring-2 focus:ring-blue-500"
-                    placeholder="New task title"
-                    value={(*title).clone()}
-                    oninput={oninput_title}
-                />
-                <button
-                    class="ml-3 px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white rounded transition-colors"
-                    onclick={onclick_add}
-                >
-                    { "Add" }
-                </button>
-            </div>
+        <div class="p-6 max-w-3xl mx-auto flex gap-6">
+            <ProjectSidebar
+                selected={*project_filter}
+                on_select={on_project_select}
+                refresh={reload.wrapping_add(*task_changes)}
+            />
+            <div class="flex-1 max-w-md">
+                <h1 class="text-2xl font-bold mb-4 text-gray-800 text-center">{ "Task Tracker" }</h1>
+
+                <div class={classes!("flex", if title_error.is_some() {"mb-1"} else {"mb-6"})}>
+                    <input
+                        class={classes!(
+                            "border", if title_error.is_some() {"border-red-500"} else {"border-gray-300"},
+                            "rounded", "px-3", "py-2", "flex-grow", "focus:outline-none", "focus:ring-2", "focus:ring-blue-500"
+                        )}
+                        placeholder="New task title"
+                        maxlength="200"
+                        aria-invalid={title_error.is_some().to_string()}
+                        value={(*title).clone()}
+                        oninput={oninput_title}
+                    />
+                    <input
+                        type="date"
+                        class="ml-3 border border-gray-300 rounded px-2 py-2 focus:outline-none focus:ring-2 focus:ring-blue-500"
+                        title="Due date"
+                        value={(*due_date).clone()}
+                        oninput={oninput_due_date}
+                    />
+                    <select
+                        class="ml-3 border border-gray-300 rounded px-2 py-2 focus:outline-none focus:ring-2 focus:ring-blue-500"
+                        title="Repeat"
+                        onchange={onchange_repeat}>
+                        { for REPEAT_OPTIONS.iter().map(|(label, rule)| html! {
+                            <option selected={*rule == *repeat}>{ *label }</option>
+                        })}
+                    </select>
+                    <button
+                        class="ml-3 px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white rounded transition-colors"
+                        onclick={onclick_add}
+                    >
+                        { "Add" }
+                    </button>
+                </div>
+                {
+                    match (*title_error).as_ref() {
+                        Some(message) => html! { <p class="mb-5 text-sm text-red-600">{ message }</p> },
+                        None => html! {},
+                    }
+                }
+
+                <div class="flex justify-end mb-4">
+                    <button
+                        type="button"
+                        class={classes!(
+                            "px-3", "py-1", "rounded-full", "text-sm", "border", "transition-colors",
+                            if *my_tasks {"bg-blue-600 border-blue-600 text-white"} else {"border-gray-300 text-gray-600 hover:bg-gray-100"}
+                        )}
+                        title="Show only tasks assigned to you"
+                        aria-pressed={my_tasks.to_string()}
+                        onclick={onclick_my_tasks}>
+                        { "My tasks" }
+                    </button>
+                </div>
 
-            <ul class="space-y-2">
-                { for (*tasks).iter().map(|task| html! {
-                    <TaskItem task={task.clone()} on_update={on_task_update.clone()} />
-                })}
-            </ul>
+                {
+                    if let Some(tag) = (*tag_filter).as_ref() {
+                        html! {
+                            <div class="flex items-center gap-2 mb-4 text-sm text-gray-600">
+                                { "Showing tasks tagged" }
+                                <TagChip tag={tag.clone()} />
+                                <button
+                                    type="button"
+                                    class="text-gray-400 hover:text-gray-700"
+                                    title="Clear tag filter"
+                                    onclick={onclick_clear_tag_filter}>
+                                    {"✕"}
+                                </button>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+
+                {
+                    if selected_ids.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <div class="mb-4 px-3 py-2 bg-blue-50 border border-blue-200 rounded text-sm">
+                                <div class="flex items-center gap-2">
+                                    <span class="flex-1 text-gray-700">{ format!("{} selected", selected_ids.len()) }</span>
+                                    <button
+                                        type="button"
+                                        class="px-2 py-1 bg-green-500 hover:bg-green-600 rounded-md"
+                                        title="Mark selected tasks as done"
+                                        onclick={run_bulk.reform(|_| BulkTaskOperationKind::MarkDone)}>
+                                        {"✔ Done"}
+                                    </button>
+                                    <button
+                                        type="button"
+                                        class="px-2 py-1 bg-yellow-500 hover:bg-yellow-600 rounded-md"
+                                        title="Reopen selected tasks"
+                                        onclick={run_bulk.reform(|_| BulkTaskOperationKind::Reopen)}>
+                                        {"↩ Reopen"}
+                                    </button>
+                                    <button
+                                        type="button"
+                                        class="px-2 py-1 bg-red-500 hover:bg-red-600 rounded-md"
+                                        title="Move selected tasks to trash"
+                                        onclick={run_bulk.reform(|_| BulkTaskOperationKind::Delete)}>
+                                        {"🗑 Delete"}
+                                    </button>
+                                    <button
+                                        type="button"
+                                        class="text-gray-400 hover:text-gray-700"
+                                        title="Clear selection"
+                                        onclick={onclick_clear_selection}>
+                                        {"✕"}
+                                    </button>
+                                </div>
+                                {
+                                    match (*bulk_error).as_ref() {
+                                        Some(message) => html! { <p class="mt-1 text-red-600">{ message }</p> },
+                                        None => html! {},
+                                    }
+                                }
+                            </div>
+                        }
+                    }
+                }
+
+                <div class="max-h-[70vh] overflow-y-auto" onscroll={onscroll}>
+                    <ul class="space-y-2">
+                        { for (*tasks).iter().enumerate().map(|(index, task)| html! {
+                            <TaskItem
+                                key={task.id}
+                                task={task.clone()}
+                                on_update={on_task_update.clone()}
+                                on_delete={on_task_delete.clone()}
+                                on_tag_click={on_tag_click.clone()}
+                                on_reload={on_reload.clone()}
+                                previous_sibling_id={index.checked_sub(1).map(|previous| tasks[previous].id)}
+                                selected_ids={(*selected_ids).clone()}
+                                on_toggle_select={on_toggle_select.clone()}
+                            />
+                        })}
+                    </ul>
+                    {
+                        if next_cursor.is_some() {
+                            html! {
+                                <button
+                                    type="button"
+                                    class="w-full mt-2 py-1 text-sm text-gray-500 hover:text-gray-800"
+                                    onclick={load_more.reform(|_| ())}>
+                                    { "Load more" }
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </div>
+
+                {
+                    if let Some(deleted) = (*trashed_task).as_ref() {
+                        html! {
+                            <UndoToast
+                                key={deleted.id}
+                                message={format!("\"{}\" moved to trash", deleted.title)}
+                                on_undo={on_undo_delete}
+                                on_dismiss={on_toast_dismiss}
+                            />
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
         </div>
     }
 }
+
+/// Replaces a task anywhere in the tree, keeping the subtasks already loaded for it.
+fn replace_task(tasks: &[Task], updated: &Task) -> Vec<Task> {
+    tasks
+        .iter()
+        .map(|task| {
+            if task.id == updated.id {
+                Task {
+                    children: task.children.clone(),
+                    ..updated.clone()
+                }
+            } else {
+                Task {
+                    children: task.children.as_deref().map(|children| replace_task(children, updated)),
+                    ..task.clone()
+                }
+            }
+        })
+        .collect()
+}
+
+/// Ids of all loaded tasks, subtasks included.
+fn task_ids(tasks: &[Task]) -> Vec<i64> {
+    tasks
+        .iter()
+        .flat_map(|task| std::iter::once(task.id).chain(task_ids(task.children.as_deref().unwrap_or_default())))
+        .collect()
+}
+
+fn find_task(tasks: &[Task], id: i64) -> Option<&Task> {
+    tasks.iter().find_map(|task| {
+        if task.id == id {
+            Some(task)
+        } else {
+            find_task(task.children.as_deref().unwrap_or_default(), id)
+        }
+    })
+}
+
+/// Removes a task, along with its loaded subtasks, anywhere in the tree.
+fn remove_task(tasks: &[Task], id: i64) -> Vec<Task> {
+    tasks
+        .iter()
+        .filter(|task| task.id != id)
+        .map(|task| Task {
+            children: task.children.as_deref().map(|children| remove_task(children, id)),
+            ..task.clone()
+        })
+        .collect()
+}
+
+/// Whether a top-level task belongs in the list with the current tag, project and assignee filters.
+fn matches_filters(task: &Task, (tag_filter_id, project_id, assignee_id): FilterIds) -> bool {
+    project_id.is_none_or(|project_id| task.project_id == Some(project_id))
+        && tag_filter_id.is_none_or(|tag_id| task.tags.iter().any(|tag| tag.id == tag_id))
+        && assignee_id.is_none_or(|user_id| task.assignees.iter().any(|assignee| assignee.id == user_id))
+}
+
+/// Adds a task at the end of its level: among the top-level tasks if it matches the filters,
+/// otherwise below its parent if that is loaded.
+fn insert_task(tasks: &[Task], inserted: Task, filter_ids: FilterIds) -> Vec<Task> {
+    let Some(parent_id) = inserted.parent_id else {
+        let mut tasks = tasks.to_vec();
+        if matches_filters(&inserted, filter_ids) {
+            tasks.push(inserted);
+        }
+        return tasks;
+    };
+    tasks
+        .iter()
+        .map(|task| {
+            let mut children = task.children.clone();
+            if task.id == parent_id {
+                children.get_or_insert_with(Vec::new).push(inserted.clone());
+            } else if let Some(loaded) = &children {
+                children = Some(insert_task(loaded, inserted.clone(), filter_ids));
+            }
+            Task {
+                children,
+                ..task.clone()
+            }
+        })
+        .collect()
+}
+
+/// Applies a created or updated task from the event stream to the loaded tree.
+fn merge_task(tasks: &[Task], changed: Task, filter_ids: FilterIds) -> Vec<Task> {
+    if changed.deleted_at.is_some() {
+        return remove_task(tasks, changed.id);
+    }
+    match find_task(tasks, changed.id) {
+        Some(loaded)
+            if loaded.parent_id == changed.parent_id
+                && (changed.parent_id.is_some() || matches_filters(&changed, filter_ids)) =>
+        {
+            replace_task(tasks, &changed)
+        }
+        // new, moved or no longer matching the filters
+        loaded => {
+            let changed = Task {
+                children: loaded.and_then(|loaded| loaded.children.clone()),
+                ..changed
+            };
+            let remaining = remove_task(tasks, changed.id);
+            insert_task(&remaining, changed, filter_ids)
+        }
+    }
+}
+
+/// The request for one page of the task tree, continuing after `cursor` when given.
+fn task_filter((tag_filter_id, project_id, assignee_id): FilterIds, cursor: Option<String>) -> models::TaskFilterAndSort {
+    models::TaskFilterAndSort {
+        filters: Some(Box::new(models::TaskFilterAndSortFilters {
+            tags_any: tag_filter_id.map(|tag_id| vec![tag_id]),
+            project_id,
+            // the server resolves `me`, the id only serves to match events locally
+            assignee: assignee_id.map(|_| "me".to_string()),
+            ..Default::default()
+        })),
+        tree: Some(true),
+        cursor,
+        ..Default::default()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+use gloo_timers::callback::Timeout;
+use yew::prelude::*;
+
+const TOAST_DURATION_MS: u32 = 5_000;
+
+#[derive(Properties, PartialEq)]
+pub struct UndoToastProps {
+    pub message: String,
+    pub on_undo: Callback<()>,
+    pub on_dismiss: Callback<()>,
+}
+
+#[function_component(UndoToast)]
+pub fn undo_toast(props: &UndoToastProps) -> Html {
+    // Dismiss automatically; remount the toast (e.g. via `key`) to restart the timer
+    {
+        let on_dismiss = props.on_dismiss.clone();
+        use_effect_with((), move |_| {
+            let timeout = Timeout::new(TOAST_DURATION_MS, move || on_dismiss.emit(()));
+            move || drop(timeout)
+        });
+    }
+
+    let onclick_undo = {
+        let on_undo = props.on_undo.clone();
+        Callback::from(move |_| on_undo.emit(()))
+    };
+
+    html! {
+        <div class="fixed bottom-4 left-1/2 -translate-x-1/2 flex items-center gap-4 px-4 py-2 bg-gray-800 text-white rounded shadow-lg">
+            <span>{ &props.message }</span>
+            <button
+                type="button"
+                class="font-semibold text-blue-300 hover:text-blue-200 transition-colors"
+                onclick={onclick_undo}
+            >
+                { "Undo" }
+            </button>
+        </div>
+    }
+}
@@ -1,5 +1,8 @@
+use api_client::apis::Error;
+use api_client::models::{Priority, Problem, Task};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
 use yew::prelude::*;
-use web_sys::HtmlInputElement;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
 
 pub fn bind_input(state: UseStateHandle<String>) -> Callback<InputEvent> {
     let state_for_closure = state.clone();
@@ -10,3 +13,150 @@ pub fn bind_input(state: UseStateHandle<String>) -> Callback<InputEvent> {
         }
     })
 }
+
+pub fn bind_textarea(state: UseStateHandle<String>) -> Callback<InputEvent> {
+    let state_for_closure = state.clone();
+
+    Callback::from(move |e: InputEvent| {
+        if let Some(textarea) = e.target_dyn_into::<HtmlTextAreaElement>() {
+            state_for_closure.set(textarea.value());
+        }
+    })
+}
+
+/// Renders Markdown to HTML, sanitized so user content cannot inject scripts or handlers.
+pub fn render_markdown(markdown: &str) -> Html {
+    let options = pulldown_cmark::Options::ENABLE_STRIKETHROUGH | pulldown_cmark::Options::ENABLE_TABLES;
+    let parser = pulldown_cmark::Parser::new_ext(markdown, options);
+    let mut unsafe_html = String::new();
+    pulldown_cmark::html::push_html(&mut unsafe_html, parser);
+
+    Html::from_html_unchecked(AttrValue::from(ammonia::clean(&unsafe_html)))
+}
+
+/// Formats an RFC 3339 timestamp as the local `YYYY-MM-DD` value of a date input.
+pub fn to_date_input_value(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|date_time| date_time.with_timezone(&Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Turns a date input value into an RFC 3339 timestamp at the end of that local day.
+pub fn from_date_input_value(value: &str) -> Option<String> {
+    let end_of_day = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.and_hms_opt(23, 59, 59)?;
+    let local = Local.from_local_datetime(&end_of_day).earliest()?;
+    Some(local.with_timezone(&Utc).to_rfc3339())
+}
+
+/// `If-Match` value that only lets a change through while the task is still at this version.
+pub fn if_match(task: &Task) -> String {
+    format!("\"{}\"", task.version)
+}
+
+/// Human-readable reason of a failed request, taken from the problem details sent by the server.
+pub fn problem_detail<T>(error: &Error<T>) -> String {
+    match error {
+        Error::ResponseError(response) => serde_json::from_str::<Problem>(&response.content)
+            .map(|problem| problem.detail)
+            .unwrap_or_else(|_| format!("Request failed with status {}", response.status)),
+        _ => "Could not reach the server".to_string(),
+    }
+}
+
+/// Reason the server gave for rejecting the named field, if the request failed because of it.
+pub fn field_error<T>(error: &Error<T>, field: &str) -> Option<String> {
+    let Error::ResponseError(response) = error else {
+        return None;
+    };
+    serde_json::from_str::<Problem>(&response.content)
+        .ok()?
+        .invalid_params?
+        .into_iter()
+        .find(|param| param.name == field)
+        .map(|param| param.reason)
+}
+
+/// Passes a changed task on, or reports why the change was rejected. Changes made elsewhere in
+/// the meantime are reported through `on_stale` so that the task can be reloaded.
+pub fn emit_change<T>(
+    result: Result<Task, Error<T>>,
+    on_update: &Callback<Task>,
+    on_stale: &Callback<()>,
+    on_error: &Callback<String>,
+) -> bool {
+    match result {
+        Ok(task) => {
+            on_update.emit(task);
+            true
+        }
+        Err(Error::ResponseError(response)) if response.status.as_u16() == 412 => {
+            on_stale.emit(());
+            false
+        }
+        Err(error) => {
+            on_error.emit(problem_detail(&error));
+            false
+        }
+    }
+}
+
+pub fn is_overdue(task: &Task) -> bool {
+    !task.done
+        && task
+            .due_at
+            .as_deref()
+            .and_then(|due_at| DateTime::parse_from_rfc3339(due_at).ok())
+            .is_some_and(|due_at| due_at < Utc::now())
+}
+
+pub const PRIORITIES: [Priority; 5] = [
+    Priority::None,
+    Priority::Low,
+    Priority::Medium,
+    Priority::High,
+    Priority::Urgent,
+];
+
+pub fn priority_label(priority: Priority) -> &'static str {
+    match priority {
+        Priority::None => "None",
+        Priority::Low => "Low",
+        Priority::Medium => "Medium",
+        Priority::High => "High",
+        Priority::Urgent => "Urgent",
+    }
+}
+
+pub fn priority_badge_class(priority: Priority) -> &'static str {
+    match priority {
+        Priority::None => "hidden",
+        Priority::Low => "bg-gray-200 text-gray-700",
+        Priority::Medium => "bg-blue-100 text-blue-700",
+        Priority::High => "bg-orange-100 text-orange-700",
+        Priority::Urgent => "bg-red-100 text-red-700",
+    }
+}
+
+/// Repeat presets offered in the UI, as labels and iCalendar RRULEs.
+pub const REPEAT_OPTIONS: [(&str, Option<&str>); 7] = [
+    ("Does not repeat", None),
+    ("Daily", Some("FREQ=DAILY")),
+    ("Every weekday", Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR")),
+    ("Weekly", Some("FREQ=WEEKLY")),
+    ("Monthly", Some("FREQ=MONTHLY")),
+    ("Last day of the month", Some("FREQ=MONTHLY;BYMONTHDAY=-1")),
+    ("Yearly", Some("FREQ=YEARLY")),
+];
+
+pub fn repeat_label(rule: Option<&str>) -> &str {
+    REPEAT_OPTIONS
+        .iter()
+        .find(|(_, option)| *option == rule)
+        .map_or("Custom", |(label, _)| label)
+}
+
+/// IANA time zone of the browser, so that repeating tasks follow the user's wall clock.
+pub fn browser_time_zone() -> Option<String> {
+    let options = js_sys::Intl::DateTimeFormat::new(&js_sys::Array::new(), &js_sys::Object::new()).resolved_options();
+    js_sys::Reflect::get(&options, &"timeZone".into()).ok()?.as_string()
+}
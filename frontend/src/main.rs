@@ -1,13 +1,126 @@
 mod components;
 mod api_config;
 
+use api_client::apis::{Error, auth_api};
+use api_client::models::{AuthSession, OidcCodeExchange, RefreshRequest};
+use chrono::{DateTime, Utc};
+use components::collaboration::CollaborationProvider;
+use components::login_page::LoginPage;
 use components::task_list::TaskList;
+use gloo_timers::callback::Timeout;
+use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
+/// Access tokens are refreshed this long before they expire.
+const REFRESH_MARGIN_MS: i64 = 60_000;
+/// Delay before retrying a refresh that failed because the server could not be reached.
+const REFRESH_RETRY_MS: u32 = 10_000;
+
+/// Explains why single sign-on failed, by the problem code the server passed back.
+fn sso_error_message(code: &str) -> String {
+    match code {
+        "unverified-email" => "Your identity provider has not verified your email address".to_string(),
+        "missing-email" => "Your identity provider did not share your email address".to_string(),
+        _ => "Single sign-on failed, please try again".to_string(),
+    }
+}
+
+fn refresh_delay_ms(session: &AuthSession) -> u32 {
+    let expires_at = DateTime::parse_from_rfc3339(&session.access_token_expires_at)
+        .map(|expires_at| expires_at.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now());
+    let delay = (expires_at - Utc::now()).num_milliseconds() - REFRESH_MARGIN_MS;
+    delay.clamp(0, u32::MAX as i64) as u32
+}
+
 #[function_component(App)]
 fn app() -> Html {
+    let session = use_state(api_config::session);
+    let sso_error = use_state(|| None::<String>);
+
+    {
+        // single sign-on lands here with a one-time code, which is exchanged for a session right away
+        let session = session.clone();
+        let sso_error = sso_error.clone();
+        use_effect_with((), move |_| {
+            match api_config::take_sso_outcome() {
+                Some(api_config::SsoOutcome::Code(code)) => spawn_local(async move {
+                    match auth_api::exchange_oidc_code(&api_config::config(), OidcCodeExchange { code }).await {
+                        Ok(signed_in) => {
+                            api_config::store_session(&signed_in);
+                            session.set(Some(signed_in));
+                        }
+                        Err(_) => sso_error.set(Some(sso_error_message("sso-failed"))),
+                    }
+                }),
+                Some(api_config::SsoOutcome::Error(code)) => sso_error.set(Some(sso_error_message(&code))),
+                None => {}
+            }
+            || ()
+        });
+    }
+
+    {
+        // keeps the access token fresh while signed in; a rejected refresh token signs the user out
+        let session = session.clone();
+        use_effect_with((*session).clone(), move |current| {
+            let timeout = current.as_ref().map(|current| {
+                let refresh_token = current.refresh_token.clone();
+                Timeout::new(refresh_delay_ms(current), move || {
+                    spawn_local(async move {
+                        match auth_api::refresh_session(&api_config::config(), RefreshRequest { refresh_token }).await {
+                            Ok(refreshed) => {
+                                api_config::store_session(&refreshed);
+                                session.set(Some(refreshed));
+                            }
+                            Err(Error::ResponseError(_)) => {
+                                api_config::clear_session();
+                                session.set(None);
+                            }
+                            Err(_) => {
+                                // the server could not be reached; re-arming the effect retries the refresh
+                                Timeout::new(REFRESH_RETRY_MS, move || session.set(api_config::session())).forget();
+                            }
+                        }
+                    });
+                })
+            });
+            move || drop(timeout)
+        });
+    }
+
+    let on_sign_in = {
+        let session = session.clone();
+        Callback::from(move |signed_in: AuthSession| session.set(Some(signed_in)))
+    };
+
+    let onclick_sign_out = {
+        let session = session.clone();
+        Callback::from(move |_| {
+            let session = session.clone();
+            let Some(current) = (*session).clone() else { return };
+            spawn_local(async move {
+                let request = RefreshRequest { refresh_token: current.refresh_token };
+                // the local session ends even if the server cannot be told
+                let _ = auth_api::logout(&api_config::config(), request).await;
+                api_config::clear_session();
+                session.set(None);
+            });
+        })
+    };
+
+    let Some(current) = &*session else {
+        return html! { <LoginPage {on_sign_in} sso_error={(*sso_error).clone()} /> };
+    };
+
     html! {
-        <TaskList />
+        <CollaborationProvider user={current.user.display_name.clone()}>
+            <div class="flex justify-end items-center gap-3 px-4 py-2 text-sm text-gray-600">
+                <span>{ &current.user.display_name }</span>
+                <button class="text-blue-600 hover:underline" onclick={onclick_sign_out}>{ "Sign out" }</button>
+            </div>
+            <TaskList user_id={current.user.id} />
+        </CollaborationProvider>
     }
 }
 